@@ -0,0 +1,239 @@
+//! ABI compatibility between two versions of a shared library, judged from
+//! the exported dynamic symbols, the version definitions and the SONAME.
+//!
+//! A change is breaking when a binary linked against the old library may no
+//! longer load or run correctly with the new one.
+
+use std::collections::BTreeMap;
+
+use super::types::SymbolType;
+use super::{ElfFile, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbiChangeKind {
+    SonameChanged,
+    VersionRemoved,
+    VersionAdded,
+    SymbolRemoved,
+    SymbolAdded,
+    TypeChanged,
+    /// The size of a data object, which copy relocations bake into the
+    /// executables linking it.
+    SizeChanged,
+    BindingChanged,
+}
+
+impl AbiChangeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            AbiChangeKind::SonameChanged => "soname changed",
+            AbiChangeKind::VersionRemoved => "version removed",
+            AbiChangeKind::VersionAdded => "version added",
+            AbiChangeKind::SymbolRemoved => "symbol removed",
+            AbiChangeKind::SymbolAdded => "symbol added",
+            AbiChangeKind::TypeChanged => "type changed",
+            AbiChangeKind::SizeChanged => "size changed",
+            AbiChangeKind::BindingChanged => "binding changed",
+        }
+    }
+
+    pub fn is_breaking(self) -> bool {
+        !matches!(
+            self,
+            AbiChangeKind::VersionAdded
+                | AbiChangeKind::SymbolAdded
+                | AbiChangeKind::BindingChanged
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AbiChange {
+    pub kind: AbiChangeKind,
+    /// The symbol (`name@VERSION` if versioned), version or SONAME.
+    pub subject: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AbiReport {
+    /// Breaking changes first.
+    pub changes: Vec<AbiChange>,
+}
+
+impl AbiReport {
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|change| change.kind.is_breaking())
+    }
+
+    pub fn count(&self, breaking: bool) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.kind.is_breaking() == breaking)
+            .count()
+    }
+}
+
+/// An exported symbol as seen by the dynamic linker.
+#[derive(Debug, Clone)]
+struct AbiSymbol {
+    symbol_type: SymbolType,
+    binding: String,
+    size: u64,
+    /// Whether this is the version unversioned references bind to.
+    default: bool,
+}
+
+impl AbiSymbol {
+    /// Whether both symbols have the same type. Callers bind to a GNU
+    /// indirect function as they would to the function it resolves to.
+    fn same_type(&self, other: &AbiSymbol) -> bool {
+        let is_function = |t| matches!(t, SymbolType::Func | SymbolType::GnuIfunc);
+        self.symbol_type == other.symbol_type
+            || (is_function(self.symbol_type) && is_function(other.symbol_type))
+    }
+}
+
+impl ElfFile {
+    /// Returns the exported symbols keyed by `name@VERSION`, or by name for
+    /// unversioned ones. Hidden and default versions share the key, since
+    /// binaries linked against either keep working as long as the version
+    /// is still there.
+    fn abi_symbols(&self) -> Result<BTreeMap<String, AbiSymbol>> {
+        let mut symbols = BTreeMap::new();
+        for (symbol, version) in self.get_exported_symbols()? {
+            let key = match &version {
+                Some(version) => format!("{}@{}", symbol.name, version.name),
+                None => symbol.name.clone(),
+            };
+            symbols.insert(
+                key,
+                AbiSymbol {
+                    symbol_type: symbol.get_type(),
+                    binding: symbol.get_binding().to_string(),
+                    size: symbol.st_size,
+                    default: version.is_none_or(|version| !version.hidden),
+                },
+            );
+        }
+        Ok(symbols)
+    }
+
+    /// Compares the ABI of `self` (the old library) with `new`.
+    pub fn check_abi(&self, new: &ElfFile) -> Result<AbiReport> {
+        let mut changes = Vec::new();
+
+        let old_soname = self.get_dynamic_info()?.soname;
+        let new_soname = new.get_dynamic_info()?.soname;
+        if old_soname != new_soname {
+            changes.push(AbiChange {
+                kind: AbiChangeKind::SonameChanged,
+                subject: "SONAME".to_string(),
+                old: old_soname,
+                new: new_soname,
+            });
+        }
+
+        let old_versions: Vec<String> = self
+            .get_version_definitions()
+            .into_iter()
+            .filter(|definition| !definition.is_base())
+            .map(|definition| definition.name)
+            .collect();
+        let new_versions: Vec<String> = new
+            .get_version_definitions()
+            .into_iter()
+            .filter(|definition| !definition.is_base())
+            .map(|definition| definition.name)
+            .collect();
+        for version in old_versions
+            .iter()
+            .filter(|version| !new_versions.contains(version))
+        {
+            changes.push(AbiChange {
+                kind: AbiChangeKind::VersionRemoved,
+                subject: version.clone(),
+                old: Some(version.clone()),
+                new: None,
+            });
+        }
+        for version in new_versions
+            .iter()
+            .filter(|version| !old_versions.contains(version))
+        {
+            changes.push(AbiChange {
+                kind: AbiChangeKind::VersionAdded,
+                subject: version.clone(),
+                old: None,
+                new: Some(version.clone()),
+            });
+        }
+
+        let old_symbols = self.abi_symbols()?;
+        let new_symbols = new.abi_symbols()?;
+        // unversioned references bind to the default version of the name
+        let find_new = |key: &str| {
+            new_symbols.get(key).or_else(|| {
+                new_symbols
+                    .iter()
+                    .find(|(other, symbol)| symbol.default && other.split('@').next() == Some(key))
+                    .map(|(_, symbol)| symbol)
+            })
+        };
+        for (key, old) in &old_symbols {
+            let Some(new) = find_new(key) else {
+                changes.push(AbiChange {
+                    kind: AbiChangeKind::SymbolRemoved,
+                    subject: key.clone(),
+                    old: Some(old.symbol_type.to_string()),
+                    new: None,
+                });
+                continue;
+            };
+            let mut change = |kind, old: String, new: String| {
+                changes.push(AbiChange {
+                    kind,
+                    subject: key.clone(),
+                    old: Some(old),
+                    new: Some(new),
+                });
+            };
+            if !old.same_type(new) {
+                change(
+                    AbiChangeKind::TypeChanged,
+                    old.symbol_type.to_string(),
+                    new.symbol_type.to_string(),
+                );
+            } else if matches!(old.symbol_type, SymbolType::Object | SymbolType::Tls)
+                && old.size != new.size
+            {
+                change(
+                    AbiChangeKind::SizeChanged,
+                    old.size.to_string(),
+                    new.size.to_string(),
+                );
+            }
+            if old.binding != new.binding {
+                change(
+                    AbiChangeKind::BindingChanged,
+                    old.binding.clone(),
+                    new.binding.clone(),
+                );
+            }
+        }
+        for (key, new) in &new_symbols {
+            if !old_symbols.contains_key(key) {
+                changes.push(AbiChange {
+                    kind: AbiChangeKind::SymbolAdded,
+                    subject: key.clone(),
+                    old: None,
+                    new: Some(new.symbol_type.to_string()),
+                });
+            }
+        }
+
+        changes.sort_by_key(|change| !change.kind.is_breaking());
+        Ok(AbiReport { changes })
+    }
+}
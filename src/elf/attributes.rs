@@ -0,0 +1,460 @@
+//! Build attribute sections (`.riscv.attributes`, `.ARM.attributes` and
+//! `.gnu.attributes`).
+//!
+//! The layout is shared by all vendors: a format version byte `'A'`, followed
+//! by vendor subsections, each holding file/section/symbol scoped groups of
+//! ULEB128 tagged attributes.
+
+use std::fmt;
+use std::io::{Cursor, Read};
+
+use super::error::ElfError;
+use super::types::{Machine, SectionType};
+use super::{ElfFile, Result, read_u32, read_uleb128};
+
+pub const SHT_ATTRIBUTES: u32 = 0x70000003;
+
+#[derive(Debug, Clone)]
+pub struct Attributes {
+    pub subsections: Vec<AttributeSubsection>,
+}
+
+/// Attributes published by one vendor (`riscv`, `aeabi`, `gnu`, ...).
+#[derive(Debug, Clone)]
+pub struct AttributeSubsection {
+    pub vendor: String,
+    pub groups: Vec<AttributeGroup>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttributeGroup {
+    pub scope: AttributeScope,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeScope {
+    File,
+    Section(Vec<u64>),
+    Symbol(Vec<u64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub tag: u64,
+    pub value: AttributeValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    Int(u64),
+    Str(String),
+    IntStr(u64, String),
+}
+
+impl fmt::Display for AttributeScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeScope::File => f.write_str("File Attributes"),
+            AttributeScope::Section(indices) => write!(f, "Section Attributes: {:?}", indices),
+            AttributeScope::Symbol(indices) => write!(f, "Symbol Attributes: {:?}", indices),
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeValue::Int(v) => write!(f, "{}", v),
+            AttributeValue::Str(s) => write!(f, "\"{}\"", s),
+            AttributeValue::IntStr(v, s) => write!(f, "{}, \"{}\"", v, s),
+        }
+    }
+}
+
+impl Attributes {
+    pub fn parse(data: &[u8], big_endian: bool) -> Result<Self> {
+        if data.first() != Some(&b'A') {
+            return Err(ElfError::ParseError(
+                "unsupported attribute section format.".to_string(),
+            ));
+        }
+
+        let mut subsections = Vec::new();
+        let mut offset = 1;
+        while offset < data.len() {
+            let mut reader = Cursor::new(&data[offset..]);
+            let length = read_u32(&mut reader, big_endian)? as usize;
+            if length < 4 || offset + length > data.len() {
+                return Err(ElfError::ParseError(
+                    "attribute subsection exceeds section range.".to_string(),
+                ));
+            }
+
+            let body = &data[offset + 4..offset + length];
+            let name_len = body.iter().position(|&c| c == 0).ok_or_else(|| {
+                ElfError::ParseError("unterminated attribute vendor name.".to_string())
+            })?;
+            let vendor = String::from_utf8_lossy(&body[..name_len]).into_owned();
+            let groups = parse_groups(&vendor, &body[name_len + 1..], big_endian)?;
+            subsections.push(AttributeSubsection { vendor, groups });
+
+            offset += length;
+        }
+
+        Ok(Self { subsections })
+    }
+}
+
+fn parse_groups(vendor: &str, data: &[u8], big_endian: bool) -> Result<Vec<AttributeGroup>> {
+    let mut groups = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let mut reader = Cursor::new(&data[offset..]);
+        let tag = read_uleb128(&mut reader)?;
+        let size = read_u32(&mut reader, big_endian)? as usize;
+        if size == 0 || offset + size > data.len() {
+            return Err(ElfError::ParseError(
+                "attribute group exceeds subsection range.".to_string(),
+            ));
+        }
+
+        let end = size as u64;
+        let scope = match tag {
+            1 => AttributeScope::File,
+            2 | 3 => {
+                let mut indices = Vec::new();
+                loop {
+                    let index = read_uleb128(&mut reader)?;
+                    if index == 0 {
+                        break;
+                    }
+                    indices.push(index);
+                }
+                if tag == 2 {
+                    AttributeScope::Section(indices)
+                } else {
+                    AttributeScope::Symbol(indices)
+                }
+            }
+            _ => {
+                return Err(ElfError::ParseError(format!(
+                    "unknown attribute scope tag {}.",
+                    tag
+                )));
+            }
+        };
+
+        let mut attributes = Vec::new();
+        while reader.position() < end {
+            let tag = read_uleb128(&mut reader)?;
+            let value = match value_kind(vendor, tag) {
+                ValueKind::Int => AttributeValue::Int(read_uleb128(&mut reader)?),
+                ValueKind::Str => AttributeValue::Str(read_ntbs(&mut reader)?),
+                ValueKind::IntStr => {
+                    let v = read_uleb128(&mut reader)?;
+                    AttributeValue::IntStr(v, read_ntbs(&mut reader)?)
+                }
+            };
+            attributes.push(Attribute { tag, value });
+        }
+
+        groups.push(AttributeGroup { scope, attributes });
+        offset += size;
+    }
+    Ok(groups)
+}
+
+fn read_ntbs<R: Read>(reader: &mut R) -> Result<String> {
+    let mut bytes = Vec::new();
+    loop {
+        let mut c = [0u8; 1];
+        reader.read_exact(&mut c)?;
+        if c[0] == 0 {
+            break;
+        }
+        bytes.push(c[0]);
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+enum ValueKind {
+    Int,
+    Str,
+    IntStr,
+}
+
+/// All vendors follow the generic rule: even tags carry a ULEB128 value and
+/// odd tags a NUL-terminated string, apart from a few aeabi exceptions.
+fn value_kind(vendor: &str, tag: u64) -> ValueKind {
+    match (vendor, tag) {
+        ("aeabi", 4 | 5) => ValueKind::Str,
+        ("aeabi", 32) => ValueKind::IntStr,
+        ("aeabi", 1..32) => ValueKind::Int,
+        _ if tag.is_multiple_of(2) => ValueKind::Int,
+        _ => ValueKind::Str,
+    }
+}
+
+impl Attribute {
+    pub fn tag_name(&self, vendor: &str) -> String {
+        let name = match vendor {
+            "riscv" => riscv_tag_name(self.tag),
+            "aeabi" => arm_tag_name(self.tag),
+            "gnu" => gnu_tag_name(self.tag),
+            _ => None,
+        };
+        match name {
+            Some(name) => name.to_string(),
+            None => format!("Tag_unknown_{}", self.tag),
+        }
+    }
+
+    /// Returns the attribute value, decoded into a readable form where the
+    /// tag is known.
+    pub fn describe(&self, vendor: &str) -> String {
+        let v = match self.value {
+            AttributeValue::Int(v) => v,
+            _ => return self.value.to_string(),
+        };
+
+        let desc = match vendor {
+            "riscv" => describe_riscv(self.tag, v),
+            "aeabi" => describe_arm(self.tag, v),
+            _ => None,
+        };
+        desc.unwrap_or_else(|| v.to_string())
+    }
+}
+
+fn riscv_tag_name(tag: u64) -> Option<&'static str> {
+    let name = match tag {
+        4 => "Tag_RISCV_stack_align",
+        5 => "Tag_RISCV_arch",
+        6 => "Tag_RISCV_unaligned_access",
+        8 => "Tag_RISCV_priv_spec",
+        10 => "Tag_RISCV_priv_spec_minor",
+        12 => "Tag_RISCV_priv_spec_revision",
+        14 => "Tag_RISCV_atomic_abi",
+        16 => "Tag_RISCV_x3_reg_usage",
+        _ => return None,
+    };
+    Some(name)
+}
+
+fn describe_riscv(tag: u64, v: u64) -> Option<String> {
+    let desc = match (tag, v) {
+        (4, _) => return Some(format!("{}-bytes", v)),
+        (6, 0) => "No unaligned access",
+        (6, 1) => "Unaligned access",
+        (14, 0) => "UNKNOWN",
+        (14, 1) => "A6C",
+        (14, 2) => "A6S",
+        (14, 3) => "A7",
+        (16, 0) => "UNKNOWN",
+        (16, 1) => "GP",
+        (16, 2) => "SCS",
+        (16, 3) => "TMP",
+        _ => return None,
+    };
+    Some(desc.to_string())
+}
+
+fn gnu_tag_name(tag: u64) -> Option<&'static str> {
+    let name = match tag {
+        4 => "Tag_GNU_Power_ABI_FP",
+        8 => "Tag_GNU_Power_ABI_Vector",
+        12 => "Tag_GNU_Power_ABI_Struct_Return",
+        _ => return None,
+    };
+    Some(name)
+}
+
+fn arm_tag_name(tag: u64) -> Option<&'static str> {
+    let name = match tag {
+        4 => "Tag_CPU_raw_name",
+        5 => "Tag_CPU_name",
+        6 => "Tag_CPU_arch",
+        7 => "Tag_CPU_arch_profile",
+        8 => "Tag_ARM_ISA_use",
+        9 => "Tag_THUMB_ISA_use",
+        10 => "Tag_FP_arch",
+        11 => "Tag_WMMX_arch",
+        12 => "Tag_Advanced_SIMD_arch",
+        13 => "Tag_PCS_config",
+        14 => "Tag_ABI_PCS_R9_use",
+        15 => "Tag_ABI_PCS_RW_data",
+        16 => "Tag_ABI_PCS_RO_data",
+        17 => "Tag_ABI_PCS_GOT_use",
+        18 => "Tag_ABI_PCS_wchar_t",
+        19 => "Tag_ABI_FP_rounding",
+        20 => "Tag_ABI_FP_denormal",
+        21 => "Tag_ABI_FP_exceptions",
+        22 => "Tag_ABI_FP_user_exceptions",
+        23 => "Tag_ABI_FP_number_model",
+        24 => "Tag_ABI_align_needed",
+        25 => "Tag_ABI_align_preserved",
+        26 => "Tag_ABI_enum_size",
+        27 => "Tag_ABI_HardFP_use",
+        28 => "Tag_ABI_VFP_args",
+        29 => "Tag_ABI_WMMX_args",
+        30 => "Tag_ABI_optimization_goals",
+        31 => "Tag_ABI_FP_optimization_goals",
+        32 => "Tag_compatibility",
+        34 => "Tag_CPU_unaligned_access",
+        36 => "Tag_FP_HP_extension",
+        38 => "Tag_ABI_FP_16bit_format",
+        42 => "Tag_MPextension_use",
+        44 => "Tag_DIV_use",
+        46 => "Tag_DSP_extension",
+        48 => "Tag_MVE_arch",
+        50 => "Tag_PAC_extension",
+        52 => "Tag_BTI_extension",
+        64 => "Tag_nodefaults",
+        65 => "Tag_also_compatible_with",
+        66 => "Tag_T2EE_use",
+        67 => "Tag_conformance",
+        68 => "Tag_Virtualization_use",
+        74 => "Tag_BTI_use",
+        76 => "Tag_PACRET_use",
+        _ => return None,
+    };
+    Some(name)
+}
+
+fn describe_arm(tag: u64, v: u64) -> Option<String> {
+    let table: &[&str] = match tag {
+        6 => &[
+            "Pre-v4",
+            "v4",
+            "v4T",
+            "v5T",
+            "v5TE",
+            "v5TEJ",
+            "v6",
+            "v6KZ",
+            "v6T2",
+            "v6K",
+            "v7",
+            "v6-M",
+            "v6S-M",
+            "v7E-M",
+            "v8",
+            "v8-R",
+            "v8-M.baseline",
+            "v8-M.mainline",
+            "v8.1-A",
+            "v8.2-A",
+            "v8.3-A",
+            "v8.1-M.mainline",
+            "v9",
+        ],
+        7 => {
+            let profile = match v {
+                0 => "None",
+                0x41 => "Application",
+                0x52 => "Realtime",
+                0x4D => "Microcontroller",
+                0x53 => "Application or Realtime",
+                _ => return None,
+            };
+            return Some(profile.to_string());
+        }
+        8 => &["No", "Yes"],
+        9 => &["No", "Thumb-1", "Thumb-2", "Yes"],
+        10 => &[
+            "No",
+            "VFPv1",
+            "VFPv2",
+            "VFPv3",
+            "VFPv3-D16",
+            "VFPv4",
+            "VFPv4-D16",
+            "FP for ARMv8",
+            "FPv5/FP-D16 for ARMv8",
+        ],
+        11 => &["No", "WMMXv1", "WMMXv2"],
+        12 => &[
+            "No",
+            "NEONv1",
+            "NEONv1 with Fused-MAC",
+            "NEON for ARMv8",
+            "NEON for ARMv8.1",
+        ],
+        14 => &["V6", "SB", "TLS", "Unused"],
+        15 => &["Absolute", "PC-relative", "SB-relative", "None"],
+        16 => &["Absolute", "PC-relative", "None"],
+        17 => &["None", "direct", "GOT-indirect"],
+        18 => {
+            let wchar = match v {
+                0 => "None",
+                2 => "2",
+                4 => "4",
+                _ => return None,
+            };
+            return Some(wchar.to_string());
+        }
+        19 => &["Unused", "Needed"],
+        20 => &["Unused", "Needed", "Sign only"],
+        21 | 22 => &["Unused", "Needed"],
+        23 => &["Unused", "Finite", "RTABI", "IEEE 754"],
+        24 => &["None", "8-byte", "4-byte"],
+        25 => &["None", "8-byte, except leaf SP", "8-byte"],
+        26 => &["Unused", "small", "int", "forced to int"],
+        27 => &["As Tag_FP_arch", "SP only", "Reserved", "Deprecated"],
+        28 => &["AAPCS", "VFP registers", "custom", "compatible"],
+        29 => &["AAPCS", "WMMX registers", "custom"],
+        34 => &["None", "v6"],
+        36 => &["Not Allowed", "Allowed"],
+        38 => &["None", "IEEE 754", "Alternative Format"],
+        42 => &["Not Allowed", "Allowed"],
+        44 => &[
+            "Allowed in Thumb-ISA, v7-R or v7-M",
+            "Not allowed",
+            "Allowed in v7-A with integer division extension",
+        ],
+        46 => &["Follow architecture", "Allowed"],
+        48 => &["No MVE", "MVE Integer only", "MVE Integer and FP"],
+        50 | 52 => &["No", "Permitted in NOP space", "Permitted"],
+        66 => &["Not Allowed", "Allowed"],
+        68 => &[
+            "Not Allowed",
+            "TrustZone",
+            "Virtualization Extensions",
+            "TrustZone and Virtualization Extensions",
+        ],
+        74 | 76 => &["Not used", "Used"],
+        _ => return None,
+    };
+    table.get(v as usize).map(|s| s.to_string())
+}
+
+impl ElfFile {
+    /// Returns the indices of all build attribute sections, found by type
+    /// or, for files written by older tools, by name.
+    pub fn get_attribute_sections(&self) -> Vec<usize> {
+        let machine = self.get_machine();
+        (0..self.section_headers.len())
+            .filter(|&i| {
+                let sh_type = self.section_headers[i].sh_type();
+                let by_type = match machine {
+                    Machine::RiscV | Machine::Arm | Machine::Msp430 => sh_type == SHT_ATTRIBUTES,
+                    _ => false,
+                };
+                by_type
+                    || SectionType::from(sh_type) == SectionType::GnuAttributes
+                    || matches!(
+                        self.get_section_name(i).as_deref(),
+                        Some(".riscv.attributes" | ".ARM.attributes" | ".gnu.attributes")
+                    )
+            })
+            .collect()
+    }
+
+    pub fn get_attributes(&self, index: usize) -> Result<Attributes> {
+        let data = self.get_section_data(index).ok_or_else(|| {
+            ElfError::ParseError("attribute section exceeds file range.".to_string())
+        })?;
+        Attributes::parse(data, self.is_big_endian())
+    }
+}
@@ -0,0 +1,216 @@
+//! Size breakdown of a binary by symbol, section, source file and
+//! namespace, in the spirit of bloaty and cargo-bloat.
+
+use std::collections::{HashMap, HashSet};
+
+use super::demangle;
+use super::error::ElfError;
+use super::header::SHF_ALLOC;
+use super::types::{SymbolBinding, SymbolType};
+use super::{ElfFile, Result};
+
+/// A sized symbol. Aliases (symbols at the same address with the same size)
+/// are counted once.
+#[derive(Debug, Clone)]
+pub struct BloatSymbol {
+    pub name: String,
+    /// Namespace path for mangled names.
+    pub demangled: Option<String>,
+    pub section: usize,
+    pub address: u64,
+    pub size: u64,
+    /// Compilation unit from the debug info, or the preceding `STT_FILE`
+    /// symbol for local symbols.
+    pub file: Option<String>,
+    pub namespace: Option<String>,
+}
+
+impl BloatSymbol {
+    pub fn display_name(&self) -> &str {
+        self.demangled.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BloatSection {
+    pub index: usize,
+    pub size: u64,
+    /// Bytes covered by symbols.
+    pub symbol_size: u64,
+    pub symbols: usize,
+}
+
+/// Symbols summed by file or namespace.
+#[derive(Debug, Clone)]
+pub struct BloatGroup {
+    pub name: String,
+    pub size: u64,
+    pub symbols: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct BloatReport {
+    /// Size of `.text`, the base of the percentages.
+    pub text_size: Option<u64>,
+    /// Sorted by size, largest first.
+    pub symbols: Vec<BloatSymbol>,
+    pub sections: Vec<BloatSection>,
+    pub files: Vec<BloatGroup>,
+    pub namespaces: Vec<BloatGroup>,
+}
+
+impl BloatReport {
+    /// Returns `size` as a percentage of `.text`.
+    pub fn percent_of_text(&self, size: u64) -> Option<f64> {
+        self.text_size
+            .filter(|&text| text > 0)
+            .map(|text| size as f64 * 100.0 / text as f64)
+    }
+}
+
+fn group<'a>(
+    symbols: impl Iterator<Item = (Option<&'a str>, u64)>,
+    unknown: &str,
+) -> Vec<BloatGroup> {
+    let mut groups: HashMap<&str, BloatGroup> = HashMap::new();
+    for (name, size) in symbols {
+        let name = name.unwrap_or(unknown);
+        let group = groups.entry(name).or_insert_with(|| BloatGroup {
+            name: name.to_string(),
+            size: 0,
+            symbols: 0,
+        });
+        group.size += size;
+        group.symbols += 1;
+    }
+    let mut groups: Vec<BloatGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    groups
+}
+
+impl ElfFile {
+    /// Returns the address ranges of the compilation units in the debug
+    /// info, sorted by start address.
+    fn unit_ranges(&self) -> Vec<(u64, u64, String)> {
+        let sections = self.get_dwarf_sections();
+        let Ok(units) = sections.units() else {
+            return Vec::new();
+        };
+        let mut ranges = Vec::new();
+        for unit in &units {
+            let Some(name) = &unit.name else {
+                continue;
+            };
+            // rustc names units `src/lib.rs/@/<codegen unit>`
+            let name = name.split("/@/").next().unwrap_or(name);
+            if let Some(Ok(root)) = unit.entries(&sections).next() {
+                for (start, end) in sections.die_ranges(unit, &root).unwrap_or_default() {
+                    if start < end {
+                        ranges.push((start, end, name.to_string()));
+                    }
+                }
+            }
+        }
+        ranges.sort_by_key(|&(start, _, _)| start);
+        ranges
+    }
+
+    /// Breaks the allocated size down by the symbols of `.symtab`, or of
+    /// `.dynsym` if the file is stripped.
+    pub fn bloat_report(&self) -> Result<BloatReport> {
+        let mut all = self.get_symbols()?;
+        if all.is_empty() {
+            all = self.get_dynamic_symbols()?;
+        }
+        if all.is_empty() {
+            return Err(ElfError::ParseError("the file has no symbols.".to_string()));
+        }
+        let units = self.unit_ranges();
+        let find_unit = |address: u64| {
+            let index = units.partition_point(|&(start, _, _)| start <= address);
+            units[..index]
+                .iter()
+                .rev()
+                .find(|&&(_, end, _)| address < end)
+                .map(|(_, _, name)| name.clone())
+        };
+
+        let machine = self.get_machine();
+        let mut seen = HashSet::new();
+        let mut file = None;
+        let mut symbols = Vec::new();
+        for symbol in &all {
+            let symbol_type = symbol.get_type();
+            if symbol_type == SymbolType::File {
+                file = Some(symbol.name.clone()).filter(|name| !name.is_empty());
+                continue;
+            }
+            if symbol.st_size == 0 || symbol_type == SymbolType::Section {
+                continue;
+            }
+            let Some(section) = symbol.section_index() else {
+                continue;
+            };
+            if self
+                .section_headers
+                .get(section)
+                .is_none_or(|sh| sh.sh_flags() & SHF_ALLOC == 0)
+            {
+                continue;
+            }
+            let address = symbol.address(machine);
+            if !seen.insert((section, address, symbol.st_size)) {
+                continue;
+            }
+            let local = symbol.get_binding() == SymbolBinding::Local;
+            symbols.push(BloatSymbol {
+                name: symbol.name.clone(),
+                demangled: demangle::demangle(&symbol.name),
+                section,
+                address,
+                size: symbol.st_size,
+                file: find_unit(address).or_else(|| if local { file.clone() } else { None }),
+                namespace: demangle::namespace(&symbol.name),
+            });
+        }
+        symbols.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+        let mut sections: Vec<BloatSection> = Vec::new();
+        for (index, sh) in self.section_headers.iter().enumerate() {
+            if sh.sh_flags() & SHF_ALLOC == 0 || sh.sh_size() == 0 {
+                continue;
+            }
+            let members = symbols.iter().filter(|symbol| symbol.section == index);
+            sections.push(BloatSection {
+                index,
+                size: sh.sh_size(),
+                symbol_size: members.clone().map(|symbol| symbol.size).sum(),
+                symbols: members.count(),
+            });
+        }
+        sections.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.index.cmp(&b.index)));
+
+        let files = group(
+            symbols
+                .iter()
+                .map(|symbol| (symbol.file.as_deref(), symbol.size)),
+            "[unknown]",
+        );
+        let namespaces = group(
+            symbols
+                .iter()
+                .map(|symbol| (symbol.namespace.as_deref(), symbol.size)),
+            "[global]",
+        );
+        let text_size = self
+            .find_section(".text")
+            .map(|index| self.section_headers[index].sh_size());
+        Ok(BloatReport {
+            text_size,
+            symbols,
+            sections,
+            files,
+            namespaces,
+        })
+    }
+}
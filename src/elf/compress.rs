@@ -0,0 +1,165 @@
+//! Compressed sections: `SHF_COMPRESSED` (`Elf32_Chdr`/`Elf64_Chdr`) and
+//! the legacy GNU `.zdebug_*` format.
+
+use std::fmt;
+use std::io::{Cursor, Read};
+
+use super::error::ElfError;
+use super::header::SectionHeader;
+use super::types::SectionFlags;
+use super::{ElfFile, Result, read_u32, read_u64};
+
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+/// Upper bound on a decompressed section, guarding against corrupt headers.
+const MAX_DECOMPRESSED_SIZE: u64 = 1 << 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionType {
+    Zlib,
+    Zstd,
+    /// `.zdebug_*` sections: `"ZLIB"`, a big-endian 64-bit size, then a
+    /// zlib stream.
+    GnuZlib,
+    Unknown(u32),
+}
+
+impl fmt::Display for CompressionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompressionType::Zlib => write!(f, "ZLIB"),
+            CompressionType::Zstd => write!(f, "ZSTD"),
+            CompressionType::GnuZlib => write!(f, "GNU ZLIB"),
+            CompressionType::Unknown(t) => write!(f, "unknown ({})", t),
+        }
+    }
+}
+
+/// Describes how a section is compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compression {
+    pub ch_type: CompressionType,
+    /// Size of the section contents after decompression.
+    pub uncompressed_size: u64,
+    /// Alignment of the decompressed contents.
+    pub uncompressed_align: u64,
+    /// Size of the compression header preceding the payload.
+    pub header_size: u64,
+}
+
+impl Compression {
+    /// Reads the compression header of a section, returning `None` if it is
+    /// not compressed. `name` is used to recognise `.zdebug_*` sections.
+    pub fn from_section(
+        sh: &SectionHeader,
+        name: &str,
+        data: &[u8],
+        is_64: bool,
+        big_endian: bool,
+    ) -> Result<Option<Self>> {
+        if sh.get_flags().contains(SectionFlags::COMPRESSED) {
+            let mut reader = Cursor::new(data);
+            let ch_type = read_u32(&mut reader, big_endian)?;
+            let (uncompressed_size, uncompressed_align) = if is_64 {
+                let _ch_reserved = read_u32(&mut reader, big_endian)?;
+                (
+                    read_u64(&mut reader, big_endian)?,
+                    read_u64(&mut reader, big_endian)?,
+                )
+            } else {
+                (
+                    read_u32(&mut reader, big_endian)? as u64,
+                    read_u32(&mut reader, big_endian)? as u64,
+                )
+            };
+            let ch_type = match ch_type {
+                ELFCOMPRESS_ZLIB => CompressionType::Zlib,
+                ELFCOMPRESS_ZSTD => CompressionType::Zstd,
+                t => CompressionType::Unknown(t),
+            };
+            return Ok(Some(Self {
+                ch_type,
+                uncompressed_size,
+                uncompressed_align,
+                header_size: reader.position(),
+            }));
+        }
+
+        if name.starts_with(".zdebug") && data.len() >= 12 && &data[..4] == b"ZLIB" {
+            let size = u64::from_be_bytes(data[4..12].try_into().unwrap_or_default());
+            return Ok(Some(Self {
+                ch_type: CompressionType::GnuZlib,
+                uncompressed_size: size,
+                uncompressed_align: 1,
+                header_size: 12,
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Decompresses the raw section contents `data`, header included.
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if self.uncompressed_size > MAX_DECOMPRESSED_SIZE {
+            return Err(ElfError::ParseError(format!(
+                "compressed section claims an uncompressed size of {} bytes.",
+                self.uncompressed_size
+            )));
+        }
+        let payload = data.get(self.header_size as usize..).unwrap_or(&[]);
+        let size = self.uncompressed_size as usize;
+
+        let output = match self.ch_type {
+            CompressionType::Zlib | CompressionType::GnuZlib => {
+                miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(payload, size).map_err(
+                    |e| ElfError::ParseError(format!("zlib decompression failed: {:?}.", e.status)),
+                )?
+            }
+            CompressionType::Zstd => {
+                let mut output = Vec::new();
+                let mut input = payload;
+                // a payload may hold several concatenated frames; reading one
+                // byte past the claimed size is enough to detect a lie
+                while !input.is_empty() && output.len() < size {
+                    let decoder =
+                        ruzstd::decoding::StreamingDecoder::new(&mut input).map_err(|e| {
+                            ElfError::ParseError(format!("zstd decompression failed: {}.", e))
+                        })?;
+                    decoder
+                        .take((size - output.len() + 1) as u64)
+                        .read_to_end(&mut output)?;
+                }
+                output
+            }
+            CompressionType::Unknown(t) => {
+                return Err(ElfError::ParseError(format!(
+                    "unsupported section compression type {}.",
+                    t
+                )));
+            }
+        };
+
+        if output.len() != size {
+            return Err(ElfError::ParseError(format!(
+                "decompressed section is {} bytes, expected {}.",
+                output.len(),
+                size
+            )));
+        }
+        Ok(output)
+    }
+}
+
+impl ElfFile {
+    /// Returns the compression header of section `index`, if it is
+    /// compressed.
+    pub fn get_section_compression(&self, index: usize) -> Option<Compression> {
+        let sh = self.section_headers.get(index)?;
+        let data = self.get_raw_section_data(index)?;
+        let name = self.get_section_name(index).unwrap_or_default();
+        Compression::from_section(sh, &name, data, self.is_64(), self.is_big_endian())
+            .ok()
+            .flatten()
+    }
+}
@@ -0,0 +1,250 @@
+//! Namespace paths of mangled symbol names: Itanium C++ nested names, legacy
+//! Rust (`_ZN...17h<hash>E`) and Rust v0 (`_R`) paths. This is not a full
+//! demangler: parameter types and hashes are dropped, and names with
+//! template arguments, generics or back-references are left alone.
+
+/// Parses `<length><identifier>` and returns the identifier and the rest.
+fn source_name(input: &str) -> Option<(&str, &str)> {
+    let digits = input.bytes().take_while(u8::is_ascii_digit).count();
+    let len: usize = input[..digits].parse().ok()?;
+    let rest = &input[digits..];
+    (len > 0 && rest.len() >= len && rest.is_char_boundary(len))
+        .then(|| (&rest[..len], &rest[len..]))
+}
+
+/// Decodes the `$...$` escapes and `..` separators of legacy Rust names.
+fn unescape_rust(ident: &str) -> Option<String> {
+    let ident = ident
+        .strip_prefix('_')
+        .filter(|rest| rest.starts_with('$'))
+        .unwrap_or(ident);
+    let mut out = String::new();
+    let mut rest = ident;
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix("..") {
+            out.push_str("::");
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix('$') {
+            let end = tail.find('$')?;
+            let escape = &tail[..end];
+            out.push(match escape {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                _ => char::from_u32(u32::from_str_radix(escape.strip_prefix('u')?, 16).ok()?)?,
+            });
+            rest = &tail[end + 1..];
+        } else {
+            let c = rest.chars().next()?;
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Some(out)
+}
+
+/// Components of an Itanium `_Z` name's nested or unscoped name, and
+/// whether they are complete (no template arguments or other constructs
+/// follow that would change their meaning).
+fn itanium_components(input: &str) -> Option<(Vec<String>, bool)> {
+    // local entities (`_ZZ<encoding>E<entity>`) belong to their function
+    let rest = input.trim_start_matches('Z');
+    let (components, complete) = itanium_nested_components(rest)?;
+    Some((components, complete && rest.len() == input.len()))
+}
+
+/// Components of an Itanium nested or unscoped name, as for
+/// [`itanium_components`].
+fn itanium_nested_components(mut input: &str) -> Option<(Vec<String>, bool)> {
+    let mut components = Vec::new();
+    let Some(nested) = input.strip_prefix('N') else {
+        if let Some(rest) = input.strip_prefix("St") {
+            components.push("std".to_string());
+            input = rest;
+        }
+        // `L` marks internal linkage
+        let (name, _) = source_name(input.strip_prefix('L').unwrap_or(input))?;
+        components.push(name.to_string());
+        return Some((components, true));
+    };
+    input = nested.trim_start_matches(['r', 'V', 'K', 'R', 'O']);
+    if let Some(rest) = input.strip_prefix("St") {
+        components.push("std".to_string());
+        input = rest;
+    } else if ["Sa", "Sb", "Ss", "Si", "So", "Sd"]
+        .iter()
+        .any(|abbreviation| input.starts_with(abbreviation))
+    {
+        // std::allocator, std::basic_string, std::string and the streams
+        return Some((vec!["std".to_string()], false));
+    }
+    loop {
+        if input.starts_with('E') {
+            return Some((components, true));
+        }
+        let Some((name, rest)) = source_name(input.strip_prefix('L').unwrap_or(input)) else {
+            return (!components.is_empty()).then_some((components, false));
+        };
+        components.push(if name == "_GLOBAL__N_1" {
+            "(anonymous namespace)".to_string()
+        } else {
+            name.to_string()
+        });
+        input = rest;
+    }
+}
+
+/// Parses a Rust v0 `[disambiguator] <identifier>`.
+fn rust_v0_identifier(input: &str) -> Option<(String, &str)> {
+    let input = match input.strip_prefix('s') {
+        Some(rest) => &rest[rest.find('_')? + 1..],
+        None => input,
+    };
+    // punycode
+    if input.starts_with('u') {
+        return None;
+    }
+    let digits = input.bytes().take_while(u8::is_ascii_digit).count();
+    let len: usize = input[..digits].parse().ok()?;
+    let rest = &input[digits..];
+    let rest = rest.strip_prefix('_').unwrap_or(rest);
+    (rest.len() >= len && rest.is_char_boundary(len))
+        .then(|| (rest[..len].to_string(), &rest[len..]))
+}
+
+/// Parses a Rust v0 path made only of crate roots and nested paths, and
+/// returns its identifiers from the crate outwards.
+fn rust_v0_path(mut input: &str) -> Option<(Vec<String>, &str)> {
+    // each nested path wraps the next one, down to the crate root
+    let mut namespaces = Vec::new();
+    while let Some(rest) = input.strip_prefix('N') {
+        let namespace = rest.chars().next()?;
+        namespaces.push(namespace);
+        input = &rest[namespace.len_utf8()..];
+    }
+    let (name, mut rest) = rust_v0_identifier(input.strip_prefix('C')?)?;
+    let mut components = vec![name];
+    for namespace in namespaces.into_iter().rev() {
+        let (name, next) = rust_v0_identifier(rest)?;
+        // closures and shims have empty names
+        components.push(if name.is_empty() {
+            format!("{{{}}}", namespace)
+        } else {
+            name
+        });
+        rest = next;
+    }
+    Some((components, rest))
+}
+
+/// Returns the namespace path of a mangled name, outermost first, and
+/// whether it fully names the symbol.
+fn components(name: &str) -> Option<(Vec<String>, bool)> {
+    if let Some(rest) = name.strip_prefix("_R") {
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        return rust_v0_path(rest).map(|(components, _)| (components, true));
+    }
+    let rest = name
+        .strip_prefix("_Z")
+        .or_else(|| name.strip_prefix("__Z"))?;
+    let (mut components, complete) = itanium_components(rest)?;
+    // legacy Rust: the last component is the `h<16 hex digits>` hash
+    let is_hash = |s: &str| {
+        s.len() == 17 && s.starts_with('h') && s[1..].bytes().all(|b| b.is_ascii_hexdigit())
+    };
+    if components.len() > 1 && components.last().is_some_and(|last| is_hash(last)) {
+        components.pop();
+        let components = components
+            .iter()
+            .map(|component| unescape_rust(component))
+            .collect::<Option<Vec<_>>>()?;
+        return Some((components, complete));
+    }
+    Some((components, complete))
+}
+
+/// Returns the `::`-joined path of a mangled name, or `None` if the name is
+/// not mangled or cannot be shown faithfully without full demangling.
+pub fn demangle(name: &str) -> Option<String> {
+    match components(name)? {
+        (components, true) => Some(components.join("::")),
+        _ => None,
+    }
+}
+
+/// Returns a mangled name without the `17h<16 hex digits>` hash of legacy
+/// Rust names, which changes from build to build. Other names are returned
+/// unchanged.
+pub fn strip_rust_hash(name: &str) -> &str {
+    const HASH: usize = "17h0123456789abcdefE".len();
+    let is_legacy = name.starts_with("_ZN") || name.starts_with("__ZN");
+    match name
+        .len()
+        .checked_sub(HASH)
+        .and_then(|at| Some((at, name.get(at..)?)))
+    {
+        Some((at, hash))
+            if is_legacy
+                && hash.starts_with("17h")
+                && hash.ends_with('E')
+                && hash[3..HASH - 1].bytes().all(|b| b.is_ascii_hexdigit()) =>
+        {
+            &name[..at]
+        }
+        _ => name,
+    }
+}
+
+/// Returns the crate of a Rust v0 name: the crate root reached by following
+/// the first path through nested paths, impls and generic arguments.
+fn rust_v0_crate(input: &str) -> Option<String> {
+    let mut rest = input;
+    loop {
+        match rest.as_bytes().first()? {
+            b'I' | b'M' | b'X' | b'Y' => rest = &rest[1..],
+            b'N' => rest = rest.get(2..)?,
+            b's' => rest = &rest[rest.find('_')? + 1..],
+            b'C' => return rust_v0_identifier(&rest[1..]).map(|(name, _)| name),
+            _ => return None,
+        }
+    }
+}
+
+/// Returns the outermost namespace (crate, C++ namespace or class) of a
+/// mangled name, or `None` for names in the global namespace. Rust trait
+/// impls (`<Type as Trait>::f`) belong to the crate of the type, or of the
+/// trait for blanket impls.
+pub fn namespace(name: &str) -> Option<String> {
+    if let Some(rest) = name.strip_prefix("_R") {
+        return rust_v0_crate(rest.trim_start_matches(|c: char| c.is_ascii_digit()));
+    }
+    let (components, _) = components(name)?;
+    if components.len() < 2 {
+        return None;
+    }
+    let first = components.into_iter().next()?;
+    if !first.starts_with('<') {
+        return Some(first);
+    }
+    let outermost = |path: &str| -> Option<String> {
+        let mut path = path.trim_start_matches(['<', '&', '*', '[', '(']);
+        for prefix in ["mut ", "const ", "dyn "] {
+            path = path.strip_prefix(prefix).unwrap_or(path);
+        }
+        let end = path.find([':', '<', '>', ' ', ',', ';', ']', ')'])?;
+        // a lone name is a generic parameter or a primitive type
+        (end > 0 && path[end..].starts_with("::")).then(|| path[..end].to_string())
+    };
+    outermost(&first)
+        .or_else(|| {
+            first
+                .find(" as ")
+                .and_then(|at| outermost(&first[at + 4..]))
+        })
+        .or(Some(first))
+}
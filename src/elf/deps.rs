@@ -0,0 +1,604 @@
+//! Resolution of the shared library dependencies of a binary the way the
+//! dynamic linker would, without running it (unlike `ldd`, which may run the
+//! binary's interpreter).
+//!
+//! Libraries are searched in the order of glibc's `ld.so`: `DT_RPATH` of the
+//! loading object and its loaders (unless it has `DT_RUNPATH`),
+//! `LD_LIBRARY_PATH`, `DT_RUNPATH`, the directories of `/etc/ld.so.conf`
+//! (standing in for `ld.so.cache`) and the default directories. Candidates
+//! of another class, byte order or machine are skipped. With a sysroot all
+//! absolute paths, including the targets of symbolic links, are looked up
+//! inside it, and relative `DT_NEEDED` paths are taken from its root.
+
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::strip::matches_pattern;
+use super::types::{SymbolBinding, SymbolType};
+use super::versions::ExportedSymbols;
+use super::{ElfFile, Result};
+
+/// Searched after the configured directories. Some distributions only have
+/// the plain `lib` directories for 64-bit targets, so those come last.
+const DEFAULT_DIRS_32: &[&str] = &["/lib", "/usr/lib"];
+const DEFAULT_DIRS_64: &[&str] = &["/lib64", "/usr/lib64", "/lib", "/usr/lib"];
+
+/// Bounds symbolic link chains and `include` nesting.
+const MAX_LINKS: usize = 40;
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchSource {
+    /// `DT_NEEDED` holds a path.
+    Path,
+    /// The program interpreter, which is loaded first.
+    Interpreter,
+    RPath,
+    LdLibraryPath,
+    RunPath,
+    LdSoConf,
+    Default,
+}
+
+impl SearchSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            SearchSource::Path => "path",
+            SearchSource::Interpreter => "interpreter",
+            SearchSource::RPath => "RPATH",
+            SearchSource::LdLibraryPath => "LD_LIBRARY_PATH",
+            SearchSource::RunPath => "RUNPATH",
+            SearchSource::LdSoConf => "ld.so.conf",
+            SearchSource::Default => "default path",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolveOptions {
+    /// Root of the target file system.
+    pub sysroot: Option<PathBuf>,
+    /// `LD_LIBRARY_PATH` entries.
+    pub library_path: Vec<String>,
+    /// Read `/etc/ld.so.conf` (in the sysroot).
+    pub ld_so_conf: bool,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            sysroot: None,
+            library_path: Vec::new(),
+            ld_so_conf: true,
+        }
+    }
+}
+
+/// A node of the dependency tree.
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    /// The `DT_NEEDED` entry, or the path of the root.
+    pub name: String,
+    pub parent: Option<usize>,
+    pub depth: usize,
+    /// Host path of the library; `None` if it was not found.
+    pub path: Option<PathBuf>,
+    pub source: Option<SearchSource>,
+    /// The earlier node that already loaded this library.
+    pub duplicate_of: Option<usize>,
+    /// Candidates with the right name but the wrong class or machine, and
+    /// why they were skipped.
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl Dependency {
+    pub fn is_missing(&self) -> bool {
+        self.path.is_none() && self.duplicate_of.is_none()
+    }
+}
+
+/// An undefined dynamic symbol and the loaded object defining it.
+#[derive(Debug, Clone)]
+pub struct SymbolResolution {
+    /// Node of the referencing object.
+    pub object: usize,
+    pub name: String,
+    pub version: Option<String>,
+    pub weak: bool,
+    /// Node of the first object in load order defining the symbol.
+    pub provider: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DependencyTree {
+    pub interpreter: Option<String>,
+    /// Host path of the interpreter, if it exists.
+    pub interpreter_path: Option<PathBuf>,
+    /// In load (breadth-first) order; the root comes first.
+    pub nodes: Vec<Dependency>,
+    pub symbols: Vec<SymbolResolution>,
+}
+
+impl DependencyTree {
+    pub fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(move |&child| self.nodes[child].parent == Some(index))
+    }
+
+    pub fn missing(&self) -> impl Iterator<Item = &Dependency> {
+        self.nodes.iter().filter(|node| node.is_missing())
+    }
+
+    /// Returns the references no loaded object defines, leaving out weak
+    /// ones, which the dynamic linker resolves to zero.
+    pub fn unresolved(&self) -> impl Iterator<Item = &SymbolResolution> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.provider.is_none() && !symbol.weak)
+    }
+}
+
+/// Maps an absolute path of the target file system to the host, resolving
+/// symbolic links inside the sysroot so that absolute link targets do not
+/// escape it. Returns `None` for chains longer than `MAX_LINKS`, whose
+/// last link would otherwise be followed on the host.
+pub fn sysroot_path(sysroot: Option<&Path>, path: &Path) -> Option<PathBuf> {
+    let Some(root) = sysroot else {
+        return Some(path.to_path_buf());
+    };
+    let push = |pending: &mut Vec<OsString>, path: &Path| {
+        for component in path.components().rev() {
+            match component {
+                Component::Normal(name) => pending.push(name.to_os_string()),
+                Component::ParentDir => pending.push(OsString::from("..")),
+                _ => {}
+            }
+        }
+    };
+    let mut pending = Vec::new();
+    push(&mut pending, path);
+    let mut resolved = PathBuf::new();
+    let mut links = 0;
+    while let Some(name) = pending.pop() {
+        if name == ".." {
+            resolved.pop();
+            continue;
+        }
+        let candidate = resolved.join(&name);
+        match fs::read_link(root.join(&candidate)) {
+            Ok(_) if links >= MAX_LINKS => return None,
+            Ok(target) => {
+                links += 1;
+                if target.is_absolute() {
+                    resolved = PathBuf::new();
+                }
+                push(&mut pending, &target);
+            }
+            _ => resolved = candidate,
+        }
+    }
+    Some(root.join(resolved))
+}
+
+/// Returns the library directories of an `ld.so.conf` file and the files it
+/// includes.
+pub fn read_ld_so_conf(sysroot: Option<&Path>, path: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    read_ld_so_conf_into(sysroot, path, 0, &mut dirs);
+    dirs
+}
+
+fn read_ld_so_conf_into(
+    sysroot: Option<&Path>,
+    path: &Path,
+    depth: usize,
+    dirs: &mut Vec<PathBuf>,
+) {
+    let Some(Ok(text)) = sysroot_path(sysroot, path).map(fs::read_to_string) else {
+        return;
+    };
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if let Some(pattern) = line.strip_prefix("include") {
+            if depth >= MAX_INCLUDE_DEPTH {
+                continue;
+            }
+            let pattern = pattern.trim();
+            let pattern = match path.parent() {
+                Some(parent) if !pattern.starts_with('/') => parent.join(pattern),
+                _ => PathBuf::from(pattern),
+            };
+            for file in expand_glob(sysroot, &pattern) {
+                read_ld_so_conf_into(sysroot, &file, depth + 1, dirs);
+            }
+        } else if !line.starts_with("hwcap") {
+            // old files allow several directories per line and `dir=type`
+            for dir in line
+                .split([' ', '\t', ':', ','])
+                .filter(|dir| !dir.is_empty())
+            {
+                let dir = PathBuf::from(dir.split('=').next().unwrap_or(dir));
+                if !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+    }
+}
+
+/// Expands `*` in the last component of `pattern`, sorted by name.
+fn expand_glob(sysroot: Option<&Path>, pattern: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(name)) = (
+        pattern.parent(),
+        pattern.file_name().and_then(|name| name.to_str()),
+    ) else {
+        return Vec::new();
+    };
+    if !name.contains('*') {
+        return vec![pattern.to_path_buf()];
+    }
+    let Some(Ok(entries)) = sysroot_path(sysroot, dir).map(fs::read_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|file| matches_pattern(name, file))
+        .map(|file| dir.join(file))
+        .collect();
+    files.sort();
+    files
+}
+
+/// A loaded object.
+struct Loaded {
+    node: usize,
+    /// `None` for the root, which the resolver borrows.
+    elf: Option<ElfFile>,
+    path: PathBuf,
+    rpath: Option<String>,
+    runpath: Option<String>,
+}
+
+struct Resolver<'a> {
+    options: &'a ResolveOptions,
+    root: &'a ElfFile,
+    conf_dirs: Vec<PathBuf>,
+    nodes: Vec<Dependency>,
+    loaded: Vec<Loaded>,
+}
+
+impl Resolver<'_> {
+    fn sysroot(&self) -> Option<&Path> {
+        self.options.sysroot.as_deref()
+    }
+
+    fn elf<'b>(&'b self, loaded: &'b Loaded) -> &'b ElfFile {
+        loaded.elf.as_ref().unwrap_or(self.root)
+    }
+
+    /// Resolves the symbolic links of a host path inside the sysroot.
+    fn contain(&self, path: &Path) -> Option<PathBuf> {
+        match self
+            .sysroot()
+            .and_then(|root| Some((root, path.strip_prefix(root).ok()?)))
+        {
+            Some((root, rest)) => sysroot_path(Some(root), &Path::new("/").join(rest)),
+            None => Some(path.to_path_buf()),
+        }
+    }
+
+    /// Host directory `$ORIGIN` expands to. Paths in the sysroot have their
+    /// links resolved already.
+    fn origin(&self, loaded: &Loaded) -> PathBuf {
+        let path = match self.sysroot() {
+            Some(_) => self
+                .contain(&loaded.path)
+                .unwrap_or_else(|| loaded.path.clone()),
+            None => fs::canonicalize(&loaded.path).unwrap_or_else(|_| loaded.path.clone()),
+        };
+        path.parent().map(Path::to_path_buf).unwrap_or_default()
+    }
+
+    /// Expands a search path entry to a host directory. `$ORIGIN` is
+    /// already a host path; other absolute paths are in the sysroot.
+    fn expand(&self, entry: &str, origin: &Path, is_64: bool) -> Option<PathBuf> {
+        let origin = origin.to_string_lossy();
+        let lib = if is_64 { "lib64" } else { "lib" };
+        let expanded = entry
+            .replace("${ORIGIN}", &origin)
+            .replace("$ORIGIN", &origin)
+            .replace("${LIB}", lib)
+            .replace("$LIB", lib);
+        if entry.contains("ORIGIN") {
+            Some(PathBuf::from(expanded))
+        } else {
+            sysroot_path(self.sysroot(), Path::new(&expanded))
+        }
+    }
+
+    /// Returns why `elf` cannot be loaded next to the root, if it cannot.
+    fn incompatibility(&self, elf: &ElfFile) -> Option<String> {
+        if elf.get_class() != self.root.get_class() {
+            Some(format!("wrong class {}", elf.get_class()))
+        } else if elf.is_big_endian() != self.root.is_big_endian() {
+            Some(format!("wrong byte order {}", elf.get_data()))
+        } else if elf.get_machine() != self.root.get_machine() {
+            Some(format!("wrong machine {}", elf.get_machine()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the directories to search for a dependency of the loaded
+    /// object `requester`, in order.
+    fn search_dirs(&self, requester: usize) -> Vec<(Option<PathBuf>, SearchSource)> {
+        let is_64 = self.root.get_class() == "ELF64";
+        let mut dirs = Vec::new();
+        let split = |paths: &str| {
+            paths
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        if self.loaded[requester].runpath.is_none() {
+            // the RPATH of the requester and of each object that loaded it
+            let mut current = Some(requester);
+            while let Some(index) = current {
+                let loaded = &self.loaded[index];
+                if loaded.runpath.is_none()
+                    && let Some(rpath) = &loaded.rpath
+                {
+                    for dir in split(rpath) {
+                        dirs.push((
+                            self.expand(&dir, &self.origin(loaded), is_64),
+                            SearchSource::RPath,
+                        ));
+                    }
+                }
+                current = self.nodes[loaded.node]
+                    .parent
+                    .and_then(|parent| self.loaded.iter().position(|other| other.node == parent));
+            }
+        }
+        for dir in &self.options.library_path {
+            dirs.push((
+                sysroot_path(self.sysroot(), Path::new(dir)),
+                SearchSource::LdLibraryPath,
+            ));
+        }
+        if let Some(runpath) = &self.loaded[requester].runpath {
+            for dir in split(runpath) {
+                let origin = self.origin(&self.loaded[requester]);
+                dirs.push((self.expand(&dir, &origin, is_64), SearchSource::RunPath));
+            }
+        }
+        for dir in &self.conf_dirs {
+            dirs.push((sysroot_path(self.sysroot(), dir), SearchSource::LdSoConf));
+        }
+        for dir in if is_64 {
+            DEFAULT_DIRS_64
+        } else {
+            DEFAULT_DIRS_32
+        } {
+            dirs.push((
+                sysroot_path(self.sysroot(), Path::new(dir)),
+                SearchSource::Default,
+            ));
+        }
+        dirs
+    }
+
+    /// Reads `path` if it is a loadable ELF file, recording why it is not
+    /// otherwise.
+    fn try_load(&self, path: &Path, skipped: &mut Vec<(PathBuf, String)>) -> Option<ElfFile> {
+        if !path.is_file() {
+            return None;
+        }
+        // every link in the sysroot is resolved, so one left would lead out
+        if self.sysroot().is_some()
+            && fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_symlink())
+        {
+            skipped.push((
+                path.to_path_buf(),
+                "symbolic link out of the sysroot".to_string(),
+            ));
+            return None;
+        }
+        let elf = match fs::read(path)
+            .map_err(Into::into)
+            .and_then(|data| ElfFile::from_bytes(&data))
+        {
+            Ok(elf) => elf,
+            Err(e) => {
+                skipped.push((path.to_path_buf(), e.to_string()));
+                return None;
+            }
+        };
+        match self.incompatibility(&elf) {
+            Some(reason) => {
+                skipped.push((path.to_path_buf(), reason));
+                None
+            }
+            None => Some(elf),
+        }
+    }
+
+    /// Returns the node of the loaded object called `name` (its needed name
+    /// or SONAME) or at `path`.
+    fn find_loaded(&self, name: &str, path: Option<&Path>) -> Option<usize> {
+        let canonical = path.and_then(|path| fs::canonicalize(path).ok());
+        self.loaded
+            .iter()
+            .find(|loaded| {
+                self.nodes[loaded.node].name == name
+                    || self
+                        .elf(loaded)
+                        .get_dynamic_info()
+                        .is_ok_and(|info| info.soname.as_deref() == Some(name))
+                    || canonical.is_some() && fs::canonicalize(&loaded.path).ok() == canonical
+            })
+            .map(|loaded| loaded.node)
+    }
+
+    fn resolve(&mut self, requester: usize, name: &str) -> Result<()> {
+        let parent = self.loaded[requester].node;
+        let mut node = Dependency {
+            name: name.to_string(),
+            parent: Some(parent),
+            depth: self.nodes[parent].depth + 1,
+            path: None,
+            source: None,
+            duplicate_of: self.find_loaded(name, None),
+            skipped: Vec::new(),
+        };
+        if node.duplicate_of.is_some() {
+            self.nodes.push(node);
+            return Ok(());
+        }
+
+        let interpreter = self.root.get_interpreter().filter(|interpreter| {
+            interpreter == name || Path::new(interpreter).file_name() == Some(name.as_ref())
+        });
+        let candidates = if let Some(interpreter) = interpreter {
+            vec![(
+                sysroot_path(self.sysroot(), Path::new(&interpreter)),
+                SearchSource::Interpreter,
+            )]
+        } else if name.contains('/') {
+            // The working directory of the target is unknown, so with a
+            // sysroot relative paths are taken from its root.
+            vec![(
+                sysroot_path(self.sysroot(), Path::new(name)),
+                SearchSource::Path,
+            )]
+        } else {
+            self.search_dirs(requester)
+                .into_iter()
+                .map(|(dir, source)| (dir.map(|dir| dir.join(name)), source))
+                .collect()
+        };
+        let mut found = None;
+        for (path, source) in candidates {
+            let Some(path) = path.and_then(|path| self.contain(&path)) else {
+                continue;
+            };
+            if let Some(elf) = self.try_load(&path, &mut node.skipped) {
+                found = Some((path, source, elf));
+                break;
+            }
+        }
+        if let Some((path, source, elf)) = found {
+            node.duplicate_of = self.find_loaded(name, Some(&path));
+            if node.duplicate_of.is_none() {
+                node.path = Some(path.clone());
+                node.source = Some(source);
+                let info = elf.get_dynamic_info()?;
+                self.loaded.push(Loaded {
+                    node: self.nodes.len(),
+                    elf: Some(elf),
+                    path,
+                    rpath: info.rpath,
+                    runpath: info.runpath,
+                });
+            }
+        }
+        self.nodes.push(node);
+        Ok(())
+    }
+}
+
+impl ElfFile {
+    /// Resolves the dependencies of `self`, read from `path`, and the
+    /// providers of its and their undefined dynamic symbols.
+    pub fn resolve_dependencies(
+        &self,
+        path: &Path,
+        options: &ResolveOptions,
+    ) -> Result<DependencyTree> {
+        let conf_dirs = if options.ld_so_conf {
+            read_ld_so_conf(options.sysroot.as_deref(), Path::new("/etc/ld.so.conf"))
+        } else {
+            Vec::new()
+        };
+        let info = self.get_dynamic_info()?;
+        let mut resolver = Resolver {
+            options,
+            root: self,
+            conf_dirs,
+            nodes: vec![Dependency {
+                name: path.display().to_string(),
+                parent: None,
+                depth: 0,
+                path: Some(path.to_path_buf()),
+                source: None,
+                duplicate_of: None,
+                skipped: Vec::new(),
+            }],
+            loaded: vec![Loaded {
+                node: 0,
+                elf: None,
+                path: path.to_path_buf(),
+                rpath: info.rpath,
+                runpath: info.runpath,
+            }],
+        };
+
+        // breadth first, like the dynamic linker
+        let mut queue = VecDeque::from([0]);
+        while let Some(requester) = queue.pop_front() {
+            let needed = resolver
+                .elf(&resolver.loaded[requester])
+                .get_dynamic_info()?
+                .needed;
+            for name in needed {
+                let count = resolver.loaded.len();
+                resolver.resolve(requester, &name)?;
+                if resolver.loaded.len() > count {
+                    queue.push_back(count);
+                }
+            }
+        }
+
+        let exports = resolver
+            .loaded
+            .iter()
+            .map(|loaded| Ok((loaded.node, ExportedSymbols::new(resolver.elf(loaded))?)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut symbols = Vec::new();
+        for loaded in &resolver.loaded {
+            for (symbol, version) in resolver.elf(loaded).get_imported_symbols()? {
+                if symbol.get_type() == SymbolType::Section {
+                    continue;
+                }
+                let provider = exports
+                    .iter()
+                    .find(|(_, exported)| exported.find(&symbol.name, version.as_ref()).is_some())
+                    .map(|&(node, _)| node);
+                symbols.push(SymbolResolution {
+                    object: loaded.node,
+                    weak: symbol.get_binding() == SymbolBinding::Weak,
+                    name: symbol.name,
+                    version: version.map(|version| version.name),
+                    provider,
+                });
+            }
+        }
+
+        let interpreter = self.get_interpreter();
+        let interpreter_path = interpreter
+            .as_ref()
+            .and_then(|interpreter| {
+                sysroot_path(options.sysroot.as_deref(), Path::new(interpreter))
+            })
+            .filter(|path| path.is_file() && !(options.sysroot.is_some() && path.is_symlink()));
+        Ok(DependencyTree {
+            interpreter,
+            interpreter_path,
+            nodes: resolver.nodes,
+            symbols,
+        })
+    }
+}
@@ -0,0 +1,347 @@
+//! Field-by-field comparison of two ELF files, e.g. to find out why a build
+//! is not reproducible.
+//!
+//! Each file is described as a list of records ("scopes") holding named
+//! fields: the header, every segment (matched by type and occurrence, e.g.
+//! `segment[LOAD#1]`), every section (matched by name), the dynamic
+//! entries, the exported dynamic symbols (matched by name and version) and
+//! the notes. Fields are addressed as `scope.field` for ignoring.
+
+use std::collections::HashMap;
+
+use super::header::SHF_ALLOC;
+use super::strip::{crc32, matches_pattern};
+use super::types::SectionType;
+use super::versions::SymbolVersion;
+use super::{ElfFile, Result, read_cstr};
+
+/// Fields that change with every build even when the code does not: the
+/// build ID and the debug link checksums.
+pub const VOLATILE: &[&str] = &[
+    "note[*NT_GNU_BUILD_ID*",
+    "note[*NT_GO_BUILD_ID*",
+    "section[.note.gnu.build-id].contents",
+    "section[.note.go.buildid].contents",
+    "section[.gnu_debuglink].contents",
+    "section[.gnu_debugaltlink].contents",
+];
+
+/// Notes are shown in hex up to this many bytes.
+const MAX_NOTE_BYTES: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub scope: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl Record {
+    fn new(scope: String) -> Self {
+        Self {
+            scope,
+            fields: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, field: &str, value: impl ToString) {
+        self.fields.push((field.to_string(), value.to_string()));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeDiff {
+    pub scope: String,
+    pub status: ScopeStatus,
+    pub fields: Vec<FieldDiff>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions {
+    /// `scope.field` or `scope` patterns with `*` wildcards to leave out.
+    pub ignore: Vec<String>,
+}
+
+/// Gives repeated keys an occurrence suffix (`NEEDED#1`), leaving the first
+/// one bare.
+fn unique_key(counts: &mut HashMap<String, usize>, key: String) -> String {
+    let count = counts.entry(key.clone()).or_insert(0);
+    *count += 1;
+    if *count == 1 {
+        key
+    } else {
+        format!("{}#{}", key, *count - 1)
+    }
+}
+
+fn hex(value: u64) -> String {
+    format!("0x{:X}", value)
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    let shown: String = bytes
+        .iter()
+        .take(MAX_NOTE_BYTES)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if bytes.len() > MAX_NOTE_BYTES {
+        format!("{}...", shown)
+    } else {
+        shown
+    }
+}
+
+impl ElfFile {
+    fn section_label(&self, index: usize) -> String {
+        match self.get_section_name(index) {
+            Some(name) if !name.is_empty() => name,
+            _ => format!("#{}", index),
+        }
+    }
+
+    /// Describes the file as records for [`diff_files`].
+    pub fn describe(&self) -> Result<Vec<Record>> {
+        let machine = self.get_machine();
+        let osabi = self.get_osabi();
+        let mut records = Vec::new();
+
+        let mut header = Record::new("header".to_string());
+        header.push("class", self.get_class());
+        header.push("data", self.get_data());
+        header.push("osabi", osabi);
+        header.push("abiversion", self.e_ident[8]);
+        header.push("e_type", self.get_type());
+        header.push("e_machine", machine);
+        header.push("e_version", self.e_version);
+        header.push("e_entry", hex(self.e_entry));
+        header.push("e_phoff", self.e_phoff);
+        header.push("e_shoff", self.e_shoff);
+        header.push("e_flags", self.get_flags());
+        header.push("e_phnum", self.e_phnum);
+        header.push("e_shnum", self.e_shnum);
+        header.push("e_shstrndx", self.section_label(self.e_shstrndx as usize));
+        records.push(header);
+
+        let mut counts = HashMap::new();
+        for ph in &self.program_headers {
+            let name = ph.get_type_name(machine, osabi);
+            let count = counts.entry(name.clone()).or_insert(0);
+            let mut record = Record::new(format!("segment[{}#{}]", name, count));
+            *count += 1;
+            record.push("p_flags", ph.get_flags());
+            record.push("p_offset", hex(ph.p_offset()));
+            record.push("p_vaddr", hex(ph.p_vaddr()));
+            record.push("p_paddr", hex(ph.p_paddr()));
+            record.push("p_filesz", hex(ph.p_filesz()));
+            record.push("p_memsz", hex(ph.p_memsz()));
+            record.push("p_align", hex(ph.p_align()));
+            let sections: Vec<String> = self
+                .section_headers
+                .iter()
+                .enumerate()
+                .filter(|(_, sh)| {
+                    sh.sh_size() > 0
+                        && sh.sh_flags() & SHF_ALLOC != 0
+                        && sh.sh_addr() >= ph.p_vaddr()
+                        && sh
+                            .sh_addr()
+                            .checked_add(sh.sh_size())
+                            .zip(ph.p_vaddr().checked_add(ph.p_memsz()))
+                            .is_some_and(|(end, segment_end)| end <= segment_end)
+                })
+                .map(|(index, _)| self.section_label(index))
+                .collect();
+            record.push("sections", sections.join(" "));
+            records.push(record);
+        }
+
+        let mut counts = HashMap::new();
+        for (index, sh) in self.section_headers.iter().enumerate().skip(1) {
+            let scope = unique_key(&mut counts, self.section_label(index));
+            let mut record = Record::new(format!("section[{}]", scope));
+            record.push("sh_type", sh.get_type_name(machine, osabi));
+            record.push("sh_flags", sh.get_flags());
+            record.push("sh_addr", hex(sh.sh_addr()));
+            record.push("sh_offset", hex(sh.sh_offset()));
+            record.push("sh_size", hex(sh.sh_size()));
+            let link = sh.sh_link() as usize;
+            record.push(
+                "sh_link",
+                if link == 0 {
+                    "0".to_string()
+                } else {
+                    self.section_label(link)
+                },
+            );
+            record.push("sh_info", sh.sh_info());
+            record.push("sh_addralign", sh.sh_addralign());
+            record.push("sh_entsize", sh.sh_entsize());
+            if sh.get_type() != SectionType::Nobits
+                && let Some(data) = self.get_raw_section_data(index)
+            {
+                record.push("contents", format!("crc32 {:08x}", crc32(data)));
+            }
+            records.push(record);
+        }
+
+        let entries = self.get_dynamic_entries()?;
+        if !entries.is_empty() {
+            let strings = self.get_dynamic_strings()?;
+            let mut record = Record::new("dynamic".to_string());
+            let mut counts = HashMap::new();
+            for entry in &entries {
+                let tag = entry.get_tag();
+                let key = unique_key(&mut counts, tag.to_string());
+                let value = match strings {
+                    Some(strings) if tag.is_string() => {
+                        read_cstr(strings, entry.d_val as usize).unwrap_or_default()
+                    }
+                    _ => hex(entry.d_val),
+                };
+                record.push(&key, value);
+            }
+            records.push(record);
+        }
+
+        let mut counts = HashMap::new();
+        let mut exported = Vec::new();
+        for (symbol, version) in self.get_exported_symbols()? {
+            let name = SymbolVersion::qualify(version.as_ref(), &symbol.name);
+            let mut record = Record::new(format!("symbol[{}]", unique_key(&mut counts, name)));
+            record.push("type", symbol.get_type());
+            record.push("binding", symbol.get_binding());
+            record.push("visibility", symbol.get_visibility());
+            record.push("value", hex(symbol.st_value));
+            record.push("size", symbol.st_size);
+            let section = match symbol.section_index() {
+                Some(index) => self.section_label(index),
+                None => hex(symbol.st_shndx as u64),
+            };
+            record.push("section", section);
+            exported.push(record);
+        }
+        exported.sort_by(|a, b| a.scope.cmp(&b.scope));
+        records.extend(exported);
+
+        let mut counts = HashMap::new();
+        for note in self.get_notes() {
+            let kind = note
+                .type_name()
+                .map_or_else(|| format!("type {}", note.n_type), str::to_string);
+            let key = unique_key(
+                &mut counts,
+                format!("{} {} {}", note.source, note.owner, kind),
+            );
+            let mut record = Record::new(format!("note[{}]", key));
+            record.push("size", note.desc.len());
+            record.push("desc", hex_bytes(&note.desc));
+            records.push(record);
+        }
+        Ok(records)
+    }
+}
+
+fn category(scope: &str) -> &str {
+    scope.split('[').next().unwrap_or(scope)
+}
+
+/// Compares the descriptions of two files. Scopes keep the order of the
+/// old file, with scopes only in the new one added at the end of their
+/// category.
+pub fn diff_files(old: &ElfFile, new: &ElfFile, options: &DiffOptions) -> Result<Vec<ScopeDiff>> {
+    let ignored = |path: &str| {
+        options
+            .ignore
+            .iter()
+            .any(|pattern| matches_pattern(pattern, path))
+    };
+    let old_records = old.describe()?;
+    let new_records = new.describe()?;
+    let new_index: HashMap<&str, &Record> =
+        new_records.iter().map(|r| (r.scope.as_str(), r)).collect();
+    let old_index: HashMap<&str, &Record> =
+        old_records.iter().map(|r| (r.scope.as_str(), r)).collect();
+
+    let mut order: Vec<&str> = old_records.iter().map(|r| r.scope.as_str()).collect();
+    for record in &new_records {
+        if old_index.contains_key(record.scope.as_str()) {
+            continue;
+        }
+        let at = order
+            .iter()
+            .rposition(|scope| category(scope) == category(&record.scope))
+            .map_or(order.len(), |i| i + 1);
+        order.insert(at, &record.scope);
+    }
+
+    let mut diffs = Vec::new();
+    for scope in order {
+        if ignored(scope) {
+            continue;
+        }
+        let fields = |record: &Record, old: bool| -> Vec<FieldDiff> {
+            record
+                .fields
+                .iter()
+                .filter(|(field, _)| !ignored(&format!("{}.{}", scope, field)))
+                .map(|(field, value)| FieldDiff {
+                    field: field.clone(),
+                    old: old.then(|| value.clone()),
+                    new: (!old).then(|| value.clone()),
+                })
+                .collect()
+        };
+        let (status, fields) = match (old_index.get(scope), new_index.get(scope)) {
+            (Some(old), None) => (ScopeStatus::Removed, fields(old, true)),
+            (None, Some(new)) => (ScopeStatus::Added, fields(new, false)),
+            (Some(old), Some(new)) => {
+                let mut changed = Vec::new();
+                for (field, value) in &old.fields {
+                    let other = new.fields.iter().find(|(f, _)| f == field).map(|(_, v)| v);
+                    if other != Some(value) && !ignored(&format!("{}.{}", scope, field)) {
+                        changed.push(FieldDiff {
+                            field: field.clone(),
+                            old: Some(value.clone()),
+                            new: other.cloned(),
+                        });
+                    }
+                }
+                for (field, value) in &new.fields {
+                    if !old.fields.iter().any(|(f, _)| f == field)
+                        && !ignored(&format!("{}.{}", scope, field))
+                    {
+                        changed.push(FieldDiff {
+                            field: field.clone(),
+                            old: None,
+                            new: Some(value.clone()),
+                        });
+                    }
+                }
+                if changed.is_empty() {
+                    continue;
+                }
+                (ScopeStatus::Changed, changed)
+            }
+            (None, None) => continue,
+        };
+        diffs.push(ScopeDiff {
+            scope: scope.to_string(),
+            status,
+            fields,
+        });
+    }
+    Ok(diffs)
+}
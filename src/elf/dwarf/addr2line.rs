@@ -0,0 +1,212 @@
+//! Address to source location lookup built from `.debug_line`, with
+//! optional inlined frame expansion from `.debug_info`.
+
+use std::collections::HashMap;
+
+use super::DwarfSections;
+use super::constants::*;
+use super::info::{Die, Unit};
+use super::line::{LineProgram, LineProgramHeader};
+use crate::elf::Result;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: Option<String>,
+    pub line: u64,
+    pub column: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub function: Option<String>,
+    pub location: Option<Location>,
+}
+
+/// A half-open address range `[start, end)` mapped to one line table row.
+#[derive(Debug, Clone, Copy)]
+struct LineRange {
+    start: u64,
+    end: u64,
+    file: Option<usize>,
+    line: u64,
+    column: u64,
+}
+
+pub struct Addr2Line<'a> {
+    sections: DwarfSections<'a>,
+    units: Vec<Unit>,
+    /// Line program headers keyed by their `.debug_line` offset.
+    headers: HashMap<u64, LineProgramHeader>,
+    files: Vec<String>,
+    ranges: Vec<LineRange>,
+}
+
+impl<'a> Addr2Line<'a> {
+    pub fn new(sections: DwarfSections<'a>) -> Result<Self> {
+        let units = sections.units()?;
+
+        // Each program is read through the unit that owns it so that
+        // relative paths can be joined with the unit's DW_AT_comp_dir.
+        let mut programs = Vec::new();
+        let mut seen = HashMap::new();
+        for unit in &units {
+            if let Some(offset) = unit.stmt_list
+                && seen.insert(offset, ()).is_none()
+            {
+                programs.push((
+                    LineProgram::parse(&sections, offset)?,
+                    unit.comp_dir.clone(),
+                ));
+            }
+        }
+        if units.is_empty() {
+            for program in LineProgram::parse_all(&sections)? {
+                programs.push((program, None));
+            }
+        }
+
+        let mut files = Vec::new();
+        let mut ranges = Vec::new();
+        let mut headers = HashMap::new();
+        for (program, comp_dir) in programs {
+            let mut file_ids = HashMap::new();
+            for pair in program.rows.windows(2) {
+                let (row, next) = (pair[0], pair[1]);
+                if row.end_sequence || next.address <= row.address {
+                    continue;
+                }
+                let file = *file_ids.entry(row.file).or_insert_with(|| {
+                    program
+                        .header
+                        .file_path(row.file, comp_dir.as_deref())
+                        .map(|path| {
+                            files.push(path);
+                            files.len() - 1
+                        })
+                });
+                ranges.push(LineRange {
+                    start: row.address,
+                    end: next.address,
+                    file,
+                    line: row.line,
+                    column: row.column,
+                });
+            }
+            headers.insert(program.header.offset, program.header);
+        }
+        ranges.sort_by_key(|r| r.start);
+
+        Ok(Self {
+            sections,
+            units,
+            headers,
+            files,
+            ranges,
+        })
+    }
+
+    /// Returns the source location of `address` from the line tables.
+    pub fn find_location(&self, address: u64) -> Option<Location> {
+        let index = self.ranges.partition_point(|r| r.start <= address);
+        let range = self.ranges[..index]
+            .iter()
+            .rev()
+            .find(|r| address < r.end)?;
+        Some(Location {
+            file: range.file.map(|i| self.files[i].clone()),
+            line: range.line,
+            column: range.column,
+        })
+    }
+
+    /// Returns the frames for `address`, innermost first. Without `inlines`
+    /// only the innermost frame is returned.
+    pub fn find_frames(&self, address: u64, inlines: bool) -> Result<Vec<Frame>> {
+        let location = self.find_location(address);
+        let (unit, chain) = match self.find_scope_chain(address)? {
+            Some(found) => found,
+            None => {
+                return Ok(vec![Frame {
+                    function: None,
+                    location,
+                }]);
+            }
+        };
+
+        let mut frames = Vec::new();
+        let mut location = location;
+        for (i, die) in chain.iter().enumerate().rev() {
+            frames.push(Frame {
+                function: self.sections.die_name(&self.units, unit, die),
+                location: location.take(),
+            });
+            if !inlines || i == 0 {
+                break;
+            }
+            // the caller's location is the call site of this inlined entry
+            location = self.call_site(unit, die);
+        }
+        Ok(frames)
+    }
+
+    /// Finds the nested subprogram and inlined subroutine entries that
+    /// contain `address`, outermost first.
+    fn find_scope_chain(&self, address: u64) -> Result<Option<(&Unit, Vec<Die<'a>>)>> {
+        for unit in &self.units {
+            let mut stack: Vec<Die<'a>> = Vec::new();
+            let mut best: Vec<Die<'a>> = Vec::new();
+            for die in unit.entries(&self.sections) {
+                let die = die?;
+                while stack.last().is_some_and(|d| d.depth >= die.depth) {
+                    stack.pop();
+                }
+                // Unreadable ranges (e.g. unrelocated objects) never match.
+                if die.depth == 0 {
+                    let ranges = self.sections.die_ranges(unit, &die).unwrap_or_default();
+                    if !ranges.is_empty() && !contains(&ranges, address) {
+                        break;
+                    }
+                    continue;
+                }
+                if die.tag != DW_TAG_SUBPROGRAM && die.tag != DW_TAG_INLINED_SUBROUTINE {
+                    continue;
+                }
+                if contains(
+                    &self.sections.die_ranges(unit, &die).unwrap_or_default(),
+                    address,
+                ) {
+                    stack.push(die);
+                    if stack.len() > best.len() {
+                        best = stack.clone();
+                    }
+                }
+            }
+            if !best.is_empty() {
+                return Ok(Some((unit, best)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn call_site(&self, unit: &Unit, die: &Die<'a>) -> Option<Location> {
+        let line = die.attr(DW_AT_CALL_LINE).and_then(|v| v.udata())?;
+        let column = die
+            .attr(DW_AT_CALL_COLUMN)
+            .and_then(|v| v.udata())
+            .unwrap_or(0);
+        let file = die
+            .attr(DW_AT_CALL_FILE)
+            .and_then(|v| v.udata())
+            .and_then(|file| {
+                let header = self.headers.get(&unit.stmt_list?)?;
+                header.file_path(file, unit.comp_dir.as_deref())
+            });
+        Some(Location { file, line, column })
+    }
+}
+
+fn contains(ranges: &[(u64, u64)], address: u64) -> bool {
+    ranges
+        .iter()
+        .any(|&(start, end)| start <= address && address < end)
+}
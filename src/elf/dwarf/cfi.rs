@@ -0,0 +1,855 @@
+//! Call frame information from `.eh_frame`, `.debug_frame` and the
+//! `.eh_frame_hdr` lookup table.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::DwarfReader;
+use super::constants::*;
+use crate::elf::error::ElfError;
+use crate::elf::types::SegmentType;
+use crate::elf::{ElfFile, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
+    EhFrame,
+    DebugFrame,
+}
+
+impl FrameKind {
+    pub fn section_name(&self) -> &'static str {
+        match self {
+            FrameKind::EhFrame => ".eh_frame",
+            FrameKind::DebugFrame => ".debug_frame",
+        }
+    }
+}
+
+/// Common information entry.
+#[derive(Debug, Clone)]
+pub struct Cie<'a> {
+    /// Offset of the entry in its section.
+    pub offset: u64,
+    /// Length of the entry, excluding the length field itself.
+    pub length: u64,
+    pub version: u8,
+    pub augmentation: String,
+    pub address_size: u8,
+    pub code_alignment_factor: u64,
+    pub data_alignment_factor: i64,
+    pub return_address_register: u64,
+    /// `DW_EH_PE_*` encoding of FDE addresses (`R` augmentation).
+    pub fde_encoding: u8,
+    /// `DW_EH_PE_*` encoding of LSDA pointers (`L` augmentation).
+    pub lsda_encoding: u8,
+    /// Personality routine address (`P` augmentation).
+    pub personality: Option<u64>,
+    /// Whether this is a signal trampoline frame (`S` augmentation).
+    pub signal_frame: bool,
+    /// Offset of the initial instructions in the section.
+    pub instructions_offset: u64,
+    pub initial_instructions: &'a [u8],
+}
+
+impl Cie<'_> {
+    fn has_augmentation_data(&self) -> bool {
+        self.augmentation.starts_with('z')
+    }
+}
+
+/// Frame description entry.
+#[derive(Debug, Clone)]
+pub struct Fde<'a> {
+    pub offset: u64,
+    /// Length of the entry, excluding the length field itself.
+    pub length: u64,
+    /// Offset of the owning CIE in the section.
+    pub cie_offset: u64,
+    pub pc_begin: u64,
+    pub pc_range: u64,
+    /// Language-specific data area (`L` augmentation).
+    pub lsda: Option<u64>,
+    pub instructions_offset: u64,
+    pub instructions: &'a [u8],
+}
+
+impl Fde<'_> {
+    pub fn pc_end(&self) -> u64 {
+        self.pc_begin.wrapping_add(self.pc_range)
+    }
+}
+
+/// A decoded call frame instruction. Offsets are already multiplied by the
+/// CIE's data alignment factor and location advances by its code alignment
+/// factor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallFrameInstruction<'a> {
+    SetLoc(u64),
+    AdvanceLoc(u64),
+    DefCfa {
+        register: u64,
+        offset: i64,
+    },
+    DefCfaRegister(u64),
+    DefCfaOffset(i64),
+    DefCfaExpression(&'a [u8]),
+    Undefined(u64),
+    SameValue(u64),
+    Offset {
+        register: u64,
+        offset: i64,
+    },
+    ValOffset {
+        register: u64,
+        offset: i64,
+    },
+    Register {
+        register: u64,
+        target: u64,
+    },
+    Expression {
+        register: u64,
+        expr: &'a [u8],
+    },
+    ValExpression {
+        register: u64,
+        expr: &'a [u8],
+    },
+    Restore(u64),
+    RememberState,
+    RestoreState,
+    ArgsSize(u64),
+    /// `DW_CFA_GNU_window_save`, which AArch64 reuses as
+    /// `DW_CFA_AARCH64_negate_ra_state`.
+    WindowSave,
+    Nop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfaRule<'a> {
+    RegisterOffset { register: u64, offset: i64 },
+    Expression(&'a [u8]),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterRule<'a> {
+    Undefined,
+    SameValue,
+    /// Saved at `CFA + offset`.
+    Offset(i64),
+    /// The value is `CFA + offset`.
+    ValOffset(i64),
+    /// Saved in another register.
+    Register(u64),
+    Expression(&'a [u8]),
+    ValExpression(&'a [u8]),
+}
+
+/// One row of the unwind table: the rules that apply from `address` up to
+/// the next row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwindRow<'a> {
+    pub address: u64,
+    pub cfa: CfaRule<'a>,
+    pub registers: BTreeMap<u64, RegisterRule<'a>>,
+}
+
+/// Base addresses for the relative `DW_EH_PE_*` pointer encodings.
+#[derive(Debug, Clone, Copy, Default)]
+struct PointerBases {
+    /// Address of the first byte of the data being read (`pcrel`).
+    section: u64,
+    /// Base for `datarel`: the start of `.eh_frame_hdr`.
+    data: u64,
+}
+
+/// Reads a pointer encoded with `encoding`, or `None` for
+/// `DW_EH_PE_omit`. Indirect pointers are returned as the address of the
+/// pointer.
+fn read_encoded(
+    reader: &mut DwarfReader<'_>,
+    encoding: u8,
+    address_size: u8,
+    bases: PointerBases,
+) -> Result<Option<u64>> {
+    if encoding == DW_EH_PE_OMIT {
+        return Ok(None);
+    }
+    if encoding & 0x70 == DW_EH_PE_ALIGNED {
+        let align = address_size as u64;
+        let pos = reader.position();
+        reader.set_position(pos.div_ceil(align) * align);
+    }
+
+    let pos = reader.position();
+    let value = match encoding & 0x0F {
+        DW_EH_PE_ABSPTR => reader.read_address(address_size)?,
+        DW_EH_PE_ULEB128 => reader.read_uleb128()?,
+        DW_EH_PE_UDATA2 => reader.read_u16()? as u64,
+        DW_EH_PE_UDATA4 => reader.read_u32()? as u64,
+        DW_EH_PE_UDATA8 => reader.read_u64()?,
+        DW_EH_PE_SLEB128 => reader.read_sleb128()? as u64,
+        DW_EH_PE_SDATA2 => reader.read_u16()? as i16 as i64 as u64,
+        DW_EH_PE_SDATA4 => reader.read_u32()? as i32 as i64 as u64,
+        DW_EH_PE_SDATA8 => reader.read_u64()?,
+        format => {
+            return Err(ElfError::ParseError(format!(
+                "unknown pointer encoding format 0x{:X}.",
+                format
+            )));
+        }
+    };
+
+    let value = match encoding & 0x70 {
+        DW_EH_PE_PCREL => bases.section.wrapping_add(pos).wrapping_add(value),
+        DW_EH_PE_DATAREL => bases.data.wrapping_add(value),
+        _ => value,
+    };
+    Ok(Some(if address_size == 4 {
+        value & 0xFFFF_FFFF
+    } else {
+        value
+    }))
+}
+
+/// The parsed contents of one `.eh_frame` or `.debug_frame` section.
+#[derive(Debug, Clone)]
+pub struct CallFrameInfo<'a> {
+    pub kind: FrameKind,
+    /// Virtual address of the section, the base for `pcrel` pointers.
+    pub address: u64,
+    pub big_endian: bool,
+    pub address_size: u8,
+    pub cies: Vec<Cie<'a>>,
+    pub fdes: Vec<Fde<'a>>,
+    data: &'a [u8],
+    cie_index: HashMap<u64, usize>,
+}
+
+impl<'a> CallFrameInfo<'a> {
+    pub fn parse(
+        kind: FrameKind,
+        data: &'a [u8],
+        address: u64,
+        big_endian: bool,
+        address_size: u8,
+    ) -> Result<Self> {
+        let mut cfi = Self {
+            kind,
+            address,
+            big_endian,
+            address_size,
+            cies: Vec::new(),
+            fdes: Vec::new(),
+            data,
+            cie_index: HashMap::new(),
+        };
+
+        let mut offset = 0;
+        while offset < data.len() as u64 {
+            let mut reader = DwarfReader::at(data, offset, big_endian)?;
+            let (length, format_64) = reader.read_initial_length()?;
+            if length == 0 {
+                // .eh_frame ends with a zero terminator
+                if kind == FrameKind::EhFrame {
+                    break;
+                }
+                offset = reader.position();
+                continue;
+            }
+            let Some(end) = reader
+                .position()
+                .checked_add(length)
+                .filter(|&end| end <= data.len() as u64)
+            else {
+                return Err(ElfError::ParseError(format!(
+                    "{} entry at 0x{:X} exceeds section range.",
+                    kind.section_name(),
+                    offset
+                )));
+            };
+
+            let id_pos = reader.position();
+            let id = reader.read_offset(format_64)?;
+            let is_cie = match kind {
+                FrameKind::EhFrame => id == 0,
+                FrameKind::DebugFrame => id == if format_64 { u64::MAX } else { 0xFFFF_FFFF },
+            };
+            if is_cie {
+                cfi.cie_at(offset)?;
+            } else {
+                let cie_offset = match kind {
+                    FrameKind::EhFrame => id_pos.wrapping_sub(id),
+                    FrameKind::DebugFrame => id,
+                };
+                let fde = cfi.parse_fde(&mut reader, offset, id_pos, end, cie_offset)?;
+                cfi.fdes.push(fde);
+            }
+            offset = end;
+        }
+        Ok(cfi)
+    }
+
+    fn bases(&self) -> PointerBases {
+        PointerBases {
+            section: self.address,
+            data: 0,
+        }
+    }
+
+    /// Returns the index of the CIE at `offset`, parsing it on first use.
+    fn cie_at(&mut self, offset: u64) -> Result<usize> {
+        if let Some(&index) = self.cie_index.get(&offset) {
+            return Ok(index);
+        }
+        let cie = self.parse_cie(offset)?;
+        self.cies.push(cie);
+        self.cie_index.insert(offset, self.cies.len() - 1);
+        Ok(self.cies.len() - 1)
+    }
+
+    fn parse_cie(&self, offset: u64) -> Result<Cie<'a>> {
+        let mut reader = DwarfReader::at(self.data, offset, self.big_endian)?;
+        let (length, format_64) = reader.read_initial_length()?;
+        let end = reader
+            .position()
+            .checked_add(length)
+            .ok_or_else(|| {
+                ElfError::ParseError(format!("CIE at 0x{:X} exceeds section range.", offset))
+            })?
+            .min(self.data.len() as u64);
+        let length = end - reader.position();
+        reader.read_offset(format_64)?;
+
+        let version = reader.read_u8()?;
+        let augmentation = reader.read_cstr()?.to_string();
+        let mut address_size = self.address_size;
+        if augmentation.contains("eh") {
+            // old GCC stored the address of the exception table here
+            reader.skip(address_size as u64)?;
+        }
+        if version >= 4 {
+            address_size = reader.read_u8()?;
+            let _segment_selector_size = reader.read_u8()?;
+        }
+        if address_size == 0 {
+            return Err(ElfError::ParseError(format!(
+                "CIE at 0x{:X} has an address size of 0.",
+                offset
+            )));
+        }
+        let code_alignment_factor = reader.read_uleb128()?;
+        let data_alignment_factor = reader.read_sleb128()?;
+        let return_address_register = if version == 1 {
+            reader.read_u8()? as u64
+        } else {
+            reader.read_uleb128()?
+        };
+
+        let mut cie = Cie {
+            offset,
+            length,
+            version,
+            augmentation,
+            address_size,
+            code_alignment_factor,
+            data_alignment_factor,
+            return_address_register,
+            fde_encoding: DW_EH_PE_ABSPTR,
+            lsda_encoding: DW_EH_PE_OMIT,
+            personality: None,
+            signal_frame: false,
+            instructions_offset: 0,
+            initial_instructions: &[],
+        };
+
+        if cie.has_augmentation_data() {
+            let len = reader.read_uleb128()?;
+            let data_end = reader.position().checked_add(len).ok_or_else(|| {
+                ElfError::ParseError("augmentation data exceeds section range.".to_string())
+            })?;
+            for c in cie.augmentation.clone().chars().skip(1) {
+                match c {
+                    'L' => cie.lsda_encoding = reader.read_u8()?,
+                    'R' => cie.fde_encoding = reader.read_u8()?,
+                    'P' => {
+                        let encoding = reader.read_u8()?;
+                        cie.personality =
+                            read_encoded(&mut reader, encoding, address_size, self.bases())?;
+                    }
+                    'S' => cie.signal_frame = true,
+                    // BTI and MTE markers carry no data
+                    'B' | 'G' => {}
+                    _ => break,
+                }
+            }
+            reader.set_position(data_end);
+        }
+
+        cie.instructions_offset = reader.position();
+        cie.initial_instructions = self
+            .data
+            .get(reader.position() as usize..end as usize)
+            .unwrap_or(&[]);
+        Ok(cie)
+    }
+
+    fn parse_fde(
+        &mut self,
+        reader: &mut DwarfReader<'a>,
+        offset: u64,
+        id_pos: u64,
+        end: u64,
+        cie_offset: u64,
+    ) -> Result<Fde<'a>> {
+        let cie_index = self.cie_at(cie_offset).map_err(|_| {
+            ElfError::ParseError(format!(
+                "FDE at 0x{:X} refers to an invalid CIE at 0x{:X}.",
+                offset, cie_offset
+            ))
+        })?;
+        let bases = self.bases();
+        let cie = &self.cies[cie_index];
+
+        let pc_begin =
+            read_encoded(reader, cie.fde_encoding, cie.address_size, bases)?.unwrap_or(0);
+        // the range is an absolute value in the same format
+        let pc_range =
+            read_encoded(reader, cie.fde_encoding & 0x0F, cie.address_size, bases)?.unwrap_or(0);
+        let mut lsda = None;
+        if cie.has_augmentation_data() {
+            let len = reader.read_uleb128()?;
+            let data_end = reader.position().checked_add(len).ok_or_else(|| {
+                ElfError::ParseError("augmentation data exceeds section range.".to_string())
+            })?;
+            if len > 0 {
+                lsda = read_encoded(reader, cie.lsda_encoding, cie.address_size, bases)?;
+            }
+            reader.set_position(data_end);
+        }
+
+        Ok(Fde {
+            offset,
+            length: end - id_pos,
+            cie_offset,
+            pc_begin,
+            pc_range,
+            lsda,
+            instructions_offset: reader.position(),
+            instructions: self
+                .data
+                .get(reader.position() as usize..end as usize)
+                .unwrap_or(&[]),
+        })
+    }
+
+    /// Returns the CIE an FDE belongs to.
+    pub fn cie(&self, fde: &Fde<'_>) -> Option<&Cie<'a>> {
+        self.cie_index.get(&fde.cie_offset).map(|&i| &self.cies[i])
+    }
+
+    /// Returns the FDE whose range contains `address`.
+    pub fn find_fde(&self, address: u64) -> Option<&Fde<'a>> {
+        self.fdes
+            .iter()
+            .find(|fde| fde.pc_begin <= address && address < fde.pc_end())
+    }
+
+    /// Decodes the instructions stored at `offset` in the section.
+    pub fn instructions(
+        &self,
+        cie: &Cie<'a>,
+        offset: u64,
+        bytes: &[u8],
+    ) -> Result<Vec<CallFrameInstruction<'a>>> {
+        let mut reader = DwarfReader::at(self.data, offset, self.big_endian)?;
+        let end = offset + bytes.len() as u64;
+        let code_align = cie.code_alignment_factor;
+        let data_align = cie.data_alignment_factor;
+        let factored = |v: i64| v.wrapping_mul(data_align);
+        let advance = |delta: u64| {
+            delta
+                .checked_mul(code_align)
+                .map(CallFrameInstruction::AdvanceLoc)
+                .ok_or_else(|| {
+                    ElfError::ParseError(format!(
+                        "advance of {} by the code alignment factor {} overflows.",
+                        delta, code_align
+                    ))
+                })
+        };
+
+        let mut insts = Vec::new();
+        while reader.position() < end {
+            let opcode = reader.read_u8()?;
+            let low = (opcode & 0x3F) as u64;
+            let inst = match opcode & 0xC0 {
+                DW_CFA_ADVANCE_LOC => advance(low)?,
+                DW_CFA_OFFSET => CallFrameInstruction::Offset {
+                    register: low,
+                    offset: factored(reader.read_uleb128()? as i64),
+                },
+                DW_CFA_RESTORE => CallFrameInstruction::Restore(low),
+                _ => match opcode {
+                    DW_CFA_NOP => CallFrameInstruction::Nop,
+                    DW_CFA_SET_LOC => {
+                        let encoding = match self.kind {
+                            FrameKind::EhFrame => cie.fde_encoding,
+                            FrameKind::DebugFrame => DW_EH_PE_ABSPTR,
+                        };
+                        let loc =
+                            read_encoded(&mut reader, encoding, cie.address_size, self.bases())?;
+                        CallFrameInstruction::SetLoc(loc.unwrap_or(0))
+                    }
+                    DW_CFA_ADVANCE_LOC1 => advance(reader.read_u8()? as u64)?,
+                    DW_CFA_ADVANCE_LOC2 => advance(reader.read_u16()? as u64)?,
+                    DW_CFA_ADVANCE_LOC4 => advance(reader.read_u32()? as u64)?,
+                    DW_CFA_OFFSET_EXTENDED => CallFrameInstruction::Offset {
+                        register: reader.read_uleb128()?,
+                        offset: factored(reader.read_uleb128()? as i64),
+                    },
+                    DW_CFA_OFFSET_EXTENDED_SF => CallFrameInstruction::Offset {
+                        register: reader.read_uleb128()?,
+                        offset: factored(reader.read_sleb128()?),
+                    },
+                    DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED => CallFrameInstruction::Offset {
+                        register: reader.read_uleb128()?,
+                        offset: -factored(reader.read_uleb128()? as i64),
+                    },
+                    DW_CFA_RESTORE_EXTENDED => {
+                        CallFrameInstruction::Restore(reader.read_uleb128()?)
+                    }
+                    DW_CFA_UNDEFINED => CallFrameInstruction::Undefined(reader.read_uleb128()?),
+                    DW_CFA_SAME_VALUE => CallFrameInstruction::SameValue(reader.read_uleb128()?),
+                    DW_CFA_REGISTER => CallFrameInstruction::Register {
+                        register: reader.read_uleb128()?,
+                        target: reader.read_uleb128()?,
+                    },
+                    DW_CFA_REMEMBER_STATE => CallFrameInstruction::RememberState,
+                    DW_CFA_RESTORE_STATE => CallFrameInstruction::RestoreState,
+                    DW_CFA_DEF_CFA => CallFrameInstruction::DefCfa {
+                        register: reader.read_uleb128()?,
+                        offset: reader.read_uleb128()? as i64,
+                    },
+                    DW_CFA_DEF_CFA_SF => CallFrameInstruction::DefCfa {
+                        register: reader.read_uleb128()?,
+                        offset: factored(reader.read_sleb128()?),
+                    },
+                    DW_CFA_DEF_CFA_REGISTER => {
+                        CallFrameInstruction::DefCfaRegister(reader.read_uleb128()?)
+                    }
+                    DW_CFA_DEF_CFA_OFFSET => {
+                        CallFrameInstruction::DefCfaOffset(reader.read_uleb128()? as i64)
+                    }
+                    DW_CFA_DEF_CFA_OFFSET_SF => {
+                        CallFrameInstruction::DefCfaOffset(factored(reader.read_sleb128()?))
+                    }
+                    DW_CFA_DEF_CFA_EXPRESSION => {
+                        let len = reader.read_uleb128()?;
+                        CallFrameInstruction::DefCfaExpression(reader.read_bytes(len)?)
+                    }
+                    DW_CFA_EXPRESSION | DW_CFA_VAL_EXPRESSION => {
+                        let register = reader.read_uleb128()?;
+                        let len = reader.read_uleb128()?;
+                        let expr = reader.read_bytes(len)?;
+                        if opcode == DW_CFA_EXPRESSION {
+                            CallFrameInstruction::Expression { register, expr }
+                        } else {
+                            CallFrameInstruction::ValExpression { register, expr }
+                        }
+                    }
+                    DW_CFA_VAL_OFFSET => CallFrameInstruction::ValOffset {
+                        register: reader.read_uleb128()?,
+                        offset: factored(reader.read_uleb128()? as i64),
+                    },
+                    DW_CFA_VAL_OFFSET_SF => CallFrameInstruction::ValOffset {
+                        register: reader.read_uleb128()?,
+                        offset: factored(reader.read_sleb128()?),
+                    },
+                    DW_CFA_GNU_ARGS_SIZE => CallFrameInstruction::ArgsSize(reader.read_uleb128()?),
+                    DW_CFA_GNU_WINDOW_SAVE => CallFrameInstruction::WindowSave,
+                    _ => {
+                        return Err(ElfError::ParseError(format!(
+                            "unknown call frame instruction 0x{:X} at 0x{:X}.",
+                            opcode,
+                            reader.position() - 1
+                        )));
+                    }
+                },
+            };
+            insts.push(inst);
+        }
+        Ok(insts)
+    }
+
+    /// Executes the CIE's initial instructions and the FDE's instructions
+    /// and returns the resulting unwind table.
+    pub fn unwind_rows(&self, fde: &Fde<'a>) -> Result<Vec<UnwindRow<'a>>> {
+        let cie = self.cie(fde).ok_or_else(|| {
+            ElfError::ParseError(format!("FDE at 0x{:X} has no CIE.", fde.offset))
+        })?;
+        let initial = self.instructions(cie, cie.instructions_offset, cie.initial_instructions)?;
+        let insts = self.instructions(cie, fde.instructions_offset, fde.instructions)?;
+
+        let mut row = UnwindRow {
+            address: fde.pc_begin,
+            cfa: CfaRule::RegisterOffset {
+                register: 0,
+                offset: 0,
+            },
+            registers: BTreeMap::new(),
+        };
+        let mut rows = Vec::new();
+        let mut stack = Vec::new();
+        let empty = row.clone();
+        for inst in &initial {
+            execute(&mut row, inst, &empty, &mut stack, &mut rows);
+        }
+        let initial_row = row.clone();
+        for inst in &insts {
+            if row.address >= fde.pc_end() {
+                break;
+            }
+            execute(&mut row, inst, &initial_row, &mut stack, &mut rows);
+        }
+        if row.address < fde.pc_end() {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+}
+
+type SavedState<'a> = (CfaRule<'a>, BTreeMap<u64, RegisterRule<'a>>);
+
+fn execute<'a>(
+    row: &mut UnwindRow<'a>,
+    inst: &CallFrameInstruction<'a>,
+    initial: &UnwindRow<'a>,
+    stack: &mut Vec<SavedState<'a>>,
+    rows: &mut Vec<UnwindRow<'a>>,
+) {
+    let mut advance_to = |row: &mut UnwindRow<'a>, address: u64| {
+        if address > row.address {
+            rows.push(row.clone());
+        }
+        row.address = address;
+    };
+
+    match *inst {
+        CallFrameInstruction::SetLoc(address) => advance_to(row, address),
+        CallFrameInstruction::AdvanceLoc(delta) => {
+            let address = row.address.wrapping_add(delta);
+            advance_to(row, address);
+        }
+        CallFrameInstruction::DefCfa { register, offset } => {
+            row.cfa = CfaRule::RegisterOffset { register, offset };
+        }
+        CallFrameInstruction::DefCfaRegister(register) => {
+            if let CfaRule::RegisterOffset { offset, .. } = row.cfa {
+                row.cfa = CfaRule::RegisterOffset { register, offset };
+            }
+        }
+        CallFrameInstruction::DefCfaOffset(offset) => {
+            if let CfaRule::RegisterOffset { register, .. } = row.cfa {
+                row.cfa = CfaRule::RegisterOffset { register, offset };
+            }
+        }
+        CallFrameInstruction::DefCfaExpression(expr) => row.cfa = CfaRule::Expression(expr),
+        CallFrameInstruction::Undefined(register) => {
+            row.registers.insert(register, RegisterRule::Undefined);
+        }
+        CallFrameInstruction::SameValue(register) => {
+            row.registers.insert(register, RegisterRule::SameValue);
+        }
+        CallFrameInstruction::Offset { register, offset } => {
+            row.registers.insert(register, RegisterRule::Offset(offset));
+        }
+        CallFrameInstruction::ValOffset { register, offset } => {
+            row.registers
+                .insert(register, RegisterRule::ValOffset(offset));
+        }
+        CallFrameInstruction::Register { register, target } => {
+            row.registers
+                .insert(register, RegisterRule::Register(target));
+        }
+        CallFrameInstruction::Expression { register, expr } => {
+            row.registers
+                .insert(register, RegisterRule::Expression(expr));
+        }
+        CallFrameInstruction::ValExpression { register, expr } => {
+            row.registers
+                .insert(register, RegisterRule::ValExpression(expr));
+        }
+        CallFrameInstruction::Restore(register) => match initial.registers.get(&register) {
+            Some(&rule) => {
+                row.registers.insert(register, rule);
+            }
+            None => {
+                row.registers.remove(&register);
+            }
+        },
+        CallFrameInstruction::RememberState => stack.push((row.cfa, row.registers.clone())),
+        CallFrameInstruction::RestoreState => {
+            if let Some((cfa, registers)) = stack.pop() {
+                row.cfa = cfa;
+                row.registers = registers;
+            }
+        }
+        CallFrameInstruction::ArgsSize(_)
+        | CallFrameInstruction::WindowSave
+        | CallFrameInstruction::Nop => {}
+    }
+}
+
+/// The `.eh_frame_hdr` binary search table.
+#[derive(Debug, Clone)]
+pub struct EhFrameHdr {
+    /// Virtual address of the header.
+    pub address: u64,
+    pub version: u8,
+    pub eh_frame_ptr_encoding: u8,
+    pub fde_count_encoding: u8,
+    pub table_encoding: u8,
+    pub eh_frame_ptr: u64,
+    pub fde_count: u64,
+    /// `(initial location, FDE address)` pairs.
+    pub table: Vec<(u64, u64)>,
+}
+
+impl EhFrameHdr {
+    pub fn parse(data: &[u8], address: u64, big_endian: bool, address_size: u8) -> Result<Self> {
+        let mut reader = DwarfReader::new(data, big_endian);
+        let bases = PointerBases {
+            section: address,
+            data: address,
+        };
+        let version = reader.read_u8()?;
+        if version != 1 {
+            return Err(ElfError::ParseError(format!(
+                "unsupported .eh_frame_hdr version {}.",
+                version
+            )));
+        }
+        let eh_frame_ptr_encoding = reader.read_u8()?;
+        let fde_count_encoding = reader.read_u8()?;
+        let table_encoding = reader.read_u8()?;
+        let eh_frame_ptr =
+            read_encoded(&mut reader, eh_frame_ptr_encoding, address_size, bases)?.unwrap_or(0);
+        let fde_count =
+            read_encoded(&mut reader, fde_count_encoding, address_size, bases)?.unwrap_or(0);
+
+        let mut table = Vec::new();
+        if table_encoding != DW_EH_PE_OMIT {
+            for _ in 0..fde_count {
+                let loc = read_encoded(&mut reader, table_encoding, address_size, bases)?;
+                let fde = read_encoded(&mut reader, table_encoding, address_size, bases)?;
+                table.push((loc.unwrap_or(0), fde.unwrap_or(0)));
+            }
+        }
+
+        Ok(Self {
+            address,
+            version,
+            eh_frame_ptr_encoding,
+            fde_count_encoding,
+            table_encoding,
+            eh_frame_ptr,
+            fde_count,
+            table,
+        })
+    }
+
+    /// Checks the table against `.eh_frame` and returns the problems found.
+    pub fn verify(&self, eh_frame: &CallFrameInfo<'_>) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.eh_frame_ptr != eh_frame.address {
+            problems.push(format!(
+                "eh_frame_ptr 0x{:X} does not match .eh_frame at 0x{:X}",
+                self.eh_frame_ptr, eh_frame.address
+            ));
+        }
+        if self.fde_count != eh_frame.fdes.len() as u64 {
+            problems.push(format!(
+                "table has {} entries but .eh_frame has {} FDEs",
+                self.fde_count,
+                eh_frame.fdes.len()
+            ));
+        }
+
+        let fdes: HashMap<u64, &Fde<'_>> = eh_frame
+            .fdes
+            .iter()
+            .map(|fde| (eh_frame.address.wrapping_add(fde.offset), fde))
+            .collect();
+        for (i, &(loc, fde_address)) in self.table.iter().enumerate() {
+            if i > 0 && self.table[i - 1].0 >= loc {
+                problems.push(format!("entry {} at 0x{:X} is out of order", i, loc));
+            }
+            match fdes.get(&fde_address) {
+                None => problems.push(format!(
+                    "entry {} points to 0x{:X}, which is not an FDE",
+                    i, fde_address
+                )),
+                Some(fde) if fde.pc_begin != loc => problems.push(format!(
+                    "entry {} has location 0x{:X} but its FDE starts at 0x{:X}",
+                    i, loc, fde.pc_begin
+                )),
+                Some(_) => {}
+            }
+        }
+        problems
+    }
+}
+
+impl ElfFile {
+    fn frame_section(&self, kind: FrameKind) -> Result<Option<CallFrameInfo<'_>>> {
+        let index = match self.find_section(kind.section_name()) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let data = match self.try_get_section_data(index)? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        let address = self.section_headers[index].sh_addr();
+        let address_size = if self.is_64() { 8 } else { 4 };
+        CallFrameInfo::parse(kind, data, address, self.is_big_endian(), address_size).map(Some)
+    }
+
+    pub fn get_eh_frame(&self) -> Result<Option<CallFrameInfo<'_>>> {
+        self.frame_section(FrameKind::EhFrame)
+    }
+
+    pub fn get_debug_frame(&self) -> Result<Option<CallFrameInfo<'_>>> {
+        self.frame_section(FrameKind::DebugFrame)
+    }
+
+    /// Reads `.eh_frame_hdr`, located through the `PT_GNU_EH_FRAME` segment
+    /// or, without program headers, by section name.
+    pub fn get_eh_frame_hdr(&self) -> Result<Option<EhFrameHdr>> {
+        let address_size = if self.is_64() { 8 } else { 4 };
+        let segment = self
+            .program_headers
+            .iter()
+            .find(|ph| ph.get_type() == SegmentType::GnuEhFrame);
+        let (data, address) = match segment {
+            Some(ph) => {
+                let start = ph.p_offset() as usize;
+                let data = start
+                    .checked_add(ph.p_filesz() as usize)
+                    .and_then(|end| self.data.get(start..end))
+                    .ok_or_else(|| {
+                        ElfError::ParseError("PT_GNU_EH_FRAME exceeds file range.".to_string())
+                    })?;
+                (data, ph.p_vaddr())
+            }
+            None => match self.find_section(".eh_frame_hdr") {
+                Some(index) => match self.get_section_data(index) {
+                    Some(data) => (data, self.section_headers[index].sh_addr()),
+                    None => return Ok(None),
+                },
+                None => return Ok(None),
+            },
+        };
+        EhFrameHdr::parse(data, address, self.is_big_endian(), address_size).map(Some)
+    }
+}
@@ -0,0 +1,195 @@
+//! Human-readable rendering of DIE tags, attributes and values.
+
+use super::DwarfSections;
+use super::constants::*;
+use super::expr::{describe_expression, hex_bytes};
+use super::form::AttrValue;
+use super::info::{Attribute, Unit};
+use super::line::LineProgramHeader;
+
+/// Returns the `DW_TAG_*` name of a tag, or its raw value if unknown.
+pub fn display_tag(tag: u64) -> String {
+    match tag_name(tag) {
+        Some(name) => name.to_string(),
+        None => format!("DW_TAG_<0x{:X}>", tag),
+    }
+}
+
+/// Returns the `DW_AT_*` name of an attribute, or its raw value if unknown.
+pub fn display_attr(attr: u64) -> String {
+    match attr_name(attr) {
+        Some(name) => name.to_string(),
+        None => format!("DW_AT_<0x{:X}>", attr),
+    }
+}
+
+/// Returns the `DW_FORM_*` name of a form, or its raw value if unknown.
+pub fn display_form(form: u64) -> String {
+    match form_name(form) {
+        Some(name) => name.to_string(),
+        None => format!("DW_FORM_<0x{:X}>", form),
+    }
+}
+
+/// Attributes whose value is a DWARF expression or a location list.
+fn is_location_attr(name: u64) -> bool {
+    matches!(
+        name,
+        DW_AT_LOCATION
+            | DW_AT_STRING_LENGTH
+            | DW_AT_RETURN_ADDR
+            | DW_AT_DATA_MEMBER_LOCATION
+            | DW_AT_FRAME_BASE
+            | DW_AT_SEGMENT
+            | DW_AT_STATIC_LINK
+            | DW_AT_USE_LOCATION
+            | DW_AT_VTABLE_ELEM_LOCATION
+            | DW_AT_DATA_LOCATION
+            | DW_AT_CALL_VALUE
+            | DW_AT_CALL_DATA_LOCATION
+            | DW_AT_CALL_DATA_VALUE
+            | DW_AT_CALL_TARGET
+            | DW_AT_CALL_TARGET_CLOBBERED
+            | DW_AT_GNU_CALL_SITE_VALUE
+            | DW_AT_GNU_CALL_SITE_TARGET
+    )
+}
+
+fn with_name(value: u64, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("{} ({})", value, name),
+        None => value.to_string(),
+    }
+}
+
+impl<'a> DwarfSections<'a> {
+    /// Renders an attribute value for display, resolving strings, indices,
+    /// references, range and location lists. `line` is the unit's line
+    /// program header, used to name `DW_AT_decl_file` and `DW_AT_call_file`.
+    pub fn describe_attr(
+        &self,
+        unit: &Unit,
+        attr: &Attribute<'a>,
+        line: Option<&LineProgramHeader>,
+    ) -> String {
+        let encoding = unit.encoding;
+        match (attr.name, attr.value) {
+            (DW_AT_LANGUAGE, v) if v.udata().is_some() => {
+                let lang = v.udata().unwrap_or(0);
+                with_name(lang, lang_name(lang))
+            }
+            (DW_AT_ENCODING, v) if v.udata().is_some() => {
+                let ate = v.udata().unwrap_or(0);
+                with_name(ate, encoding_name(ate))
+            }
+            (DW_AT_INLINE, v) if v.udata().is_some() => {
+                let inline = v.udata().unwrap_or(0);
+                with_name(inline, inline_name(inline))
+            }
+            (DW_AT_ACCESSIBILITY, v) if v.udata().is_some() => {
+                let access = v.udata().unwrap_or(0);
+                with_name(access, accessibility_name(access))
+            }
+            (DW_AT_VIRTUALITY, v) if v.udata().is_some() => {
+                let virtuality = v.udata().unwrap_or(0);
+                with_name(virtuality, virtuality_name(virtuality))
+            }
+            (DW_AT_DECL_FILE | DW_AT_CALL_FILE, v) if v.udata().is_some() => {
+                let file = v.udata().unwrap_or(0);
+                let path = line.and_then(|h| h.file_path(file, unit.comp_dir.as_deref()));
+                with_name(file, path.as_deref())
+            }
+            (DW_AT_HIGH_PC, AttrValue::Udata(len)) => format!("0x{:X} (length)", len),
+            (DW_AT_RANGES, v) => {
+                let offset = match v {
+                    AttrValue::RnglistIndex(index) => format!("index {}", index),
+                    v => format!("0x{:X}", v.offset().unwrap_or(0)),
+                };
+                match self.ranges(unit, v) {
+                    Ok(ranges) => {
+                        let ranges: Vec<String> = ranges
+                            .iter()
+                            .map(|(start, end)| format!("[0x{:X}, 0x{:X})", start, end))
+                            .collect();
+                        format!("{}: {}", offset, ranges.join(" "))
+                    }
+                    Err(e) => format!("{}: <{}>", offset, e),
+                }
+            }
+            (name, AttrValue::Exprloc(expr)) | (name, AttrValue::Block(expr))
+                if is_location_attr(name) =>
+            {
+                format!("({})", describe_expression(expr, encoding, self.big_endian))
+            }
+            (name, v)
+                if is_location_attr(name)
+                    && (matches!(v, AttrValue::SecOffset(_) | AttrValue::LoclistIndex(_))
+                        || (encoding.version < 4
+                            && matches!(attr.form, DW_FORM_DATA4 | DW_FORM_DATA8))) =>
+            {
+                self.describe_locations(unit, v)
+            }
+            (_, v) => self.describe_value(unit, v),
+        }
+    }
+
+    fn describe_locations(&self, unit: &Unit, value: AttrValue<'a>) -> String {
+        let offset = match value {
+            AttrValue::LoclistIndex(index) => format!("location list index {}", index),
+            v => format!("location list 0x{:X}", v.offset().unwrap_or(0)),
+        };
+        match self.locations(unit, value) {
+            Ok(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|entry| {
+                        let expr = describe_expression(entry.expr, unit.encoding, self.big_endian);
+                        match entry.range {
+                            Some((start, end)) => {
+                                format!("[0x{:X}, 0x{:X}) ({})", start, end, expr)
+                            }
+                            None => format!("default ({})", expr),
+                        }
+                    })
+                    .collect();
+                format!("{}: {}", offset, entries.join("; "))
+            }
+            Err(e) => format!("{}: <{}>", offset, e),
+        }
+    }
+
+    /// Renders an attribute value by form class alone.
+    pub fn describe_value(&self, unit: &Unit, value: AttrValue<'a>) -> String {
+        match value {
+            AttrValue::Address(addr) => format!("0x{:X}", addr),
+            AttrValue::AddressIndex(index) => match self.indexed_address(unit, index) {
+                Ok(addr) => format!("0x{:X} (index {})", addr, index),
+                Err(_) => format!("<address index {}>", index),
+            },
+            AttrValue::Block(bytes) => format!("{} byte block: {}", bytes.len(), hex_bytes(bytes)),
+            AttrValue::Exprloc(expr) => format!(
+                "({})",
+                describe_expression(expr, unit.encoding, self.big_endian)
+            ),
+            AttrValue::Udata(v) => v.to_string(),
+            AttrValue::Sdata(v) => v.to_string(),
+            AttrValue::Data16(bytes) => hex_bytes(bytes),
+            AttrValue::Flag(flag) => flag.to_string(),
+            AttrValue::String(_)
+            | AttrValue::StrOffset(_)
+            | AttrValue::LineStrOffset(_)
+            | AttrValue::StrIndex(_) => match self.attr_string(unit, value) {
+                Some(s) => format!("\"{}\"", s),
+                None => "<invalid string>".to_string(),
+            },
+            AttrValue::SupStrOffset(off) => format!("<alt string 0x{:X}>", off),
+            AttrValue::UnitRef(off) => format!("<0x{:X}>", unit.offset + off),
+            AttrValue::InfoRef(off) => format!("<0x{:X}>", off),
+            AttrValue::SupRef(off) => format!("<alt 0x{:X}>", off),
+            AttrValue::TypeSig(sig) => format!("signature 0x{:016X}", sig),
+            AttrValue::SecOffset(off) => format!("0x{:X}", off),
+            AttrValue::LoclistIndex(index) => format!("location list index {}", index),
+            AttrValue::RnglistIndex(index) => format!("range list index {}", index),
+        }
+    }
+}
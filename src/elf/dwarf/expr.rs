@@ -0,0 +1,216 @@
+//! DWARF expression (`DW_OP_*`) decoding for display.
+
+use super::{DwarfReader, Encoding};
+use crate::elf::Result;
+
+/// Decodes a DWARF expression into a readable operation list such as
+/// `DW_OP_fbreg: -20; DW_OP_deref`. Decoding stops at the first unknown
+/// opcode, which is shown together with the remaining raw bytes.
+pub fn describe_expression(expr: &[u8], encoding: Encoding, big_endian: bool) -> String {
+    let mut reader = DwarfReader::new(expr, big_endian);
+    let mut ops = Vec::new();
+    while !reader.is_empty() {
+        let start = reader.position() as usize;
+        match describe_op(&mut reader, encoding) {
+            Ok(Some(op)) => ops.push(op),
+            Ok(None) => {
+                ops.push(format!("DW_OP_<0x{:X}>", expr[start]));
+                if start + 1 < expr.len() {
+                    ops.push(format!("[{}]", hex_bytes(&expr[start + 1..])));
+                }
+                break;
+            }
+            Err(_) => {
+                ops.push(format!("<truncated: {}>", hex_bytes(&expr[start..])));
+                break;
+            }
+        }
+    }
+    ops.join("; ")
+}
+
+fn describe_op(reader: &mut DwarfReader<'_>, encoding: Encoding) -> Result<Option<String>> {
+    let opcode = reader.read_u8()?;
+    let op = match opcode {
+        0x03 => format!(
+            "DW_OP_addr: 0x{:X}",
+            reader.read_address(encoding.address_size)?
+        ),
+        0x06 => "DW_OP_deref".to_string(),
+        0x08 => format!("DW_OP_const1u: {}", reader.read_u8()?),
+        0x09 => format!("DW_OP_const1s: {}", reader.read_i8()?),
+        0x0A => format!("DW_OP_const2u: {}", reader.read_u16()?),
+        0x0B => format!("DW_OP_const2s: {}", reader.read_u16()? as i16),
+        0x0C => format!("DW_OP_const4u: {}", reader.read_u32()?),
+        0x0D => format!("DW_OP_const4s: {}", reader.read_u32()? as i32),
+        0x0E => format!("DW_OP_const8u: {}", reader.read_u64()?),
+        0x0F => format!("DW_OP_const8s: {}", reader.read_u64()? as i64),
+        0x10 => format!("DW_OP_constu: {}", reader.read_uleb128()?),
+        0x11 => format!("DW_OP_consts: {}", reader.read_sleb128()?),
+        0x12 => "DW_OP_dup".to_string(),
+        0x13 => "DW_OP_drop".to_string(),
+        0x14 => "DW_OP_over".to_string(),
+        0x15 => format!("DW_OP_pick: {}", reader.read_u8()?),
+        0x16 => "DW_OP_swap".to_string(),
+        0x17 => "DW_OP_rot".to_string(),
+        0x18 => "DW_OP_xderef".to_string(),
+        0x19 => "DW_OP_abs".to_string(),
+        0x1A => "DW_OP_and".to_string(),
+        0x1B => "DW_OP_div".to_string(),
+        0x1C => "DW_OP_minus".to_string(),
+        0x1D => "DW_OP_mod".to_string(),
+        0x1E => "DW_OP_mul".to_string(),
+        0x1F => "DW_OP_neg".to_string(),
+        0x20 => "DW_OP_not".to_string(),
+        0x21 => "DW_OP_or".to_string(),
+        0x22 => "DW_OP_plus".to_string(),
+        0x23 => format!("DW_OP_plus_uconst: {}", reader.read_uleb128()?),
+        0x24 => "DW_OP_shl".to_string(),
+        0x25 => "DW_OP_shr".to_string(),
+        0x26 => "DW_OP_shra".to_string(),
+        0x27 => "DW_OP_xor".to_string(),
+        0x28 => format!("DW_OP_bra: {}", reader.read_u16()? as i16),
+        0x29 => "DW_OP_eq".to_string(),
+        0x2A => "DW_OP_ge".to_string(),
+        0x2B => "DW_OP_gt".to_string(),
+        0x2C => "DW_OP_le".to_string(),
+        0x2D => "DW_OP_lt".to_string(),
+        0x2E => "DW_OP_ne".to_string(),
+        0x2F => format!("DW_OP_skip: {}", reader.read_u16()? as i16),
+        0x30..=0x4F => format!("DW_OP_lit{}", opcode - 0x30),
+        0x50..=0x6F => format!("DW_OP_reg{}", opcode - 0x50),
+        0x70..=0x8F => format!("DW_OP_breg{}: {}", opcode - 0x70, reader.read_sleb128()?),
+        0x90 => format!("DW_OP_regx: {}", reader.read_uleb128()?),
+        0x91 => format!("DW_OP_fbreg: {}", reader.read_sleb128()?),
+        0x92 => {
+            let reg = reader.read_uleb128()?;
+            format!("DW_OP_bregx: {} {}", reg, reader.read_sleb128()?)
+        }
+        0x93 => format!("DW_OP_piece: {}", reader.read_uleb128()?),
+        0x94 => format!("DW_OP_deref_size: {}", reader.read_u8()?),
+        0x95 => format!("DW_OP_xderef_size: {}", reader.read_u8()?),
+        0x96 => "DW_OP_nop".to_string(),
+        0x97 => "DW_OP_push_object_address".to_string(),
+        0x98 => format!("DW_OP_call2: <0x{:X}>", reader.read_u16()?),
+        0x99 => format!("DW_OP_call4: <0x{:X}>", reader.read_u32()?),
+        0x9A => format!(
+            "DW_OP_call_ref: <0x{:X}>",
+            reader.read_offset(encoding.format_64)?
+        ),
+        0x9B => "DW_OP_form_tls_address".to_string(),
+        0x9C => "DW_OP_call_frame_cfa".to_string(),
+        0x9D => {
+            let size = reader.read_uleb128()?;
+            format!(
+                "DW_OP_bit_piece: size {} offset {}",
+                size,
+                reader.read_uleb128()?
+            )
+        }
+        0x9E => {
+            let len = reader.read_uleb128()?;
+            format!(
+                "DW_OP_implicit_value: [{}]",
+                hex_bytes(reader.read_bytes(len)?)
+            )
+        }
+        0x9F => "DW_OP_stack_value".to_string(),
+        0xA0 | 0xF2 => {
+            let name = if opcode == 0xA0 {
+                "DW_OP_implicit_pointer"
+            } else {
+                "DW_OP_GNU_implicit_pointer"
+            };
+            let die = reader.read_offset(encoding.format_64)?;
+            format!("{}: <0x{:X}> {}", name, die, reader.read_sleb128()?)
+        }
+        0xA1 => format!("DW_OP_addrx: {}", reader.read_uleb128()?),
+        0xA2 => format!("DW_OP_constx: {}", reader.read_uleb128()?),
+        0xA3 | 0xF3 => {
+            let name = if opcode == 0xA3 {
+                "DW_OP_entry_value"
+            } else {
+                "DW_OP_GNU_entry_value"
+            };
+            let len = reader.read_uleb128()?;
+            let inner = reader.read_bytes(len)?;
+            format!(
+                "{}: ({})",
+                name,
+                describe_expression(inner, encoding, reader.big_endian())
+            )
+        }
+        0xA4 | 0xF4 => {
+            let name = if opcode == 0xA4 {
+                "DW_OP_const_type"
+            } else {
+                "DW_OP_GNU_const_type"
+            };
+            let die = reader.read_uleb128()?;
+            let len = reader.read_u8()? as u64;
+            format!(
+                "{}: <0x{:X}> [{}]",
+                name,
+                die,
+                hex_bytes(reader.read_bytes(len)?)
+            )
+        }
+        0xA5 | 0xF5 => {
+            let name = if opcode == 0xA5 {
+                "DW_OP_regval_type"
+            } else {
+                "DW_OP_GNU_regval_type"
+            };
+            let reg = reader.read_uleb128()?;
+            format!("{}: {} <0x{:X}>", name, reg, reader.read_uleb128()?)
+        }
+        0xA6 | 0xF6 => {
+            let name = if opcode == 0xA6 {
+                "DW_OP_deref_type"
+            } else {
+                "DW_OP_GNU_deref_type"
+            };
+            let size = reader.read_u8()?;
+            format!("{}: {} <0x{:X}>", name, size, reader.read_uleb128()?)
+        }
+        0xA7 => {
+            let size = reader.read_u8()?;
+            format!(
+                "DW_OP_xderef_type: {} <0x{:X}>",
+                size,
+                reader.read_uleb128()?
+            )
+        }
+        0xA8 | 0xF7 => {
+            let name = if opcode == 0xA8 {
+                "DW_OP_convert"
+            } else {
+                "DW_OP_GNU_convert"
+            };
+            format!("{}: <0x{:X}>", name, reader.read_uleb128()?)
+        }
+        0xA9 | 0xF9 => {
+            let name = if opcode == 0xA9 {
+                "DW_OP_reinterpret"
+            } else {
+                "DW_OP_GNU_reinterpret"
+            };
+            format!("{}: <0x{:X}>", name, reader.read_uleb128()?)
+        }
+        0xE0 => "DW_OP_GNU_push_tls_address".to_string(),
+        0xF0 => "DW_OP_GNU_uninit".to_string(),
+        0xFA => format!("DW_OP_GNU_parameter_ref: <0x{:X}>", reader.read_u32()?),
+        0xFB => format!("DW_OP_GNU_addr_index: {}", reader.read_uleb128()?),
+        0xFC => format!("DW_OP_GNU_const_index: {}", reader.read_uleb128()?),
+        _ => return Ok(None),
+    };
+    Ok(Some(op))
+}
+
+pub(crate) fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,151 @@
+use super::constants::*;
+use super::{DwarfReader, Encoding};
+use crate::elf::Result;
+use crate::elf::error::ElfError;
+
+/// A decoded attribute value. References and string/address indices are kept
+/// raw; resolving them needs the unit and the other DWARF sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrValue<'a> {
+    Address(u64),
+    AddressIndex(u64),
+    Block(&'a [u8]),
+    Exprloc(&'a [u8]),
+    Udata(u64),
+    Sdata(i64),
+    Data16(&'a [u8]),
+    Flag(bool),
+    String(&'a str),
+    /// Offset into `.debug_str` (`DW_FORM_strp`).
+    StrOffset(u64),
+    /// Offset into `.debug_line_str` (`DW_FORM_line_strp`).
+    LineStrOffset(u64),
+    /// Index into the unit's `.debug_str_offsets` contribution.
+    StrIndex(u64),
+    /// Offset into the supplementary or alternate string section.
+    SupStrOffset(u64),
+    /// Offset relative to the start of the current unit.
+    UnitRef(u64),
+    /// Offset from the start of `.debug_info` (`DW_FORM_ref_addr`).
+    InfoRef(u64),
+    /// Reference into the supplementary or alternate object file.
+    SupRef(u64),
+    TypeSig(u64),
+    SecOffset(u64),
+    LoclistIndex(u64),
+    RnglistIndex(u64),
+}
+
+impl AttrValue<'_> {
+    /// Returns the value as an unsigned constant.
+    pub fn udata(&self) -> Option<u64> {
+        match *self {
+            AttrValue::Udata(v) => Some(v),
+            AttrValue::Sdata(v) if v >= 0 => Some(v as u64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a section offset. DWARF 2 and 3 encode offsets
+    /// with the plain `data4`/`data8` forms.
+    pub fn offset(&self) -> Option<u64> {
+        match *self {
+            AttrValue::SecOffset(v) | AttrValue::Udata(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Reads one attribute value encoded with `form`. `implicit_const` is the
+/// value stored in the abbreviation for `DW_FORM_implicit_const`.
+pub fn read_form<'a>(
+    reader: &mut DwarfReader<'a>,
+    form: u64,
+    encoding: Encoding,
+    implicit_const: i64,
+) -> Result<AttrValue<'a>> {
+    let value = match form {
+        DW_FORM_ADDR => AttrValue::Address(reader.read_address(encoding.address_size)?),
+        DW_FORM_BLOCK1 => {
+            let len = reader.read_u8()? as u64;
+            AttrValue::Block(reader.read_bytes(len)?)
+        }
+        DW_FORM_BLOCK2 => {
+            let len = reader.read_u16()? as u64;
+            AttrValue::Block(reader.read_bytes(len)?)
+        }
+        DW_FORM_BLOCK4 => {
+            let len = reader.read_u32()? as u64;
+            AttrValue::Block(reader.read_bytes(len)?)
+        }
+        DW_FORM_BLOCK => {
+            let len = reader.read_uleb128()?;
+            AttrValue::Block(reader.read_bytes(len)?)
+        }
+        DW_FORM_EXPRLOC => {
+            let len = reader.read_uleb128()?;
+            AttrValue::Exprloc(reader.read_bytes(len)?)
+        }
+        DW_FORM_DATA1 => AttrValue::Udata(reader.read_u8()? as u64),
+        DW_FORM_DATA2 => AttrValue::Udata(reader.read_u16()? as u64),
+        DW_FORM_DATA4 => AttrValue::Udata(reader.read_u32()? as u64),
+        DW_FORM_DATA8 => AttrValue::Udata(reader.read_u64()?),
+        DW_FORM_DATA16 => AttrValue::Data16(reader.read_bytes(16)?),
+        DW_FORM_UDATA => AttrValue::Udata(reader.read_uleb128()?),
+        DW_FORM_SDATA => AttrValue::Sdata(reader.read_sleb128()?),
+        DW_FORM_IMPLICIT_CONST => AttrValue::Sdata(implicit_const),
+        DW_FORM_FLAG => AttrValue::Flag(reader.read_u8()? != 0),
+        DW_FORM_FLAG_PRESENT => AttrValue::Flag(true),
+        DW_FORM_STRING => AttrValue::String(reader.read_cstr()?),
+        DW_FORM_STRP => AttrValue::StrOffset(reader.read_offset(encoding.format_64)?),
+        DW_FORM_LINE_STRP => AttrValue::LineStrOffset(reader.read_offset(encoding.format_64)?),
+        DW_FORM_STRP_SUP | DW_FORM_GNU_STRP_ALT => {
+            AttrValue::SupStrOffset(reader.read_offset(encoding.format_64)?)
+        }
+        DW_FORM_STRX | DW_FORM_GNU_STR_INDEX => AttrValue::StrIndex(reader.read_uleb128()?),
+        DW_FORM_STRX1 => AttrValue::StrIndex(reader.read_u8()? as u64),
+        DW_FORM_STRX2 => AttrValue::StrIndex(reader.read_u16()? as u64),
+        DW_FORM_STRX3 => AttrValue::StrIndex(reader.read_u24()? as u64),
+        DW_FORM_STRX4 => AttrValue::StrIndex(reader.read_u32()? as u64),
+        DW_FORM_ADDRX | DW_FORM_GNU_ADDR_INDEX => AttrValue::AddressIndex(reader.read_uleb128()?),
+        DW_FORM_ADDRX1 => AttrValue::AddressIndex(reader.read_u8()? as u64),
+        DW_FORM_ADDRX2 => AttrValue::AddressIndex(reader.read_u16()? as u64),
+        DW_FORM_ADDRX3 => AttrValue::AddressIndex(reader.read_u24()? as u64),
+        DW_FORM_ADDRX4 => AttrValue::AddressIndex(reader.read_u32()? as u64),
+        DW_FORM_REF1 => AttrValue::UnitRef(reader.read_u8()? as u64),
+        DW_FORM_REF2 => AttrValue::UnitRef(reader.read_u16()? as u64),
+        DW_FORM_REF4 => AttrValue::UnitRef(reader.read_u32()? as u64),
+        DW_FORM_REF8 => AttrValue::UnitRef(reader.read_u64()?),
+        DW_FORM_REF_UDATA => AttrValue::UnitRef(reader.read_uleb128()?),
+        DW_FORM_REF_ADDR => {
+            // DWARF 2 used the address size for references to other units.
+            if encoding.version <= 2 {
+                AttrValue::InfoRef(reader.read_address(encoding.address_size)?)
+            } else {
+                AttrValue::InfoRef(reader.read_offset(encoding.format_64)?)
+            }
+        }
+        DW_FORM_REF_SUP4 => AttrValue::SupRef(reader.read_u32()? as u64),
+        DW_FORM_REF_SUP8 => AttrValue::SupRef(reader.read_u64()?),
+        DW_FORM_GNU_REF_ALT => AttrValue::SupRef(reader.read_offset(encoding.format_64)?),
+        DW_FORM_REF_SIG8 => AttrValue::TypeSig(reader.read_u64()?),
+        DW_FORM_SEC_OFFSET => AttrValue::SecOffset(reader.read_offset(encoding.format_64)?),
+        DW_FORM_LOCLISTX => AttrValue::LoclistIndex(reader.read_uleb128()?),
+        DW_FORM_RNGLISTX => AttrValue::RnglistIndex(reader.read_uleb128()?),
+        DW_FORM_INDIRECT => {
+            let form = reader.read_uleb128()?;
+            // bounds the recursion on crafted input
+            if form == DW_FORM_INDIRECT {
+                return Err(ElfError::ParseError("nested DW_FORM_indirect.".to_string()));
+            }
+            return read_form(reader, form, encoding, implicit_const);
+        }
+        _ => {
+            return Err(ElfError::ParseError(format!(
+                "unknown DWARF form 0x{:X}.",
+                form
+            )));
+        }
+    };
+    Ok(value)
+}
@@ -0,0 +1,722 @@
+//! `.debug_info` units and debugging information entries (DIEs).
+
+use std::collections::HashMap;
+
+use super::constants::*;
+use super::form::{AttrValue, read_form};
+use super::{DwarfReader, DwarfSections, Encoding, str_at, table_offset};
+use crate::elf::Result;
+use crate::elf::error::ElfError;
+
+#[derive(Debug, Clone)]
+pub struct AbbrevAttr {
+    pub name: u64,
+    pub form: u64,
+    pub implicit_const: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Abbrev {
+    pub code: u64,
+    pub tag: u64,
+    pub has_children: bool,
+    pub attrs: Vec<AbbrevAttr>,
+}
+
+/// One abbreviation table from `.debug_abbrev`, keyed by code.
+#[derive(Debug, Clone, Default)]
+pub struct Abbreviations {
+    abbrevs: HashMap<u64, Abbrev>,
+}
+
+impl Abbreviations {
+    pub fn parse(data: &[u8], offset: u64, big_endian: bool) -> Result<Self> {
+        let mut reader = DwarfReader::at(data, offset, big_endian)?;
+        let mut abbrevs = HashMap::new();
+        loop {
+            let code = reader.read_uleb128()?;
+            if code == 0 {
+                break;
+            }
+            let tag = reader.read_uleb128()?;
+            let has_children = reader.read_u8()? != 0;
+            let mut attrs = Vec::new();
+            loop {
+                let name = reader.read_uleb128()?;
+                let form = reader.read_uleb128()?;
+                if name == 0 && form == 0 {
+                    break;
+                }
+                let implicit_const = if form == DW_FORM_IMPLICIT_CONST {
+                    reader.read_sleb128()?
+                } else {
+                    0
+                };
+                attrs.push(AbbrevAttr {
+                    name,
+                    form,
+                    implicit_const,
+                });
+            }
+            abbrevs.insert(
+                code,
+                Abbrev {
+                    code,
+                    tag,
+                    has_children,
+                    attrs,
+                },
+            );
+        }
+        Ok(Self { abbrevs })
+    }
+
+    pub fn get(&self, code: u64) -> Option<&Abbrev> {
+        self.abbrevs.get(&code)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Attribute<'a> {
+    pub name: u64,
+    pub form: u64,
+    pub value: AttrValue<'a>,
+}
+
+/// A debugging information entry.
+#[derive(Debug, Clone)]
+pub struct Die<'a> {
+    /// Offset of the entry in `.debug_info`.
+    pub offset: u64,
+    /// Nesting depth below the unit's root entry (which has depth 0).
+    pub depth: usize,
+    pub tag: u64,
+    pub has_children: bool,
+    pub attrs: Vec<Attribute<'a>>,
+}
+
+impl<'a> Die<'a> {
+    pub fn attr(&self, name: u64) -> Option<AttrValue<'a>> {
+        self.attrs.iter().find(|a| a.name == name).map(|a| a.value)
+    }
+}
+
+/// A unit header from `.debug_info` together with the values of its root
+/// entry that are needed to decode the rest of the unit.
+#[derive(Debug, Clone)]
+pub struct Unit {
+    /// Offset of the unit header in `.debug_info`.
+    pub offset: u64,
+    /// Total size of the unit including its length field.
+    pub size: u64,
+    pub encoding: Encoding,
+    pub unit_type: u8,
+    pub abbrev_offset: u64,
+    /// Offset of the first entry in `.debug_info`.
+    pub entries_offset: u64,
+    pub abbrevs: Abbreviations,
+    pub str_offsets_base: u64,
+    pub addr_base: u64,
+    pub rnglists_base: u64,
+    pub loclists_base: u64,
+    /// Base address for range lists (the root entry's `DW_AT_low_pc`).
+    pub low_pc: u64,
+    pub name: Option<String>,
+    pub comp_dir: Option<String>,
+    pub producer: Option<String>,
+    /// `DW_AT_language` code, see [`lang_name`].
+    pub language: Option<u64>,
+    pub stmt_list: Option<u64>,
+}
+
+impl Unit {
+    fn parse(sections: &DwarfSections<'_>, offset: u64) -> Result<Self> {
+        let mut reader = DwarfReader::at(sections.debug_info, offset, sections.big_endian)?;
+        let (unit_length, format_64) = reader.read_initial_length()?;
+        let end = reader
+            .position()
+            .checked_add(unit_length)
+            .filter(|&end| end <= sections.debug_info.len() as u64)
+            .ok_or_else(|| ElfError::ParseError("unit exceeds .debug_info range.".to_string()))?;
+
+        let version = reader.read_u16()?;
+        if !(2..=5).contains(&version) {
+            return Err(ElfError::ParseError(format!(
+                "unsupported DWARF version {}.",
+                version
+            )));
+        }
+
+        let (unit_type, address_size, abbrev_offset) = if version >= 5 {
+            let unit_type = reader.read_u8()?;
+            let address_size = reader.read_u8()?;
+            let abbrev_offset = reader.read_offset(format_64)?;
+            match unit_type {
+                DW_UT_SKELETON | DW_UT_SPLIT_COMPILE => {
+                    // dwo_id
+                    reader.read_u64()?;
+                }
+                DW_UT_TYPE | DW_UT_SPLIT_TYPE => {
+                    // type_signature and type_offset
+                    reader.read_u64()?;
+                    reader.read_offset(format_64)?;
+                }
+                _ => {}
+            }
+            (unit_type, address_size, abbrev_offset)
+        } else {
+            let abbrev_offset = reader.read_offset(format_64)?;
+            let address_size = reader.read_u8()?;
+            (DW_UT_COMPILE, address_size, abbrev_offset)
+        };
+
+        let mut unit = Unit {
+            offset,
+            size: end - offset,
+            encoding: Encoding {
+                version,
+                format_64,
+                address_size,
+            },
+            unit_type,
+            abbrev_offset,
+            entries_offset: reader.position(),
+            abbrevs: Abbreviations::parse(
+                sections.debug_abbrev,
+                abbrev_offset,
+                sections.big_endian,
+            )?,
+            str_offsets_base: 0,
+            addr_base: 0,
+            rnglists_base: 0,
+            loclists_base: 0,
+            low_pc: 0,
+            name: None,
+            comp_dir: None,
+            producer: None,
+            language: None,
+            stmt_list: None,
+        };
+
+        let root = match unit.entries(sections).next() {
+            Some(root) => root?,
+            None => return Ok(unit),
+        };
+
+        // The bases must be known before any index form can be resolved.
+        for attr in &root.attrs {
+            let offset = attr.value.offset();
+            match attr.name {
+                DW_AT_STR_OFFSETS_BASE => unit.str_offsets_base = offset.unwrap_or(0),
+                DW_AT_ADDR_BASE | DW_AT_GNU_ADDR_BASE => unit.addr_base = offset.unwrap_or(0),
+                DW_AT_RNGLISTS_BASE | DW_AT_GNU_RANGES_BASE => {
+                    unit.rnglists_base = offset.unwrap_or(0)
+                }
+                DW_AT_LOCLISTS_BASE => unit.loclists_base = offset.unwrap_or(0),
+                DW_AT_STMT_LIST => unit.stmt_list = offset,
+                _ => {}
+            }
+        }
+        unit.low_pc = root
+            .attr(DW_AT_LOW_PC)
+            .and_then(|v| sections.attr_address(&unit, v))
+            .unwrap_or(0);
+        unit.name = root
+            .attr(DW_AT_NAME)
+            .and_then(|v| sections.attr_string(&unit, v));
+        unit.comp_dir = root
+            .attr(DW_AT_COMP_DIR)
+            .and_then(|v| sections.attr_string(&unit, v));
+        unit.producer = root
+            .attr(DW_AT_PRODUCER)
+            .and_then(|v| sections.attr_string(&unit, v));
+        unit.language = root.attr(DW_AT_LANGUAGE).and_then(|v| v.udata());
+        Ok(unit)
+    }
+
+    /// Returns an iterator over the entries of this unit in depth-first
+    /// order.
+    pub fn entries<'a>(&self, sections: &DwarfSections<'a>) -> Entries<'a, '_> {
+        let end = (self.offset + self.size).min(sections.debug_info.len() as u64);
+        let mut reader = DwarfReader::new(sections.debug_info, sections.big_endian);
+        reader.set_position(self.entries_offset);
+        Entries {
+            unit: self,
+            reader,
+            end,
+            depth: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the unit type as a `DW_UT_*` name.
+    pub fn unit_type_name(&self) -> String {
+        match unit_type_name(self.unit_type) {
+            Some(name) => name.to_string(),
+            None => format!("DW_UT_<0x{:X}>", self.unit_type),
+        }
+    }
+
+    /// Returns the source language name, e.g. `C11`.
+    pub fn language_name(&self) -> Option<String> {
+        let lang = self.language?;
+        Some(match lang_name(lang) {
+            Some(name) => name.to_string(),
+            None => format!("0x{:X}", lang),
+        })
+    }
+
+    /// Reads the entry at `offset` in `.debug_info`, which must belong to
+    /// this unit. The depth of the returned entry is not known and set to 0.
+    pub fn entry_at<'a>(&self, sections: &DwarfSections<'a>, offset: u64) -> Result<Die<'a>> {
+        if offset < self.entries_offset || offset >= self.offset + self.size {
+            return Err(ElfError::ParseError(format!(
+                "DIE offset 0x{:X} is outside its unit.",
+                offset
+            )));
+        }
+        let mut reader = DwarfReader::new(sections.debug_info, sections.big_endian);
+        reader.set_position(offset);
+        read_die(self, &mut reader, 0)?
+            .ok_or_else(|| ElfError::ParseError(format!("null DIE at offset 0x{:X}.", offset)))
+    }
+}
+
+/// Iterator over the entries of one unit.
+pub struct Entries<'a, 'u> {
+    unit: &'u Unit,
+    reader: DwarfReader<'a>,
+    end: u64,
+    depth: usize,
+    done: bool,
+}
+
+impl<'a> Iterator for Entries<'a, '_> {
+    type Item = Result<Die<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done && self.reader.position() < self.end {
+            match read_die(self.unit, &mut self.reader, self.depth) {
+                Ok(Some(die)) => {
+                    if die.has_children {
+                        self.depth += 1;
+                    }
+                    return Some(Ok(die));
+                }
+                Ok(None) => {
+                    // null entry: end of a sibling chain
+                    if self.depth == 0 {
+                        self.done = true;
+                    } else {
+                        self.depth -= 1;
+                    }
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the units of `.debug_info`, see [`DwarfSections::iter_units`].
+pub struct Units<'a> {
+    sections: DwarfSections<'a>,
+    offset: u64,
+    done: bool,
+}
+
+impl Iterator for Units<'_> {
+    type Item = Result<Unit>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.offset >= self.sections.debug_info.len() as u64 {
+            return None;
+        }
+        match Unit::parse(&self.sections, self.offset) {
+            Ok(unit) => {
+                self.offset += unit.size;
+                Some(Ok(unit))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// One entry of a location list. `range` is `None` for a default location
+/// that applies wherever no other entry does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocationListEntry<'a> {
+    pub range: Option<(u64, u64)>,
+    pub expr: &'a [u8],
+}
+
+/// Reads one entry, returning `None` for a null entry.
+fn read_die<'a>(
+    unit: &Unit,
+    reader: &mut DwarfReader<'a>,
+    depth: usize,
+) -> Result<Option<Die<'a>>> {
+    let offset = reader.position();
+    let code = reader.read_uleb128()?;
+    if code == 0 {
+        return Ok(None);
+    }
+
+    let abbrev = unit.abbrevs.get(code).ok_or_else(|| {
+        ElfError::ParseError(format!(
+            "unknown abbreviation code {} at offset 0x{:X}.",
+            code, offset
+        ))
+    })?;
+
+    let mut attrs = Vec::with_capacity(abbrev.attrs.len());
+    for spec in &abbrev.attrs {
+        let value = read_form(reader, spec.form, unit.encoding, spec.implicit_const)?;
+        attrs.push(Attribute {
+            name: spec.name,
+            form: spec.form,
+            value,
+        });
+    }
+
+    Ok(Some(Die {
+        offset,
+        depth,
+        tag: abbrev.tag,
+        has_children: abbrev.has_children,
+        attrs,
+    }))
+}
+
+impl<'a> DwarfSections<'a> {
+    /// Returns an iterator over the units in `.debug_info`. Iteration stops
+    /// after the first unit that fails to parse.
+    pub fn iter_units(&self) -> Units<'a> {
+        Units {
+            sections: *self,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// Parses the headers of all units in `.debug_info`.
+    pub fn units(&self) -> Result<Vec<Unit>> {
+        self.iter_units().collect()
+    }
+
+    /// Resolves a string-class attribute value.
+    pub fn attr_string(&self, unit: &Unit, value: AttrValue<'a>) -> Option<String> {
+        let s = match value {
+            AttrValue::String(s) => s,
+            AttrValue::StrOffset(off) => str_at(self.debug_str, off).ok()?,
+            AttrValue::LineStrOffset(off) => str_at(self.debug_line_str, off).ok()?,
+            AttrValue::StrIndex(index) => {
+                let offset_size = unit.encoding.offset_size();
+                let mut reader = DwarfReader::at(
+                    self.debug_str_offsets,
+                    table_offset(unit.str_offsets_base, index, offset_size).ok()?,
+                    self.big_endian,
+                )
+                .ok()?;
+                let off = reader.read_offset(unit.encoding.format_64).ok()?;
+                str_at(self.debug_str, off).ok()?
+            }
+            _ => return None,
+        };
+        Some(s.to_string())
+    }
+
+    /// Resolves an address-class attribute value.
+    pub fn attr_address(&self, unit: &Unit, value: AttrValue<'a>) -> Option<u64> {
+        match value {
+            AttrValue::Address(addr) => Some(addr),
+            AttrValue::AddressIndex(index) => self.indexed_address(unit, index).ok(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn indexed_address(&self, unit: &Unit, index: u64) -> Result<u64> {
+        let size = unit.encoding.address_size;
+        let mut reader = DwarfReader::at(
+            self.debug_addr,
+            table_offset(unit.addr_base, index, size as u64)?,
+            self.big_endian,
+        )?;
+        reader.read_address(size)
+    }
+
+    /// Returns the address ranges covered by an entry, from either
+    /// `DW_AT_low_pc`/`DW_AT_high_pc` or `DW_AT_ranges`.
+    pub fn die_ranges(&self, unit: &Unit, die: &Die<'a>) -> Result<Vec<(u64, u64)>> {
+        if let Some(ranges) = die.attr(DW_AT_RANGES) {
+            return self.ranges(unit, ranges);
+        }
+
+        let low = match die
+            .attr(DW_AT_LOW_PC)
+            .and_then(|v| self.attr_address(unit, v))
+        {
+            Some(low) => low,
+            None => return Ok(Vec::new()),
+        };
+        let high = match die.attr(DW_AT_HIGH_PC) {
+            Some(AttrValue::Udata(len)) => low.wrapping_add(len),
+            Some(AttrValue::Sdata(len)) => low.wrapping_add(len as u64),
+            Some(v) => match self.attr_address(unit, v) {
+                Some(high) => high,
+                None => return Ok(Vec::new()),
+            },
+            None => low.wrapping_add(1),
+        };
+        Ok(vec![(low, high)])
+    }
+
+    /// Decodes a `DW_AT_ranges` value into address ranges.
+    pub fn ranges(&self, unit: &Unit, value: AttrValue<'a>) -> Result<Vec<(u64, u64)>> {
+        let offset = match value {
+            AttrValue::RnglistIndex(index) => {
+                let offset_size = unit.encoding.offset_size();
+                let mut reader = DwarfReader::at(
+                    self.debug_rnglists,
+                    table_offset(unit.rnglists_base, index, offset_size)?,
+                    self.big_endian,
+                )?;
+                let offset = reader.read_offset(unit.encoding.format_64)?;
+                unit.rnglists_base.checked_add(offset).ok_or_else(|| {
+                    ElfError::ParseError("range list offset overflows.".to_string())
+                })?
+            }
+            v => v.offset().ok_or_else(|| {
+                ElfError::ParseError("unsupported DW_AT_ranges form.".to_string())
+            })?,
+        };
+
+        if unit.encoding.version >= 5 {
+            self.rnglist(unit, offset)
+        } else {
+            self.range_list(unit, offset)
+        }
+    }
+
+    /// Reads a DWARF 2-4 `.debug_ranges` list.
+    fn range_list(&self, unit: &Unit, offset: u64) -> Result<Vec<(u64, u64)>> {
+        let size = unit.encoding.address_size;
+        let max = if size == 8 { u64::MAX } else { u32::MAX as u64 };
+        let mut reader = DwarfReader::at(self.debug_ranges, offset, self.big_endian)?;
+        let mut base = unit.low_pc;
+        let mut ranges = Vec::new();
+        loop {
+            let begin = reader.read_address(size)?;
+            let end = reader.read_address(size)?;
+            if begin == 0 && end == 0 {
+                break;
+            }
+            if begin == max {
+                base = end;
+                continue;
+            }
+            ranges.push((base.wrapping_add(begin), base.wrapping_add(end)));
+        }
+        Ok(ranges)
+    }
+
+    /// Reads a DWARF 5 `.debug_rnglists` list.
+    fn rnglist(&self, unit: &Unit, offset: u64) -> Result<Vec<(u64, u64)>> {
+        let size = unit.encoding.address_size;
+        let mut reader = DwarfReader::at(self.debug_rnglists, offset, self.big_endian)?;
+        let mut base = unit.low_pc;
+        let mut ranges = Vec::new();
+        loop {
+            match reader.read_u8()? {
+                DW_RLE_END_OF_LIST => break,
+                DW_RLE_BASE_ADDRESSX => {
+                    base = self.indexed_address(unit, reader.read_uleb128()?)?;
+                }
+                DW_RLE_STARTX_ENDX => {
+                    let start = self.indexed_address(unit, reader.read_uleb128()?)?;
+                    let end = self.indexed_address(unit, reader.read_uleb128()?)?;
+                    ranges.push((start, end));
+                }
+                DW_RLE_STARTX_LENGTH => {
+                    let start = self.indexed_address(unit, reader.read_uleb128()?)?;
+                    let len = reader.read_uleb128()?;
+                    ranges.push((start, start.wrapping_add(len)));
+                }
+                DW_RLE_OFFSET_PAIR => {
+                    let start = reader.read_uleb128()?;
+                    let end = reader.read_uleb128()?;
+                    ranges.push((base.wrapping_add(start), base.wrapping_add(end)));
+                }
+                DW_RLE_BASE_ADDRESS => base = reader.read_address(size)?,
+                DW_RLE_START_END => {
+                    let start = reader.read_address(size)?;
+                    let end = reader.read_address(size)?;
+                    ranges.push((start, end));
+                }
+                DW_RLE_START_LENGTH => {
+                    let start = reader.read_address(size)?;
+                    let len = reader.read_uleb128()?;
+                    ranges.push((start, start.wrapping_add(len)));
+                }
+                kind => {
+                    return Err(ElfError::ParseError(format!(
+                        "unknown range list entry kind 0x{:X}.",
+                        kind
+                    )));
+                }
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// Decodes a location list reference (`DW_FORM_sec_offset`,
+    /// `DW_FORM_loclistx`, or `data4`/`data8` before DWARF 4).
+    pub fn locations(
+        &self,
+        unit: &Unit,
+        value: AttrValue<'a>,
+    ) -> Result<Vec<LocationListEntry<'a>>> {
+        let offset = match value {
+            AttrValue::LoclistIndex(index) => {
+                let offset_size = unit.encoding.offset_size();
+                let mut reader = DwarfReader::at(
+                    self.debug_loclists,
+                    table_offset(unit.loclists_base, index, offset_size)?,
+                    self.big_endian,
+                )?;
+                let offset = reader.read_offset(unit.encoding.format_64)?;
+                unit.loclists_base.checked_add(offset).ok_or_else(|| {
+                    ElfError::ParseError("location list offset overflows.".to_string())
+                })?
+            }
+            v => v.offset().ok_or_else(|| {
+                ElfError::ParseError("unsupported location list form.".to_string())
+            })?,
+        };
+
+        if unit.encoding.version >= 5 {
+            self.loclist(unit, offset)
+        } else {
+            self.location_list(unit, offset)
+        }
+    }
+
+    /// Reads a DWARF 2-4 `.debug_loc` list.
+    fn location_list(&self, unit: &Unit, offset: u64) -> Result<Vec<LocationListEntry<'a>>> {
+        let size = unit.encoding.address_size;
+        let max = if size == 8 { u64::MAX } else { u32::MAX as u64 };
+        let mut reader = DwarfReader::at(self.debug_loc, offset, self.big_endian)?;
+        let mut base = unit.low_pc;
+        let mut entries = Vec::new();
+        loop {
+            let begin = reader.read_address(size)?;
+            let end = reader.read_address(size)?;
+            if begin == 0 && end == 0 {
+                break;
+            }
+            if begin == max {
+                base = end;
+                continue;
+            }
+            let len = reader.read_u16()? as u64;
+            entries.push(LocationListEntry {
+                range: Some((base.wrapping_add(begin), base.wrapping_add(end))),
+                expr: reader.read_bytes(len)?,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Reads a DWARF 5 `.debug_loclists` list.
+    fn loclist(&self, unit: &Unit, offset: u64) -> Result<Vec<LocationListEntry<'a>>> {
+        let size = unit.encoding.address_size;
+        let mut reader = DwarfReader::at(self.debug_loclists, offset, self.big_endian)?;
+        let mut base = unit.low_pc;
+        let mut entries = Vec::new();
+        loop {
+            let range = match reader.read_u8()? {
+                DW_LLE_END_OF_LIST => break,
+                DW_LLE_BASE_ADDRESSX => {
+                    base = self.indexed_address(unit, reader.read_uleb128()?)?;
+                    continue;
+                }
+                DW_LLE_BASE_ADDRESS => {
+                    base = reader.read_address(size)?;
+                    continue;
+                }
+                DW_LLE_STARTX_ENDX => {
+                    let start = self.indexed_address(unit, reader.read_uleb128()?)?;
+                    let end = self.indexed_address(unit, reader.read_uleb128()?)?;
+                    Some((start, end))
+                }
+                DW_LLE_STARTX_LENGTH => {
+                    let start = self.indexed_address(unit, reader.read_uleb128()?)?;
+                    let len = reader.read_uleb128()?;
+                    Some((start, start.wrapping_add(len)))
+                }
+                DW_LLE_OFFSET_PAIR => {
+                    let start = reader.read_uleb128()?;
+                    let end = reader.read_uleb128()?;
+                    Some((base.wrapping_add(start), base.wrapping_add(end)))
+                }
+                DW_LLE_DEFAULT_LOCATION => None,
+                DW_LLE_START_END => {
+                    let start = reader.read_address(size)?;
+                    let end = reader.read_address(size)?;
+                    Some((start, end))
+                }
+                DW_LLE_START_LENGTH => {
+                    let start = reader.read_address(size)?;
+                    let len = reader.read_uleb128()?;
+                    Some((start, start.wrapping_add(len)))
+                }
+                kind => {
+                    return Err(ElfError::ParseError(format!(
+                        "unknown location list entry kind 0x{:X}.",
+                        kind
+                    )));
+                }
+            };
+            let len = reader.read_uleb128()?;
+            entries.push(LocationListEntry {
+                range,
+                expr: reader.read_bytes(len)?,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Returns the name of an entry, following `DW_AT_abstract_origin` and
+    /// `DW_AT_specification` links to the declaration that carries it.
+    pub fn die_name(&self, units: &[Unit], unit: &Unit, die: &Die<'a>) -> Option<String> {
+        let mut unit = unit;
+        let mut die = die.clone();
+        // bounded to guard against reference cycles
+        for _ in 0..16 {
+            if let Some(name) = die.attr(DW_AT_NAME).and_then(|v| self.attr_string(unit, v)) {
+                return Some(name);
+            }
+            let target = die
+                .attr(DW_AT_ABSTRACT_ORIGIN)
+                .or_else(|| die.attr(DW_AT_SPECIFICATION))?;
+            let offset = match target {
+                AttrValue::UnitRef(off) => unit.offset + off,
+                AttrValue::InfoRef(off) => {
+                    unit = units
+                        .iter()
+                        .find(|u| off >= u.offset && off < u.offset + u.size)?;
+                    off
+                }
+                _ => return None,
+            };
+            die = unit.entry_at(self, offset).ok()?;
+        }
+        None
+    }
+}
@@ -0,0 +1,198 @@
+//! Memory layout of structs, classes and unions, in the manner of pahole.
+
+use std::collections::HashSet;
+
+use super::DwarfReader;
+use super::constants::*;
+use super::form::AttrValue;
+use super::typeinfo::TypeIndex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutMember {
+    /// `None` for anonymous members.
+    pub name: Option<String>,
+    pub type_name: String,
+    /// Offset from the start of the aggregate in bits.
+    pub bit_offset: u64,
+    /// Size of the member's type in bytes (the storage unit for bitfields).
+    pub size: u64,
+    /// Width of a bitfield member.
+    pub bit_size: Option<u64>,
+    /// Unused bits between the previous member and this one.
+    pub hole_bits: u64,
+    /// Whether this is a base class subobject (`DW_TAG_inheritance`).
+    pub is_base: bool,
+}
+
+impl LayoutMember {
+    pub fn byte_offset(&self) -> u64 {
+        self.bit_offset / 8
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.bit_size.unwrap_or(self.size.saturating_mul(8))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    /// `struct`, `class` or `union`.
+    pub kind: &'static str,
+    pub name: String,
+    pub size: u64,
+    pub members: Vec<LayoutMember>,
+    /// Unused bits after the last member.
+    pub padding_bits: u64,
+}
+
+impl StructLayout {
+    /// Sum of the member sizes in bytes, counting bitfields by their width.
+    pub fn sum_members(&self) -> u64 {
+        let bits = self
+            .members
+            .iter()
+            .fold(0u64, |total, m| total.saturating_add(m.bits()));
+        bits / 8
+    }
+
+    pub fn holes(&self) -> usize {
+        self.members.iter().filter(|m| m.hole_bits > 0).count()
+    }
+
+    pub fn sum_hole_bits(&self) -> u64 {
+        self.members
+            .iter()
+            .fold(0, |total, m| total.saturating_add(m.hole_bits))
+    }
+
+    /// Whether the aggregate has holes or trailing padding.
+    pub fn has_waste(&self) -> bool {
+        self.padding_bits > 0 || self.holes() > 0
+    }
+}
+
+impl TypeIndex<'_> {
+    /// Returns the layouts of all named, complete structs, classes and
+    /// unions. Types defined identically in several units are reported once.
+    pub fn struct_layouts(&self) -> Vec<StructLayout> {
+        let mut seen = HashSet::new();
+        let mut layouts = Vec::new();
+        for index in 0..self.entries.len() {
+            let kind = match self.entries[index].die.tag {
+                DW_TAG_STRUCTURE_TYPE => "struct",
+                DW_TAG_CLASS_TYPE => "class",
+                DW_TAG_UNION_TYPE => "union",
+                _ => continue,
+            };
+            if self.flag(index, DW_AT_DECLARATION) {
+                continue;
+            }
+            let (name, size) = match (self.name(index), self.udata(index, DW_AT_BYTE_SIZE)) {
+                (Some(name), Some(size)) => (name, size),
+                _ => continue,
+            };
+            if !seen.insert((kind, name.clone(), size)) {
+                continue;
+            }
+            layouts.push(self.struct_layout(index, kind, name, size));
+        }
+        layouts
+    }
+
+    fn struct_layout(
+        &self,
+        index: usize,
+        kind: &'static str,
+        name: String,
+        size: u64,
+    ) -> StructLayout {
+        let is_union = kind == "union";
+        let mut members = Vec::new();
+        let mut end_bits = 0;
+        for &child in &self.entries[index].children {
+            let tag = self.entries[child].die.tag;
+            if tag != DW_TAG_MEMBER && tag != DW_TAG_INHERITANCE {
+                continue;
+            }
+            // static data members are declarations without a location
+            if self.flag(child, DW_AT_DECLARATION) || self.flag(child, DW_AT_EXTERNAL) {
+                continue;
+            }
+
+            let member_type = self.type_of(child);
+            let type_size = member_type.and_then(|t| self.type_size(t)).unwrap_or(0);
+            let bit_size = self.udata(child, DW_AT_BIT_SIZE);
+            let bit_offset = self.member_bit_offset(child, type_size, bit_size);
+            let member = LayoutMember {
+                name: self.name(child),
+                type_name: self.type_name(member_type),
+                bit_offset,
+                size: self.udata(child, DW_AT_BYTE_SIZE).unwrap_or(type_size),
+                bit_size,
+                hole_bits: if is_union {
+                    0
+                } else {
+                    bit_offset.saturating_sub(end_bits)
+                },
+                is_base: tag == DW_TAG_INHERITANCE,
+            };
+            end_bits = end_bits.max(bit_offset.saturating_add(member.bits()));
+            members.push(member);
+        }
+
+        StructLayout {
+            kind,
+            name,
+            size,
+            members,
+            padding_bits: size.saturating_mul(8).saturating_sub(end_bits),
+        }
+    }
+
+    /// Returns the offset of a member in bits from the start of its parent.
+    fn member_bit_offset(&self, index: usize, type_size: u64, bit_size: Option<u64>) -> u64 {
+        if let Some(offset) = self.udata(index, DW_AT_DATA_BIT_OFFSET) {
+            return offset;
+        }
+
+        let byte_offset = match self.entries[index].die.attr(DW_AT_DATA_MEMBER_LOCATION) {
+            Some(AttrValue::Exprloc(expr)) | Some(AttrValue::Block(expr)) => {
+                plus_uconst(expr).unwrap_or(0)
+            }
+            Some(value) => value.udata().unwrap_or(0),
+            None => 0,
+        };
+
+        // Crafted sizes and offsets saturate rather than wrap.
+        let byte_offset_bits = byte_offset.saturating_mul(8);
+
+        // DWARF 2-4 bitfields count DW_AT_bit_offset from the most
+        // significant bit of the storage unit.
+        match (bit_size, self.udata(index, DW_AT_BIT_OFFSET)) {
+            (Some(bit_size), Some(bit_offset)) => {
+                let storage = self
+                    .udata(index, DW_AT_BYTE_SIZE)
+                    .unwrap_or(type_size)
+                    .saturating_mul(8);
+                if self.sections.big_endian {
+                    byte_offset_bits.saturating_add(bit_offset)
+                } else {
+                    byte_offset_bits
+                        .saturating_add(storage)
+                        .saturating_sub(bit_offset.saturating_add(bit_size))
+                }
+            }
+            _ => byte_offset_bits,
+        }
+    }
+}
+
+/// Decodes the `DW_OP_plus_uconst N` expression that DWARF 2 producers use
+/// for member locations.
+fn plus_uconst(expr: &[u8]) -> Option<u64> {
+    let mut reader = DwarfReader::new(expr, false);
+    match reader.read_u8().ok()? {
+        0x23 => reader.read_uleb128().ok(),
+        _ => None,
+    }
+}
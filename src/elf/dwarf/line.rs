@@ -0,0 +1,367 @@
+//! `.debug_line` line number programs.
+
+use super::constants::*;
+use super::form::{AttrValue, read_form};
+use super::{DwarfReader, DwarfSections, Encoding, str_at};
+use crate::elf::Result;
+use crate::elf::error::ElfError;
+
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub path: String,
+    pub directory_index: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LineProgramHeader {
+    /// Offset of the program in `.debug_line`.
+    pub offset: u64,
+    /// Total size of the program including its length field.
+    pub size: u64,
+    pub encoding: Encoding,
+    pub minimum_instruction_length: u8,
+    pub maximum_operations_per_instruction: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+    pub standard_opcode_lengths: Vec<u8>,
+    pub include_directories: Vec<String>,
+    pub file_names: Vec<FileEntry>,
+}
+
+/// One row of the line number matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRow {
+    pub address: u64,
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    pub end_sequence: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct LineProgram {
+    pub header: LineProgramHeader,
+    pub rows: Vec<LineRow>,
+}
+
+impl LineProgramHeader {
+    /// Returns the directory with the given index. Before DWARF 5 index 0
+    /// refers to the compilation directory, which is not stored in the
+    /// header.
+    pub fn directory(&self, index: u64, comp_dir: Option<&str>) -> Option<String> {
+        if self.encoding.version >= 5 {
+            self.include_directories.get(index as usize).cloned()
+        } else if index == 0 {
+            comp_dir.map(|dir| dir.to_string())
+        } else {
+            self.include_directories.get(index as usize - 1).cloned()
+        }
+    }
+
+    /// Returns the file entry for a `file` register value. File numbers are
+    /// 1-based before DWARF 5 and 0-based from DWARF 5 on.
+    pub fn file(&self, file: u64) -> Option<&FileEntry> {
+        if self.encoding.version >= 5 {
+            self.file_names.get(file as usize)
+        } else if file == 0 {
+            None
+        } else {
+            self.file_names.get(file as usize - 1)
+        }
+    }
+
+    /// Returns the full path of a file, joined with its directory and the
+    /// compilation directory where these are relative.
+    pub fn file_path(&self, file: u64, comp_dir: Option<&str>) -> Option<String> {
+        let entry = self.file(file)?;
+        let mut path = entry.path.clone();
+        if !path.starts_with('/')
+            && let Some(dir) = self.directory(entry.directory_index, comp_dir)
+        {
+            path = join_path(&dir, &path);
+        }
+        if !path.starts_with('/')
+            && let Some(dir) = comp_dir
+        {
+            path = join_path(dir, &path);
+        }
+        Some(path)
+    }
+}
+
+fn join_path(dir: &str, path: &str) -> String {
+    if dir.is_empty() {
+        path.to_string()
+    } else if dir.ends_with('/') {
+        format!("{}{}", dir, path)
+    } else {
+        format!("{}/{}", dir, path)
+    }
+}
+
+impl LineProgram {
+    /// Parses the line number program at `offset` in `.debug_line`.
+    pub fn parse(sections: &DwarfSections<'_>, offset: u64) -> Result<Self> {
+        let mut reader = DwarfReader::at(sections.debug_line, offset, sections.big_endian)?;
+        let (unit_length, format_64) = reader.read_initial_length()?;
+        let end = reader
+            .position()
+            .checked_add(unit_length)
+            .filter(|&end| end <= sections.debug_line.len() as u64)
+            .ok_or_else(|| {
+                ElfError::ParseError("line program exceeds .debug_line range.".to_string())
+            })?;
+
+        let version = reader.read_u16()?;
+        if !(2..=5).contains(&version) {
+            return Err(ElfError::ParseError(format!(
+                "unsupported line program version {}.",
+                version
+            )));
+        }
+
+        let mut address_size = sections.address_size;
+        if version >= 5 {
+            address_size = reader.read_u8()?;
+            let _segment_selector_size = reader.read_u8()?;
+        }
+        let encoding = Encoding {
+            version,
+            format_64,
+            address_size,
+        };
+
+        let header_length = reader.read_offset(format_64)?;
+        let program_start = reader
+            .position()
+            .checked_add(header_length)
+            .ok_or_else(|| {
+                ElfError::ParseError("line program header length overflows.".to_string())
+            })?;
+
+        let minimum_instruction_length = reader.read_u8()?;
+        let maximum_operations_per_instruction = if version >= 4 { reader.read_u8()? } else { 1 };
+        let default_is_stmt = reader.read_u8()? != 0;
+        let line_base = reader.read_i8()?;
+        let line_range = reader.read_u8()?;
+        let opcode_base = reader.read_u8()?;
+        let mut standard_opcode_lengths = Vec::new();
+        for _ in 1..opcode_base {
+            standard_opcode_lengths.push(reader.read_u8()?);
+        }
+
+        let mut include_directories = Vec::new();
+        let mut file_names = Vec::new();
+        if version >= 5 {
+            for entry in read_v5_entries(&mut reader, sections, encoding)? {
+                include_directories.push(entry.path);
+            }
+            file_names = read_v5_entries(&mut reader, sections, encoding)?;
+        } else {
+            loop {
+                let dir = reader.read_cstr()?;
+                if dir.is_empty() {
+                    break;
+                }
+                include_directories.push(dir.to_string());
+            }
+            loop {
+                let path = reader.read_cstr()?;
+                if path.is_empty() {
+                    break;
+                }
+                let directory_index = reader.read_uleb128()?;
+                let _mtime = reader.read_uleb128()?;
+                let _length = reader.read_uleb128()?;
+                file_names.push(FileEntry {
+                    path: path.to_string(),
+                    directory_index,
+                });
+            }
+        }
+
+        let mut header = LineProgramHeader {
+            offset,
+            size: end - offset,
+            encoding,
+            minimum_instruction_length,
+            maximum_operations_per_instruction,
+            default_is_stmt,
+            line_base,
+            line_range,
+            opcode_base,
+            standard_opcode_lengths,
+            include_directories,
+            file_names,
+        };
+
+        reader.set_position(program_start);
+        let rows = run_program(&mut reader, &mut header, end)?;
+        Ok(Self { header, rows })
+    }
+
+    /// Parses every line number program in `.debug_line`, in section order.
+    pub fn parse_all(sections: &DwarfSections<'_>) -> Result<Vec<Self>> {
+        let mut programs = Vec::new();
+        let mut offset = 0;
+        while offset < sections.debug_line.len() as u64 {
+            let program = Self::parse(sections, offset)?;
+            offset += program.header.size;
+            programs.push(program);
+        }
+        Ok(programs)
+    }
+}
+
+/// Reads a DWARF 5 directory or file name table.
+fn read_v5_entries(
+    reader: &mut DwarfReader<'_>,
+    sections: &DwarfSections<'_>,
+    encoding: Encoding,
+) -> Result<Vec<FileEntry>> {
+    let format_count = reader.read_u8()?;
+    let mut formats = Vec::new();
+    for _ in 0..format_count {
+        let content_type = reader.read_uleb128()?;
+        let form = reader.read_uleb128()?;
+        formats.push((content_type, form));
+    }
+
+    let count = reader.read_uleb128()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let mut entry = FileEntry {
+            path: String::new(),
+            directory_index: 0,
+        };
+        for &(content_type, form) in &formats {
+            let value = read_form(reader, form, encoding, 0)?;
+            match content_type {
+                DW_LNCT_PATH => {
+                    entry.path = match value {
+                        AttrValue::String(s) => s.to_string(),
+                        AttrValue::LineStrOffset(off) => {
+                            str_at(sections.debug_line_str, off)?.to_string()
+                        }
+                        AttrValue::StrOffset(off) => str_at(sections.debug_str, off)?.to_string(),
+                        _ => String::from("<unknown>"),
+                    }
+                }
+                DW_LNCT_DIRECTORY_INDEX => entry.directory_index = value.udata().unwrap_or(0),
+                _ => {}
+            }
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Runs the line number state machine up to `end` and collects the rows.
+fn run_program(
+    reader: &mut DwarfReader<'_>,
+    header: &mut LineProgramHeader,
+    end: u64,
+) -> Result<Vec<LineRow>> {
+    let min_inst = header.minimum_instruction_length as u64;
+    let line_range = header.line_range.max(1) as u64;
+    let initial = LineRow {
+        address: 0,
+        file: 1,
+        line: 1,
+        column: 0,
+        is_stmt: header.default_is_stmt,
+        end_sequence: false,
+    };
+
+    let mut rows = Vec::new();
+    let mut state = initial;
+    while reader.position() < end {
+        let opcode = reader.read_u8()?;
+        if opcode >= header.opcode_base {
+            // special opcode
+            let adjusted = (opcode - header.opcode_base) as u64;
+            state.address = state
+                .address
+                .wrapping_add((adjusted / line_range) * min_inst);
+            state.line = state
+                .line
+                .wrapping_add_signed(header.line_base as i64 + (adjusted % line_range) as i64);
+            rows.push(state);
+            continue;
+        }
+
+        match opcode {
+            0 => {
+                let len = reader.read_uleb128()?;
+                if len == 0 {
+                    continue;
+                }
+                let next = reader.position().checked_add(len).ok_or_else(|| {
+                    ElfError::ParseError("extended opcode length overflows.".to_string())
+                })?;
+                let sub_opcode = reader.read_u8()?;
+                match sub_opcode {
+                    DW_LNE_END_SEQUENCE => {
+                        state.end_sequence = true;
+                        rows.push(state);
+                        state = initial;
+                    }
+                    DW_LNE_SET_ADDRESS => {
+                        state.address = reader.read_sized((len - 1) as u8)?;
+                    }
+                    DW_LNE_DEFINE_FILE => {
+                        let path = reader.read_cstr()?.to_string();
+                        let directory_index = reader.read_uleb128()?;
+                        header.file_names.push(FileEntry {
+                            path,
+                            directory_index,
+                        });
+                    }
+                    _ => {}
+                }
+                reader.set_position(next);
+            }
+            DW_LNS_COPY => rows.push(state),
+            DW_LNS_ADVANCE_PC => {
+                let advance = reader
+                    .read_uleb128()?
+                    .checked_mul(min_inst)
+                    .ok_or_else(|| {
+                        ElfError::ParseError("DW_LNS_advance_pc operand overflows.".to_string())
+                    })?;
+                state.address = state.address.wrapping_add(advance);
+            }
+            DW_LNS_ADVANCE_LINE => {
+                let advance = reader.read_sleb128()?;
+                state.line = state.line.wrapping_add_signed(advance);
+            }
+            DW_LNS_SET_FILE => state.file = reader.read_uleb128()?,
+            DW_LNS_SET_COLUMN => state.column = reader.read_uleb128()?,
+            DW_LNS_NEGATE_STMT => state.is_stmt = !state.is_stmt,
+            DW_LNS_SET_BASIC_BLOCK | DW_LNS_SET_PROLOGUE_END | DW_LNS_SET_EPILOGUE_BEGIN => {}
+            DW_LNS_CONST_ADD_PC => {
+                let adjusted = (255 - header.opcode_base) as u64;
+                state.address = state
+                    .address
+                    .wrapping_add((adjusted / line_range) * min_inst);
+            }
+            DW_LNS_FIXED_ADVANCE_PC => {
+                state.address = state.address.wrapping_add(reader.read_u16()? as u64);
+            }
+            DW_LNS_SET_ISA => {
+                reader.read_uleb128()?;
+            }
+            _ => {
+                // unknown standard opcode: skip its ULEB128 operands
+                let operands = header.standard_opcode_lengths[opcode as usize - 1];
+                for _ in 0..operands {
+                    reader.read_uleb128()?;
+                }
+            }
+        }
+    }
+    Ok(rows)
+}
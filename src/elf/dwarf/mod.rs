@@ -0,0 +1,272 @@
+//! DWARF debugging information (versions 2 to 5).
+
+pub mod addr2line;
+pub mod cfi;
+pub mod constants;
+pub mod describe;
+pub mod expr;
+pub mod form;
+pub mod info;
+pub mod layout;
+pub mod line;
+pub mod registers;
+pub mod sizes;
+pub mod typeinfo;
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use super::error::ElfError;
+use super::{ElfFile, Result, read_u16, read_u32, read_u64, read_uleb128};
+
+/// Raw contents of the `.debug_*` sections. Missing sections are empty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DwarfSections<'a> {
+    pub debug_info: &'a [u8],
+    pub debug_abbrev: &'a [u8],
+    pub debug_str: &'a [u8],
+    pub debug_line: &'a [u8],
+    pub debug_line_str: &'a [u8],
+    pub debug_str_offsets: &'a [u8],
+    pub debug_addr: &'a [u8],
+    pub debug_ranges: &'a [u8],
+    pub debug_rnglists: &'a [u8],
+    pub debug_loc: &'a [u8],
+    pub debug_loclists: &'a [u8],
+    pub big_endian: bool,
+    /// Address size of the ELF file, used when a header does not carry one.
+    pub address_size: u8,
+}
+
+impl ElfFile {
+    pub fn get_dwarf_sections(&self) -> DwarfSections<'_> {
+        // legacy compressed sections are named `.zdebug_*`
+        let section = |name: &str| {
+            self.find_section(name)
+                .or_else(|| self.find_section(&format!(".z{}", &name[1..])))
+                .and_then(|i| self.get_section_data(i))
+                .unwrap_or(&[])
+        };
+
+        DwarfSections {
+            debug_info: section(".debug_info"),
+            debug_abbrev: section(".debug_abbrev"),
+            debug_str: section(".debug_str"),
+            debug_line: section(".debug_line"),
+            debug_line_str: section(".debug_line_str"),
+            debug_str_offsets: section(".debug_str_offsets"),
+            debug_addr: section(".debug_addr"),
+            debug_ranges: section(".debug_ranges"),
+            debug_rnglists: section(".debug_rnglists"),
+            debug_loc: section(".debug_loc"),
+            debug_loclists: section(".debug_loclists"),
+            big_endian: self.is_big_endian(),
+            address_size: if self.is_64() { 8 } else { 4 },
+        }
+    }
+}
+
+/// Size and format parameters shared by all data in one unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    pub version: u16,
+    pub format_64: bool,
+    pub address_size: u8,
+}
+
+impl Encoding {
+    pub fn offset_size(&self) -> u64 {
+        if self.format_64 { 8 } else { 4 }
+    }
+}
+
+/// Cursor over a DWARF section honouring the file's byte order.
+#[derive(Debug, Clone)]
+pub struct DwarfReader<'a> {
+    cursor: Cursor<&'a [u8]>,
+    big_endian: bool,
+}
+
+impl<'a> DwarfReader<'a> {
+    pub fn new(data: &'a [u8], big_endian: bool) -> Self {
+        Self {
+            cursor: Cursor::new(data),
+            big_endian,
+        }
+    }
+
+    /// Creates a reader positioned at `offset`, failing if it lies outside
+    /// `data`.
+    pub fn at(data: &'a [u8], offset: u64, big_endian: bool) -> Result<Self> {
+        if offset > data.len() as u64 {
+            return Err(ElfError::ParseError(format!(
+                "DWARF offset 0x{:X} exceeds section range.",
+                offset
+            )));
+        }
+        let mut reader = Self::new(data, big_endian);
+        reader.cursor.set_position(offset);
+        Ok(reader)
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.cursor.get_ref()
+    }
+
+    pub fn big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    pub fn position(&self) -> u64 {
+        self.cursor.position()
+    }
+
+    pub fn set_position(&mut self, pos: u64) {
+        self.cursor.set_position(pos);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cursor.position() >= self.data().len() as u64
+    }
+
+    pub fn skip(&mut self, n: u64) -> Result<()> {
+        self.cursor.seek(SeekFrom::Current(n as i64))?;
+        if self.cursor.position() > self.data().len() as u64 {
+            return Err(ElfError::ParseError(
+                "unexpected end of DWARF data.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(byteorder::ReadBytesExt::read_u8(&mut self.cursor)?)
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8> {
+        Ok(byteorder::ReadBytesExt::read_i8(&mut self.cursor)?)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16> {
+        Ok(read_u16(&mut self.cursor, self.big_endian)?)
+    }
+
+    pub fn read_u24(&mut self) -> Result<u32> {
+        let mut b = [0u8; 3];
+        self.cursor.read_exact(&mut b)?;
+        Ok(if self.big_endian {
+            (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32
+        } else {
+            (b[2] as u32) << 16 | (b[1] as u32) << 8 | b[0] as u32
+        })
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32> {
+        Ok(read_u32(&mut self.cursor, self.big_endian)?)
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64> {
+        Ok(read_u64(&mut self.cursor, self.big_endian)?)
+    }
+
+    pub fn read_uleb128(&mut self) -> Result<u64> {
+        Ok(read_uleb128(&mut self.cursor)?)
+    }
+
+    pub fn read_sleb128(&mut self) -> Result<i64> {
+        let mut result = 0i64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift < 64 {
+                result |= ((byte & 0x7F) as i64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    result |= -1i64 << shift;
+                }
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Reads an unsigned value of `size` bytes (1, 2, 4 or 8).
+    pub fn read_sized(&mut self, size: u8) -> Result<u64> {
+        match size {
+            1 => self.read_u8().map(|v| v as u64),
+            2 => self.read_u16().map(|v| v as u64),
+            4 => self.read_u32().map(|v| v as u64),
+            8 => self.read_u64(),
+            _ => Err(ElfError::ParseError(format!(
+                "unsupported DWARF value size {}.",
+                size
+            ))),
+        }
+    }
+
+    pub fn read_address(&mut self, address_size: u8) -> Result<u64> {
+        self.read_sized(address_size)
+    }
+
+    /// Reads a section offset: 4 bytes in 32-bit DWARF, 8 bytes in 64-bit.
+    pub fn read_offset(&mut self, format_64: bool) -> Result<u64> {
+        if format_64 {
+            self.read_u64()
+        } else {
+            self.read_u32().map(|v| v as u64)
+        }
+    }
+
+    /// Reads an initial length field, returning the length and whether the
+    /// data uses the 64-bit DWARF format.
+    pub fn read_initial_length(&mut self) -> Result<(u64, bool)> {
+        let length = self.read_u32()?;
+        if length == 0xFFFFFFFF {
+            Ok((self.read_u64()?, true))
+        } else if length >= 0xFFFFFFF0 {
+            Err(ElfError::ParseError(format!(
+                "reserved DWARF initial length 0x{:X}.",
+                length
+            )))
+        } else {
+            Ok((length as u64, false))
+        }
+    }
+
+    pub fn read_bytes(&mut self, len: u64) -> Result<&'a [u8]> {
+        let start = self.cursor.position() as usize;
+        let end = start
+            .checked_add(len as usize)
+            .filter(|&end| end <= self.data().len())
+            .ok_or_else(|| ElfError::ParseError("unexpected end of DWARF data.".to_string()))?;
+        self.cursor.set_position(end as u64);
+        Ok(&self.data()[start..end])
+    }
+
+    /// Reads a NUL-terminated string.
+    pub fn read_cstr(&mut self) -> Result<&'a str> {
+        let start = self.cursor.position() as usize;
+        let data = self.data();
+        let len = data
+            .get(start..)
+            .and_then(|rest| rest.iter().position(|&c| c == 0))
+            .ok_or_else(|| ElfError::ParseError("unterminated DWARF string.".to_string()))?;
+        self.cursor.set_position((start + len + 1) as u64);
+        std::str::from_utf8(&data[start..start + len])
+            .map_err(|_| ElfError::ParseError("invalid UTF-8 in DWARF string.".to_string()))
+    }
+}
+
+/// Returns the offset of entry `index` of a table of `size`-byte entries
+/// starting at `base`.
+pub(crate) fn table_offset(base: u64, index: u64, size: u64) -> Result<u64> {
+    index
+        .checked_mul(size)
+        .and_then(|offset| base.checked_add(offset))
+        .ok_or_else(|| ElfError::ParseError(format!("DWARF index {} is out of range.", index)))
+}
+
+/// Reads the NUL-terminated string at `offset` in a string section.
+pub(crate) fn str_at(data: &[u8], offset: u64) -> Result<&str> {
+    DwarfReader::at(data, offset, false)?.read_cstr()
+}
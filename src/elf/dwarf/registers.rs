@@ -0,0 +1,53 @@
+//! DWARF register number to name mapping for common architectures.
+
+use crate::elf::types::Machine;
+
+const X86_64: [&str; 17] = [
+    "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15", "rip",
+];
+
+const I386: [&str; 9] = [
+    "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "eip",
+];
+
+const RISCV: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+/// Returns the conventional name of DWARF register `reg` on `machine`, or
+/// `r<N>` if it has none.
+pub fn register_name(machine: Machine, reg: u64) -> String {
+    let name = match machine {
+        Machine::X86_64 => X86_64.get(reg as usize).map(|s| s.to_string()),
+        Machine::I386 => I386.get(reg as usize).map(|s| s.to_string()),
+        Machine::AArch64 => match reg {
+            0..=30 => Some(format!("x{}", reg)),
+            31 => Some("sp".to_string()),
+            64..=95 => Some(format!("v{}", reg - 64)),
+            _ => None,
+        },
+        Machine::Arm => match reg {
+            0..=12 => Some(format!("r{}", reg)),
+            13 => Some("sp".to_string()),
+            14 => Some("lr".to_string()),
+            15 => Some("pc".to_string()),
+            256..=287 => Some(format!("d{}", reg - 256)),
+            _ => None,
+        },
+        Machine::RiscV => match reg {
+            0..=31 => Some(RISCV[reg as usize].to_string()),
+            32..=63 => Some(format!("f{}", reg - 32)),
+            _ => None,
+        },
+        Machine::LoongArch => match reg {
+            0..=31 => Some(format!("r{}", reg)),
+            32..=63 => Some(format!("f{}", reg - 32)),
+            _ => None,
+        },
+        _ => None,
+    };
+    name.unwrap_or_else(|| format!("r{}", reg))
+}
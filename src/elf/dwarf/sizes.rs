@@ -0,0 +1,165 @@
+//! Attribution of debug information size to sections, units and source
+//! directories.
+
+use std::collections::HashMap;
+
+use super::{DwarfReader, DwarfSections};
+use crate::elf::{ElfFile, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionSize {
+    pub name: String,
+    /// Size in the file (`sh_size`).
+    pub size: u64,
+    /// Size after decompression, which the unit sizes are measured in;
+    /// `size` for sections that are not compressed.
+    pub uncompressed_size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitSize {
+    /// Offset of the unit in `.debug_info`.
+    pub offset: u64,
+    /// Source path of the unit, joined with its compilation directory.
+    pub path: String,
+    /// Directory the unit's source file lives in.
+    pub directory: String,
+    pub info_size: u64,
+    pub line_size: u64,
+}
+
+impl UnitSize {
+    pub fn total(&self) -> u64 {
+        self.info_size.saturating_add(self.line_size)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectorySize {
+    pub directory: String,
+    pub units: usize,
+    pub info_size: u64,
+    pub line_size: u64,
+}
+
+impl DirectorySize {
+    pub fn total(&self) -> u64 {
+        self.info_size.saturating_add(self.line_size)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DebugSizes {
+    /// `.debug_*` and `.zdebug_*` sections, largest first.
+    pub sections: Vec<SectionSize>,
+    /// Compilation units, largest first.
+    pub units: Vec<UnitSize>,
+}
+
+impl DebugSizes {
+    pub fn total(&self) -> u64 {
+        self.sections
+            .iter()
+            .fold(0, |total, s| total.saturating_add(s.size))
+    }
+
+    pub fn uncompressed_total(&self) -> u64 {
+        self.sections
+            .iter()
+            .fold(0, |total, s| total.saturating_add(s.uncompressed_size))
+    }
+
+    /// Sums the unit sizes per source directory, largest first.
+    pub fn directories(&self) -> Vec<DirectorySize> {
+        let mut dirs: HashMap<&str, DirectorySize> = HashMap::new();
+        for unit in &self.units {
+            let dir = dirs
+                .entry(unit.directory.as_str())
+                .or_insert_with(|| DirectorySize {
+                    directory: unit.directory.clone(),
+                    units: 0,
+                    info_size: 0,
+                    line_size: 0,
+                });
+            dir.units += 1;
+            dir.info_size = dir.info_size.saturating_add(unit.info_size);
+            dir.line_size = dir.line_size.saturating_add(unit.line_size);
+        }
+        let mut dirs: Vec<DirectorySize> = dirs.into_values().collect();
+        dirs.sort_by(|a, b| {
+            b.total()
+                .cmp(&a.total())
+                .then(a.directory.cmp(&b.directory))
+        });
+        dirs
+    }
+}
+
+impl ElfFile {
+    /// Measures the debug sections and attributes `.debug_info` and
+    /// `.debug_line` bytes to the compilation units that own them.
+    pub fn debug_sizes(&self) -> Result<DebugSizes> {
+        let mut sections = Vec::new();
+        for (i, sh) in self.section_headers.iter().enumerate() {
+            let name = self.get_section_name(i).unwrap_or_default();
+            if name.starts_with(".debug_") || name.starts_with(".zdebug_") {
+                let uncompressed_size = self
+                    .get_section_compression(i)
+                    .map_or(sh.sh_size(), |compression| compression.uncompressed_size);
+                sections.push(SectionSize {
+                    name,
+                    size: sh.sh_size(),
+                    uncompressed_size,
+                });
+            }
+        }
+        sections.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+
+        let dwarf = self.get_dwarf_sections();
+        let mut units = Vec::new();
+        for unit in dwarf.iter_units() {
+            let unit = unit?;
+            let name = unit.name.clone().unwrap_or_else(|| "<unknown>".to_string());
+            let path = match &unit.comp_dir {
+                Some(dir) if !name.starts_with('/') => {
+                    format!("{}/{}", dir.trim_end_matches('/'), name)
+                }
+                _ => name,
+            };
+            // rustc names units `src/lib.rs/@/<codegen unit>`
+            let source = path.split("/@/").next().unwrap_or(&path);
+            let directory = match source.rfind('/') {
+                Some(0) => "/".to_string(),
+                Some(pos) => source[..pos].to_string(),
+                None => ".".to_string(),
+            };
+            let line_size = unit
+                .stmt_list
+                .and_then(|offset| line_program_size(&dwarf, offset))
+                .unwrap_or(0);
+            units.push(UnitSize {
+                offset: unit.offset,
+                path,
+                directory,
+                info_size: unit.size,
+                line_size,
+            });
+        }
+        units.sort_by(|a, b| b.total().cmp(&a.total()).then(a.offset.cmp(&b.offset)));
+
+        Ok(DebugSizes { sections, units })
+    }
+}
+
+/// Returns the size of the line program at `offset` from its length field,
+/// bounded by the end of `.debug_line`.
+fn line_program_size(sections: &DwarfSections<'_>, offset: u64) -> Option<u64> {
+    let mut reader = DwarfReader::at(sections.debug_line, offset, sections.big_endian).ok()?;
+    let (length, _) = reader.read_initial_length().ok()?;
+    let remaining = (sections.debug_line.len() as u64).checked_sub(offset)?;
+    Some(
+        (reader.position() - offset)
+            .saturating_add(length)
+            .min(remaining),
+    )
+}
@@ -0,0 +1,252 @@
+//! Type resolution over the whole `.debug_info` tree.
+
+use std::collections::HashMap;
+
+use super::DwarfSections;
+use super::constants::*;
+use super::form::AttrValue;
+use super::info::{Die, Unit};
+use crate::elf::Result;
+
+/// Bound on type chain walks, guarding against reference cycles.
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// One entry of the tree together with its position in it.
+#[derive(Debug, Clone)]
+pub struct Entry<'a> {
+    /// Index of the owning unit in [`TypeIndex::units`].
+    pub unit: usize,
+    pub die: Die<'a>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// All entries of `.debug_info` indexed by offset, so that `DW_AT_type`
+/// and other references can be followed across units.
+pub struct TypeIndex<'a> {
+    pub sections: DwarfSections<'a>,
+    pub units: Vec<Unit>,
+    pub entries: Vec<Entry<'a>>,
+    offsets: HashMap<u64, usize>,
+}
+
+impl<'a> TypeIndex<'a> {
+    pub fn new(sections: DwarfSections<'a>) -> Result<Self> {
+        let units = sections.units()?;
+        let mut entries: Vec<Entry<'a>> = Vec::new();
+        let mut offsets = HashMap::new();
+        for (unit_index, unit) in units.iter().enumerate() {
+            let mut stack: Vec<usize> = Vec::new();
+            for die in unit.entries(&sections) {
+                let die = die?;
+                stack.truncate(die.depth);
+                let index = entries.len();
+                let parent = stack.last().copied();
+                if let Some(parent) = parent {
+                    entries[parent].children.push(index);
+                }
+                if die.has_children {
+                    stack.push(index);
+                }
+                offsets.insert(die.offset, index);
+                entries.push(Entry {
+                    unit: unit_index,
+                    die,
+                    parent,
+                    children: Vec::new(),
+                });
+            }
+        }
+        Ok(Self {
+            sections,
+            units,
+            entries,
+            offsets,
+        })
+    }
+
+    /// Returns the index of the entry at `offset` in `.debug_info`.
+    pub fn find(&self, offset: u64) -> Option<usize> {
+        self.offsets.get(&offset).copied()
+    }
+
+    pub fn unit(&self, index: usize) -> &Unit {
+        &self.units[self.entries[index].unit]
+    }
+
+    /// Resolves a reference-class attribute of an entry.
+    pub fn reference(&self, index: usize, name: u64) -> Option<usize> {
+        let unit = self.unit(index);
+        match self.entries[index].die.attr(name)? {
+            AttrValue::UnitRef(off) => self.find(unit.offset.checked_add(off)?),
+            AttrValue::InfoRef(off) => self.find(off),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self, index: usize) -> Option<String> {
+        let value = self.entries[index].die.attr(DW_AT_NAME)?;
+        self.sections.attr_string(self.unit(index), value)
+    }
+
+    pub fn udata(&self, index: usize, name: u64) -> Option<u64> {
+        self.entries[index].die.attr(name)?.udata()
+    }
+
+    pub fn flag(&self, index: usize, name: u64) -> bool {
+        matches!(
+            self.entries[index].die.attr(name),
+            Some(AttrValue::Flag(true))
+        )
+    }
+
+    /// Follows `DW_AT_type`; `None` means `void`.
+    pub fn type_of(&self, index: usize) -> Option<usize> {
+        self.reference(index, DW_AT_TYPE)
+    }
+
+    /// Returns the C-like spelling of the type at `index`, e.g.
+    /// `const char *` or `struct node`.
+    pub fn type_name(&self, index: Option<usize>) -> String {
+        self.type_name_depth(index, 0)
+    }
+
+    fn type_name_depth(&self, index: Option<usize>, depth: usize) -> String {
+        let index = match index {
+            Some(index) => index,
+            None => return "void".to_string(),
+        };
+        if depth > MAX_TYPE_DEPTH {
+            return "...".to_string();
+        }
+        let inner = || self.type_name_depth(self.type_of(index), depth + 1);
+        let named = |keyword: &str| match self.name(index) {
+            Some(name) => format!("{} {}", keyword, name),
+            None => format!("{} {{...}}", keyword),
+        };
+
+        match self.entries[index].die.tag {
+            DW_TAG_STRUCTURE_TYPE => named("struct"),
+            DW_TAG_CLASS_TYPE => named("class"),
+            DW_TAG_UNION_TYPE => named("union"),
+            DW_TAG_ENUMERATION_TYPE => named("enum"),
+            DW_TAG_POINTER_TYPE | DW_TAG_REFERENCE_TYPE | DW_TAG_RVALUE_REFERENCE_TYPE => {
+                let sigil = match self.entries[index].die.tag {
+                    DW_TAG_POINTER_TYPE => "*",
+                    DW_TAG_REFERENCE_TYPE => "&",
+                    _ => "&&",
+                };
+                match self.type_of(index) {
+                    Some(target) if self.entries[target].die.tag == DW_TAG_SUBROUTINE_TYPE => {
+                        let (ret, params) = self.signature(target, depth + 1);
+                        format!("{} ({})({})", ret, sigil, params)
+                    }
+                    _ => format!("{} {}", inner(), sigil),
+                }
+            }
+            DW_TAG_CONST_TYPE => format!("const {}", inner()),
+            DW_TAG_VOLATILE_TYPE => format!("volatile {}", inner()),
+            DW_TAG_ATOMIC_TYPE => format!("_Atomic {}", inner()),
+            DW_TAG_RESTRICT_TYPE => format!("{} restrict", inner()),
+            DW_TAG_ARRAY_TYPE => {
+                let dims: String = self
+                    .array_dimensions(index)
+                    .iter()
+                    .map(|dim| match dim {
+                        Some(count) => format!("[{}]", count),
+                        None => "[]".to_string(),
+                    })
+                    .collect();
+                format!("{}{}", inner(), dims)
+            }
+            DW_TAG_SUBROUTINE_TYPE => {
+                let (ret, params) = self.signature(index, depth);
+                format!("{} ({})", ret, params)
+            }
+            DW_TAG_PTR_TO_MEMBER_TYPE => {
+                let class = self.reference(index, DW_AT_CONTAINING_TYPE);
+                format!("{} {}::*", inner(), self.type_name_depth(class, depth + 1))
+            }
+            _ => self.name(index).unwrap_or_else(|| "?".to_string()),
+        }
+    }
+
+    /// Returns the return type and parameter list of a subroutine type.
+    fn signature(&self, index: usize, depth: usize) -> (String, String) {
+        let ret = self.type_name_depth(self.type_of(index), depth + 1);
+        let params: Vec<String> = self.entries[index]
+            .children
+            .iter()
+            .filter_map(|&child| match self.entries[child].die.tag {
+                DW_TAG_FORMAL_PARAMETER => {
+                    Some(self.type_name_depth(self.type_of(child), depth + 1))
+                }
+                DW_TAG_UNSPECIFIED_PARAMETERS => Some("...".to_string()),
+                _ => None,
+            })
+            .collect();
+        let params = if params.is_empty() && self.flag(index, DW_AT_PROTOTYPED) {
+            "void".to_string()
+        } else {
+            params.join(", ")
+        };
+        (ret, params)
+    }
+
+    /// Returns the element count of each dimension of an array type, or
+    /// `None` for a dimension of unknown size.
+    pub fn array_dimensions(&self, index: usize) -> Vec<Option<u64>> {
+        self.entries[index]
+            .children
+            .iter()
+            .filter(|&&child| self.entries[child].die.tag == DW_TAG_SUBRANGE_TYPE)
+            .map(|&child| {
+                if let Some(count) = self.udata(child, DW_AT_COUNT) {
+                    return Some(count);
+                }
+                let upper = self.entries[child].die.attr(DW_AT_UPPER_BOUND)?;
+                let lower = self.udata(child, DW_AT_LOWER_BOUND).unwrap_or(0);
+                match upper {
+                    AttrValue::Udata(upper) => Some(upper.checked_add(1)?.saturating_sub(lower)),
+                    // `-1` marks a flexible array member
+                    AttrValue::Sdata(upper) if upper >= 0 => {
+                        Some((upper as u64).checked_add(1)?.saturating_sub(lower))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the size of the type at `index` in bytes, looking through
+    /// typedefs and qualifiers.
+    pub fn type_size(&self, index: usize) -> Option<u64> {
+        let mut index = index;
+        // product of the dimensions of the arrays seen so far
+        let mut count = 1u64;
+        for _ in 0..MAX_TYPE_DEPTH {
+            if let Some(size) = self.udata(index, DW_AT_BYTE_SIZE) {
+                return size.checked_mul(count);
+            }
+            match self.entries[index].die.tag {
+                DW_TAG_POINTER_TYPE
+                | DW_TAG_REFERENCE_TYPE
+                | DW_TAG_RVALUE_REFERENCE_TYPE
+                | DW_TAG_PTR_TO_MEMBER_TYPE => {
+                    return (self.unit(index).encoding.address_size as u64).checked_mul(count);
+                }
+                DW_TAG_ARRAY_TYPE => {
+                    count = self
+                        .array_dimensions(index)
+                        .iter()
+                        .try_fold(count, |count, dim| count.checked_mul((*dim)?))?;
+                    index = self.type_of(index)?;
+                }
+                DW_TAG_TYPEDEF | DW_TAG_CONST_TYPE | DW_TAG_VOLATILE_TYPE
+                | DW_TAG_RESTRICT_TYPE | DW_TAG_ATOMIC_TYPE => index = self.type_of(index)?,
+                _ => return None,
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,137 @@
+//! The dynamic section (`SHT_DYNAMIC` / `PT_DYNAMIC`) and the program
+//! interpreter (`PT_INTERP`).
+
+use std::io::Cursor;
+
+use super::types::{DynamicTag, SectionType, SegmentType};
+use super::{ElfFile, Result, read_cstr, read_word};
+
+impl DynamicTag {
+    /// Whether the value of the tag is an offset into the dynamic string
+    /// table.
+    pub fn is_string(self) -> bool {
+        matches!(
+            self,
+            DynamicTag::Needed | DynamicTag::SoName | DynamicTag::RPath | DynamicTag::RunPath
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynamicEntry {
+    pub d_tag: u64,
+    /// `d_val` or `d_ptr`.
+    pub d_val: u64,
+}
+
+impl DynamicEntry {
+    pub fn get_tag(&self) -> DynamicTag {
+        DynamicTag::from(self.d_tag)
+    }
+}
+
+/// The string-valued entries of the dynamic section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DynamicInfo {
+    pub needed: Vec<String>,
+    pub soname: Option<String>,
+    pub rpath: Option<String>,
+    pub runpath: Option<String>,
+}
+
+impl ElfFile {
+    /// Returns the index of the `SHT_DYNAMIC` section.
+    pub fn dynamic_section_index(&self) -> Option<usize> {
+        self.section_headers
+            .iter()
+            .position(|sh| sh.get_type() == SectionType::Dynamic)
+    }
+
+    /// Returns the raw contents of the dynamic section, falling back to the
+    /// `PT_DYNAMIC` segment if there are no section headers.
+    fn dynamic_data(&self) -> Option<&[u8]> {
+        if let Some(index) = self.dynamic_section_index() {
+            return self.get_section_data(index);
+        }
+        let ph = self
+            .program_headers
+            .iter()
+            .find(|ph| ph.get_type() == SegmentType::Dynamic)?;
+        let start = usize::try_from(ph.p_offset()).ok()?;
+        self.data
+            .get(start..start.checked_add(usize::try_from(ph.p_filesz()).ok()?)?)
+    }
+
+    /// Reads the dynamic entries up to, and not including, the first
+    /// `DT_NULL`.
+    pub fn get_dynamic_entries(&self) -> Result<Vec<DynamicEntry>> {
+        let Some(data) = self.dynamic_data() else {
+            return Ok(Vec::new());
+        };
+        let is_64 = self.is_64();
+        let be = self.is_big_endian();
+        let entsize = if is_64 { 16 } else { 8 };
+
+        let mut entries = Vec::with_capacity(data.len() / entsize);
+        for entry in data.chunks_exact(entsize) {
+            let mut reader = Cursor::new(entry);
+            let d_tag = read_word(&mut reader, is_64, be)?;
+            let d_val = read_word(&mut reader, is_64, be)?;
+            if d_tag == 0 {
+                break;
+            }
+            entries.push(DynamicEntry { d_tag, d_val });
+        }
+        Ok(entries)
+    }
+
+    /// Returns the dynamic string table: the section linked from the dynamic
+    /// section, or the `DT_STRTAB`/`DT_STRSZ` range.
+    pub fn get_dynamic_strings(&self) -> Result<Option<&[u8]>> {
+        if let Some(index) = self.dynamic_section_index() {
+            let link = self.section_headers[index].sh_link() as usize;
+            if link != 0 && link < self.section_headers.len() {
+                return Ok(self.get_section_data(link));
+            }
+        }
+        let entries = self.get_dynamic_entries()?;
+        let value = |tag: DynamicTag| entries.iter().find(|e| e.get_tag() == tag).map(|e| e.d_val);
+        Ok(
+            match (value(DynamicTag::StrTab), value(DynamicTag::StrSz)) {
+                (Some(address), Some(size)) => self.get_virtual_data(address, size),
+                _ => None,
+            },
+        )
+    }
+
+    /// Collects the needed libraries, SONAME, RPATH and RUNPATH.
+    pub fn get_dynamic_info(&self) -> Result<DynamicInfo> {
+        let entries = self.get_dynamic_entries()?;
+        let strings = self.get_dynamic_strings()?.unwrap_or(&[]);
+        let mut info = DynamicInfo::default();
+        for entry in entries {
+            let string = || read_cstr(strings, entry.d_val as usize).unwrap_or_default();
+            match entry.get_tag() {
+                DynamicTag::Needed => info.needed.push(string()),
+                DynamicTag::SoName => info.soname = Some(string()),
+                DynamicTag::RPath => info.rpath = Some(string()),
+                DynamicTag::RunPath => info.runpath = Some(string()),
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    /// Returns the program interpreter named by `PT_INTERP`.
+    pub fn get_interpreter(&self) -> Option<String> {
+        let ph = self
+            .program_headers
+            .iter()
+            .find(|ph| ph.get_type() == SegmentType::Interp)?;
+        let start = usize::try_from(ph.p_offset()).ok()?;
+        let data = self
+            .data
+            .get(start..start.checked_add(usize::try_from(ph.p_filesz()).ok()?)?)?;
+        read_cstr(data, 0)
+    }
+}
@@ -0,0 +1,393 @@
+//! patchelf-style editing of the program interpreter and the string entries
+//! of the dynamic section (`DT_NEEDED`, `DT_SONAME`, `DT_RPATH` and
+//! `DT_RUNPATH`).
+//!
+//! Strings are overwritten in place when the new value is no longer than
+//! the old one and nothing else refers to it; otherwise they are appended
+//! to `.dynstr`. Sections that outgrow their place (`.dynstr`, `.dynamic`,
+//! `.interp`) are moved into a new `PT_LOAD` segment.
+
+use std::io::Cursor;
+
+use super::dynamic::DynamicEntry;
+use super::error::ElfError;
+use super::types::{DynamicTag, SectionType, SegmentType};
+use super::versions::version_entries;
+use super::writer::ElfWriter;
+use super::{ElfFile, Result, field_u32, read_cstr, write_u32, write_word};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicEdit {
+    SetInterpreter(String),
+    SetSoname(String),
+    SetRpath(String),
+    RemoveRpath,
+    SetRunpath(String),
+    RemoveRunpath,
+    AddNeeded(String),
+    RemoveNeeded(String),
+    ReplaceNeeded(String, String),
+}
+
+/// A reference into the dynamic string table from a version section: the
+/// position of the `u32` field and the string offset it holds.
+#[derive(Debug, Clone, Copy)]
+struct VersionRef {
+    section: usize,
+    position: usize,
+    offset: u32,
+    /// `vn_file` of a `Verneed` entry, which names a needed library.
+    is_file: bool,
+}
+
+/// Collects the fields of a `SHT_GNU_verneed` or `SHT_GNU_verdef` section
+/// that hold dynamic string offsets.
+fn version_refs(section: usize, data: &[u8], verneed: bool, be: bool) -> Vec<VersionRef> {
+    let name_at = if verneed { 8 } else { 0 };
+    let mut refs = Vec::new();
+    for entry in version_entries(data, verneed, be) {
+        if verneed && let Some(offset) = field_u32(data, entry.position + 4, be) {
+            refs.push(VersionRef {
+                section,
+                position: entry.position + 4,
+                offset,
+                is_file: true,
+            });
+        }
+        for aux in entry.aux {
+            if let Some(offset) = field_u32(data, aux + name_at, be) {
+                refs.push(VersionRef {
+                    section,
+                    position: aux + name_at,
+                    offset,
+                    is_file: false,
+                });
+            }
+        }
+    }
+    refs
+}
+
+/// The dynamic string table being edited.
+struct StringTable {
+    data: Vec<u8>,
+    /// Offsets referenced by symbols and version names, which never change.
+    fixed: Vec<u64>,
+}
+
+impl StringTable {
+    fn get(&self, offset: u64) -> String {
+        read_cstr(&self.data, offset as usize).unwrap_or_default()
+    }
+
+    /// Returns the offset of `value`, reusing an existing string (or string
+    /// suffix) if there is one.
+    fn add(&mut self, value: &str) -> u64 {
+        let needle: Vec<u8> = value.bytes().chain([0]).collect();
+        if let Some(offset) = self.data.windows(needle.len()).position(|w| w == needle) {
+            return offset as u64;
+        }
+        let offset = self.data.len() as u64;
+        self.data.extend_from_slice(&needle);
+        offset
+    }
+
+    /// Replaces the string at `offset`, which is referenced from `others`
+    /// as well. It is overwritten in place if the new value fits and no
+    /// other reference reaches into it.
+    fn replace(&mut self, offset: u64, value: &str, others: &[u64]) -> u64 {
+        let old_len = self.get(offset).len() as u64;
+        let starts_string = offset == 0 || self.data.get(offset as usize - 1) == Some(&0);
+        let shared = self
+            .fixed
+            .iter()
+            .chain(others)
+            .any(|&o| o >= offset && o <= offset + old_len);
+        if offset != 0 && starts_string && !shared && value.len() as u64 <= old_len {
+            let start = offset as usize;
+            let end = start + old_len as usize;
+            self.data[start..end].fill(0);
+            self.data[start..start + value.len()].copy_from_slice(value.as_bytes());
+            return offset;
+        }
+        self.add(value)
+    }
+}
+
+/// The dynamic section and its string table, edited in place or moved.
+struct DynamicUpdate {
+    dynamic: usize,
+    dynstr: usize,
+    entries: Vec<DynamicEntry>,
+}
+
+impl ElfFile {
+    /// Applies `edits` in order and returns the new file image.
+    pub fn edit_dynamic(&self, edits: &[DynamicEdit]) -> Result<Vec<u8>> {
+        let mut writer = ElfWriter::new(self);
+        let mut moved = Vec::new();
+
+        for edit in edits {
+            if let DynamicEdit::SetInterpreter(path) = edit {
+                let index = self.interp_section_index()?;
+                let section = &mut writer.sections[index];
+                let mut data = path.as_bytes().to_vec();
+                data.push(0);
+                if data.len() <= section.data.len() {
+                    data.resize(section.data.len(), 0);
+                } else if !moved.contains(&index) {
+                    moved.push(index);
+                }
+                section.data = data;
+            }
+        }
+
+        let update = if edits
+            .iter()
+            .any(|edit| !matches!(edit, DynamicEdit::SetInterpreter(_)))
+        {
+            Some(self.edit_dynamic_entries(&mut writer, edits, &mut moved)?)
+        } else {
+            None
+        };
+        if !moved.is_empty() {
+            writer.move_to_new_segment(&moved)?;
+        }
+
+        // written last, as DT_STRTAB follows a moved .dynstr
+        if let Some(update) = update {
+            let is_64 = self.is_64();
+            let be = self.is_big_endian();
+            let strtab = &writer.sections[update.dynstr];
+            let (strtab_addr, strtab_size) = (strtab.header.sh_addr(), strtab.data.len() as u64);
+            let mut data = Cursor::new(&mut writer.sections[update.dynamic].data);
+            for entry in &update.entries {
+                let value = match entry.get_tag() {
+                    DynamicTag::StrTab => strtab_addr,
+                    DynamicTag::StrSz => strtab_size,
+                    _ => entry.d_val,
+                };
+                write_word(&mut data, entry.d_tag, is_64, be)?;
+                write_word(&mut data, value, is_64, be)?;
+            }
+        }
+        writer.write()
+    }
+
+    /// Applies the dynamic entry edits. The new strings and version
+    /// references are stored in `writer`, and `.dynamic` is resized (but
+    /// left empty) for the returned entries. Sections that outgrow their
+    /// place are added to `moved`.
+    fn edit_dynamic_entries(
+        &self,
+        writer: &mut ElfWriter,
+        edits: &[DynamicEdit],
+        moved: &mut Vec<usize>,
+    ) -> Result<DynamicUpdate> {
+        let is_64 = self.is_64();
+        let be = self.is_big_endian();
+        let dynamic = self
+            .dynamic_section_index()
+            .ok_or_else(|| ElfError::ParseError("the file has no dynamic section.".to_string()))?;
+        let dynstr = self.section_headers[dynamic].sh_link() as usize;
+        if dynstr == 0 || dynstr >= self.section_headers.len() {
+            return Err(ElfError::ParseError(
+                "the dynamic section is not linked to a string table.".to_string(),
+            ));
+        }
+
+        // string references that are not dynamic entries
+        let mut fixed = Vec::new();
+        let mut version_files = Vec::new();
+        for (index, sh) in self.section_headers.iter().enumerate() {
+            if sh.sh_link() as usize != dynstr {
+                continue;
+            }
+            let data = &writer.sections[index].data;
+            match sh.get_type() {
+                SectionType::Dynsym => {
+                    let entsize = if is_64 { 24 } else { 16 };
+                    fixed.extend(
+                        data.chunks_exact(entsize)
+                            .filter_map(|entry| field_u32(entry, 0, be))
+                            .map(u64::from),
+                    );
+                }
+                SectionType::GnuVerneed | SectionType::GnuVerdef => {
+                    let verneed = sh.get_type() == SectionType::GnuVerneed;
+                    for r in version_refs(index, data, verneed, be) {
+                        if r.is_file {
+                            version_files.push(r);
+                        } else {
+                            fixed.push(r.offset as u64);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut strings = StringTable {
+            data: writer.sections[dynstr].data.clone(),
+            fixed,
+        };
+        let mut entries = self.get_dynamic_entries()?;
+        let entsize = if is_64 { 16 } else { 8 };
+        let slots = writer.sections[dynamic].data.len() / entsize;
+        let string_offsets = |entries: &[DynamicEntry], skip: usize| -> Vec<u64> {
+            entries
+                .iter()
+                .enumerate()
+                .filter(|(i, e)| *i != skip && e.get_tag().is_string())
+                .map(|(_, e)| e.d_val)
+                .collect()
+        };
+        // new entries go after the last DT_NEEDED, DT_SONAME, DT_RPATH or
+        // DT_RUNPATH entry, like linkers place them
+        let insert_at = |entries: &[DynamicEntry]| {
+            entries
+                .iter()
+                .rposition(|e| e.get_tag().is_string())
+                .map_or(0, |i| i + 1)
+        };
+
+        for edit in edits {
+            let (tag, value) = match edit {
+                DynamicEdit::SetInterpreter(_) => continue,
+                DynamicEdit::SetSoname(name) => (DynamicTag::SoName, name),
+                DynamicEdit::SetRpath(path) => (DynamicTag::RPath, path),
+                DynamicEdit::SetRunpath(path) => (DynamicTag::RunPath, path),
+                DynamicEdit::RemoveRpath | DynamicEdit::RemoveRunpath => {
+                    let tag = if *edit == DynamicEdit::RemoveRpath {
+                        DynamicTag::RPath
+                    } else {
+                        DynamicTag::RunPath
+                    };
+                    entries.retain(|e| e.get_tag() != tag);
+                    continue;
+                }
+                DynamicEdit::AddNeeded(name) => {
+                    let exists = entries.iter().any(|e| {
+                        e.get_tag() == DynamicTag::Needed && strings.get(e.d_val) == *name
+                    });
+                    if !exists {
+                        let position = entries
+                            .iter()
+                            .rposition(|e| e.get_tag() == DynamicTag::Needed)
+                            .map_or(0, |i| i + 1);
+                        let offset = strings.add(name);
+                        entries.insert(
+                            position,
+                            DynamicEntry {
+                                d_tag: DynamicTag::Needed.into(),
+                                d_val: offset,
+                            },
+                        );
+                    }
+                    continue;
+                }
+                DynamicEdit::RemoveNeeded(name) => {
+                    let before = entries.len();
+                    entries.retain(|e| {
+                        e.get_tag() != DynamicTag::Needed || strings.get(e.d_val) != *name
+                    });
+                    if entries.len() == before {
+                        return Err(ElfError::ParseError(format!(
+                            "{} is not a needed library.",
+                            name
+                        )));
+                    }
+                    continue;
+                }
+                DynamicEdit::ReplaceNeeded(old, new) => {
+                    let Some(i) = entries.iter().position(|e| {
+                        e.get_tag() == DynamicTag::Needed && strings.get(e.d_val) == *old
+                    }) else {
+                        return Err(ElfError::ParseError(format!(
+                            "{} is not a needed library.",
+                            old
+                        )));
+                    };
+                    let offset = entries[i].d_val;
+                    // the version requirements name the library too
+                    let mut others = string_offsets(&entries, i);
+                    others.extend(
+                        version_files
+                            .iter()
+                            .filter(|r| strings.get(r.offset as u64) != *old)
+                            .map(|r| r.offset as u64),
+                    );
+                    let new_offset = strings.replace(offset, new, &others);
+                    entries[i].d_val = new_offset;
+                    for r in &mut version_files {
+                        if r.offset as u64 == offset {
+                            r.offset = new_offset as u32;
+                        }
+                    }
+                    continue;
+                }
+            };
+            match entries.iter().position(|e| e.get_tag() == tag) {
+                Some(i) => {
+                    let mut others = string_offsets(&entries, i);
+                    others.extend(version_files.iter().map(|r| r.offset as u64));
+                    entries[i].d_val = strings.replace(entries[i].d_val, value, &others);
+                }
+                None => {
+                    let offset = strings.add(value);
+                    let position = insert_at(&entries);
+                    entries.insert(
+                        position,
+                        DynamicEntry {
+                            d_tag: tag.into(),
+                            d_val: offset,
+                        },
+                    );
+                }
+            }
+        }
+
+        for r in &version_files {
+            let data = &mut writer.sections[r.section].data;
+            write_u32(
+                &mut Cursor::new(&mut data[r.position..r.position + 4]),
+                r.offset,
+                be,
+            )?;
+        }
+
+        if strings.data.len() > writer.sections[dynstr].data.len() {
+            moved.push(dynstr);
+        }
+        writer.sections[dynstr].data = strings.data;
+        let count = if entries.len() < slots {
+            slots
+        } else {
+            moved.push(dynamic);
+            entries.len() + 1
+        };
+        writer.sections[dynamic].data = vec![0; count * entsize];
+        Ok(DynamicUpdate {
+            dynamic,
+            dynstr,
+            entries,
+        })
+    }
+
+    /// Returns the index of the section holding the `PT_INTERP` string.
+    fn interp_section_index(&self) -> Result<usize> {
+        let ph = self
+            .program_headers
+            .iter()
+            .find(|ph| ph.get_type() == SegmentType::Interp)
+            .ok_or_else(|| {
+                ElfError::ParseError("the file has no PT_INTERP segment.".to_string())
+            })?;
+        self.section_headers
+            .iter()
+            .position(|sh| sh.sh_offset() == ph.p_offset() && sh.get_type() != SectionType::Nobits)
+            .ok_or_else(|| {
+                ElfError::ParseError("no section holds the PT_INTERP string.".to_string())
+            })
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+
+use super::types::Machine;
+
+// RISC-V
+pub const EF_RISCV_RVC: u32 = 0x0001;
+pub const EF_RISCV_FLOAT_ABI: u32 = 0x0006;
+pub const EF_RISCV_RVE: u32 = 0x0008;
+pub const EF_RISCV_TSO: u32 = 0x0010;
+
+// ARM
+pub const EF_ARM_EABIMASK: u32 = 0xFF000000;
+pub const EF_ARM_BE8: u32 = 0x00800000;
+pub const EF_ARM_LE8: u32 = 0x00400000;
+pub const EF_ARM_ABI_FLOAT_SOFT: u32 = 0x00000200;
+pub const EF_ARM_ABI_FLOAT_HARD: u32 = 0x00000400;
+pub const EF_ARM_INTERWORK: u32 = 0x00000004;
+pub const EF_ARM_APCS_26: u32 = 0x00000008;
+pub const EF_ARM_APCS_FLOAT: u32 = 0x00000010;
+pub const EF_ARM_PIC: u32 = 0x00000020;
+
+// MIPS
+pub const EF_MIPS_NOREORDER: u32 = 0x00000001;
+pub const EF_MIPS_PIC: u32 = 0x00000002;
+pub const EF_MIPS_CPIC: u32 = 0x00000004;
+pub const EF_MIPS_XGOT: u32 = 0x00000008;
+pub const EF_MIPS_UCODE: u32 = 0x00000010;
+pub const EF_MIPS_ABI2: u32 = 0x00000020;
+pub const EF_MIPS_OPTIONS_FIRST: u32 = 0x00000080;
+pub const EF_MIPS_32BITMODE: u32 = 0x00000100;
+pub const EF_MIPS_FP64: u32 = 0x00000200;
+pub const EF_MIPS_NAN2008: u32 = 0x00000400;
+pub const EF_MIPS_ABI: u32 = 0x0000F000;
+pub const EF_MIPS_MACH: u32 = 0x00FF0000;
+pub const EF_MIPS_ARCH_ASE: u32 = 0x0F000000;
+pub const EF_MIPS_ARCH: u32 = 0xF0000000;
+
+// PowerPC / PowerPC64
+pub const EF_PPC_EMB: u32 = 0x80000000;
+pub const EF_PPC_RELOCATABLE: u32 = 0x00010000;
+pub const EF_PPC_RELOCATABLE_LIB: u32 = 0x00008000;
+pub const EF_PPC64_ABI: u32 = 0x00000003;
+
+// LoongArch
+pub const EF_LARCH_ABI_MODIFIER_MASK: u32 = 0x00000007;
+pub const EF_LARCH_OBJABI_MASK: u32 = 0x000000C0;
+
+/// Processor-specific file flags (`e_flags`), decoded according to the
+/// machine they belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EFlags {
+    pub machine: Machine,
+    pub raw: u32,
+}
+
+impl EFlags {
+    pub fn new(machine: Machine, raw: u32) -> Self {
+        Self { machine, raw }
+    }
+
+    /// Returns the names of all flags set in `raw`. Bits that have no
+    /// meaning for the machine are reported as a trailing "unknown" entry.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let known = match self.machine {
+            Machine::RiscV => decode_riscv(self.raw, &mut names),
+            Machine::Arm => decode_arm(self.raw, &mut names),
+            Machine::Mips | Machine::MipsRs3Le => decode_mips(self.raw, &mut names),
+            Machine::PowerPc => decode_ppc(self.raw, &mut names),
+            Machine::PowerPc64 => decode_ppc64(self.raw, &mut names),
+            Machine::LoongArch => decode_loongarch(self.raw, &mut names),
+            // The AArch64 psABI does not define any e_flags bits.
+            Machine::AArch64 => 0,
+            _ => 0,
+        };
+
+        let unknown = self.raw & !known;
+        if unknown != 0 {
+            names.push(format!("unknown flags 0x{:X}", unknown));
+        }
+        names
+    }
+}
+
+impl fmt::Display for EFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:X}", self.raw)?;
+        for name in self.names() {
+            write!(f, ", {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+fn decode_riscv(flags: u32, names: &mut Vec<String>) -> u32 {
+    if flags & EF_RISCV_RVC != 0 {
+        names.push("RVC".to_string());
+    }
+
+    names.push(
+        match flags & EF_RISCV_FLOAT_ABI {
+            0x0 => "soft-float ABI",
+            0x2 => "single-float ABI",
+            0x4 => "double-float ABI",
+            _ => "quad-float ABI",
+        }
+        .to_string(),
+    );
+
+    if flags & EF_RISCV_RVE != 0 {
+        names.push("RVE".to_string());
+    }
+    if flags & EF_RISCV_TSO != 0 {
+        names.push("TSO".to_string());
+    }
+
+    EF_RISCV_RVC | EF_RISCV_FLOAT_ABI | EF_RISCV_RVE | EF_RISCV_TSO
+}
+
+fn decode_arm(flags: u32, names: &mut Vec<String>) -> u32 {
+    let version = (flags & EF_ARM_EABIMASK) >> 24;
+    match version {
+        0 => {
+            names.push("GNU EABI".to_string());
+            let gnu = [
+                (EF_ARM_INTERWORK, "interworking enabled"),
+                (EF_ARM_APCS_26, "uses APCS/26"),
+                (EF_ARM_APCS_FLOAT, "uses APCS/float"),
+                (EF_ARM_PIC, "position independent"),
+            ];
+            let mut known = EF_ARM_EABIMASK;
+            for (bit, name) in gnu {
+                known |= bit;
+                if flags & bit != 0 {
+                    names.push(name.to_string());
+                }
+            }
+            known
+        }
+        5 => {
+            names.push("Version5 EABI".to_string());
+            if flags & EF_ARM_ABI_FLOAT_SOFT != 0 {
+                names.push("soft-float ABI".to_string());
+            }
+            if flags & EF_ARM_ABI_FLOAT_HARD != 0 {
+                names.push("hard-float ABI".to_string());
+            }
+            if flags & EF_ARM_BE8 != 0 {
+                names.push("BE8".to_string());
+            }
+            if flags & EF_ARM_LE8 != 0 {
+                names.push("LE8".to_string());
+            }
+            EF_ARM_EABIMASK
+                | EF_ARM_ABI_FLOAT_SOFT
+                | EF_ARM_ABI_FLOAT_HARD
+                | EF_ARM_BE8
+                | EF_ARM_LE8
+        }
+        v => {
+            names.push(format!("Version{} EABI", v));
+            if flags & EF_ARM_BE8 != 0 {
+                names.push("BE8".to_string());
+            }
+            if flags & EF_ARM_LE8 != 0 {
+                names.push("LE8".to_string());
+            }
+            EF_ARM_EABIMASK | EF_ARM_BE8 | EF_ARM_LE8
+        }
+    }
+}
+
+fn decode_mips(flags: u32, names: &mut Vec<String>) -> u32 {
+    let arch = match flags & EF_MIPS_ARCH {
+        0x00000000 => Some("mips1"),
+        0x10000000 => Some("mips2"),
+        0x20000000 => Some("mips3"),
+        0x30000000 => Some("mips4"),
+        0x40000000 => Some("mips5"),
+        0x50000000 => Some("mips32"),
+        0x60000000 => Some("mips64"),
+        0x70000000 => Some("mips32r2"),
+        0x80000000 => Some("mips64r2"),
+        0x90000000 => Some("mips32r6"),
+        0xA0000000 => Some("mips64r6"),
+        _ => None,
+    };
+    match arch {
+        Some(arch) => names.push(arch.to_string()),
+        None => names.push(format!("unknown ISA 0x{:X}", (flags & EF_MIPS_ARCH) >> 28)),
+    }
+
+    let mach = match flags & EF_MIPS_MACH {
+        0x00000000 => None,
+        0x00810000 => Some("3900"),
+        0x00820000 => Some("4010"),
+        0x00830000 => Some("4100"),
+        0x00850000 => Some("4650"),
+        0x00870000 => Some("4120"),
+        0x00880000 => Some("4111"),
+        0x008A0000 => Some("sb1"),
+        0x008B0000 => Some("octeon"),
+        0x008C0000 => Some("xlr"),
+        0x008D0000 => Some("octeon2"),
+        0x008E0000 => Some("octeon3"),
+        0x00910000 => Some("5400"),
+        0x00920000 => Some("5900"),
+        0x00980000 => Some("5500"),
+        0x00990000 => Some("9000"),
+        0x00A00000 => Some("loongson-2e"),
+        0x00A10000 => Some("loongson-2f"),
+        0x00A20000 => Some("gs464"),
+        _ => Some("unknown CPU"),
+    };
+    if let Some(mach) = mach {
+        names.push(mach.to_string());
+    }
+
+    let abi = match flags & EF_MIPS_ABI {
+        0x0000 if flags & EF_MIPS_ABI2 != 0 => Some("n32"),
+        0x0000 => None,
+        0x1000 => Some("o32"),
+        0x2000 => Some("o64"),
+        0x3000 => Some("eabi32"),
+        0x4000 => Some("eabi64"),
+        _ => Some("unknown ABI"),
+    };
+    if let Some(abi) = abi {
+        names.push(abi.to_string());
+    }
+
+    let ase = [
+        (0x08000000, "mdmx"),
+        (0x04000000, "mips16"),
+        (0x02000000, "micromips"),
+    ];
+    for (bit, name) in ase {
+        if flags & bit != 0 {
+            names.push(name.to_string());
+        }
+    }
+
+    let bits = [
+        (EF_MIPS_NOREORDER, "noreorder"),
+        (EF_MIPS_PIC, "pic"),
+        (EF_MIPS_CPIC, "cpic"),
+        (EF_MIPS_XGOT, "xgot"),
+        (EF_MIPS_UCODE, "ugen_reserved"),
+        (EF_MIPS_OPTIONS_FIRST, "odk first"),
+        (EF_MIPS_32BITMODE, "32bitmode"),
+        (EF_MIPS_FP64, "fp64"),
+        (EF_MIPS_NAN2008, "nan2008"),
+    ];
+    let mut known = EF_MIPS_ABI2 | EF_MIPS_ABI | EF_MIPS_MACH | EF_MIPS_ARCH_ASE | EF_MIPS_ARCH;
+    for (bit, name) in bits {
+        known |= bit;
+        if flags & bit != 0 {
+            names.push(name.to_string());
+        }
+    }
+    known
+}
+
+fn decode_ppc(flags: u32, names: &mut Vec<String>) -> u32 {
+    if flags & EF_PPC_EMB != 0 {
+        names.push("emb".to_string());
+    }
+    if flags & EF_PPC_RELOCATABLE != 0 {
+        names.push("relocatable".to_string());
+    }
+    if flags & EF_PPC_RELOCATABLE_LIB != 0 {
+        names.push("relocatable-lib".to_string());
+    }
+    EF_PPC_EMB | EF_PPC_RELOCATABLE | EF_PPC_RELOCATABLE_LIB
+}
+
+fn decode_ppc64(flags: u32, names: &mut Vec<String>) -> u32 {
+    match flags & EF_PPC64_ABI {
+        0 => names.push("unspecified or ELFv1 ABI".to_string()),
+        1 => names.push("ELFv1 ABI".to_string()),
+        2 => names.push("ELFv2 ABI".to_string()),
+        v => names.push(format!("unknown ABI {}", v)),
+    }
+    EF_PPC64_ABI
+}
+
+fn decode_loongarch(flags: u32, names: &mut Vec<String>) -> u32 {
+    match flags & EF_LARCH_ABI_MODIFIER_MASK {
+        1 => names.push("SOFT-FLOAT".to_string()),
+        2 => names.push("SINGLE-FLOAT".to_string()),
+        3 => names.push("DOUBLE-FLOAT".to_string()),
+        v => names.push(format!("unknown ABI modifier {}", v)),
+    }
+    match (flags & EF_LARCH_OBJABI_MASK) >> 6 {
+        0 => names.push("OBJ-v0".to_string()),
+        1 => names.push("OBJ-v1".to_string()),
+        v => names.push(format!("unknown object ABI version {}", v)),
+    }
+    EF_LARCH_ABI_MODIFIER_MASK | EF_LARCH_OBJABI_MASK
+}
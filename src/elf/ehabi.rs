@@ -0,0 +1,501 @@
+//! ARM exception handling ABI unwind tables (`.ARM.exidx` and `.ARM.extab`).
+//!
+//! Each index entry holds a prel31 offset to the start of a function and
+//! either `EXIDX_CANTUNWIND`, an inline compact-model description, or a
+//! prel31 offset to its `.ARM.extab` entry. Relocatable objects keep those
+//! offsets in `R_ARM_PREL31` relocations, which are resolved here as well.
+
+use std::collections::HashMap;
+
+use super::error::ElfError;
+use super::relocations::Relocation;
+use super::symbols::Symbol;
+use super::types::{ElfType, Machine, SectionType, SegmentType};
+use super::{ElfFile, Result};
+
+pub const SHT_ARM_EXIDX: u32 = 0x70000001;
+pub const PT_ARM_EXIDX: u32 = 0x70000001;
+pub const EXIDX_CANTUNWIND: u32 = 1;
+pub const R_ARM_PREL31: u32 = 42;
+
+/// Personality routines whose data starts with GCC's unwind opcode words.
+const GNU_PERSONALITIES: [&str; 4] = [
+    "__gcc_personality_v0",
+    "__gxx_personality_v0",
+    "__gcj_personality_v0",
+    "__gnu_objc_personality_v0",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Personality {
+    /// ARM-defined compact model routine `__aeabi_unwind_cpp_pr<N>`.
+    Compact(u8),
+    /// Generic model routine given by its address or symbol.
+    Routine {
+        address: u64,
+        symbol: Option<String>,
+    },
+}
+
+impl Personality {
+    pub fn name(&self) -> String {
+        match self {
+            Personality::Compact(index) => format!("__aeabi_unwind_cpp_pr{}", index),
+            Personality::Routine {
+                symbol: Some(symbol),
+                ..
+            } => symbol.clone(),
+            Personality::Routine { address, .. } => format!("0x{:x}", address),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwindDescription {
+    pub personality: Personality,
+    /// Unwind opcodes in execution order. Empty when the personality data
+    /// is not in a known format.
+    pub opcodes: Vec<u8>,
+    /// Language-specific data of GNU personality routines.
+    pub lsda: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExidxUnwind {
+    CantUnwind,
+    /// Compact model description stored in the index entry itself.
+    Inline(UnwindDescription),
+    /// Description in `.ARM.extab`; `None` if it cannot be read.
+    Table {
+        address: u64,
+        description: Option<UnwindDescription>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExidxEntry {
+    /// Address of the function (section offset in relocatable files).
+    pub function: u64,
+    /// Function symbol covering `function`.
+    pub symbol: Option<String>,
+    pub unwind: ExidxUnwind,
+}
+
+/// A location in the image: a section and an address inside it, or a bare
+/// virtual address when the file has no section headers.
+#[derive(Debug, Clone, Copy)]
+struct Place {
+    section: Option<usize>,
+    address: u64,
+}
+
+impl Place {
+    fn offset(self, n: u64) -> Self {
+        Place {
+            section: self.section,
+            address: self.address.wrapping_add(n),
+        }
+    }
+}
+
+/// The target of a prel31 field, with the symbol a relocation names.
+struct Target {
+    place: Place,
+    symbol: Option<String>,
+}
+
+/// Sign-extends a prel31 value.
+fn prel31(word: u32) -> i64 {
+    (((word << 1) as i32) >> 1) as i64
+}
+
+struct ExidxReader<'a> {
+    elf: &'a ElfFile,
+    machine: Machine,
+    symbols: Vec<Symbol>,
+    /// Relocations of relocatable files, keyed by section and offset.
+    relocations: HashMap<(usize, u64), Relocation>,
+}
+
+impl<'a> ExidxReader<'a> {
+    fn new(elf: &'a ElfFile) -> Result<Self> {
+        let mut relocations = HashMap::new();
+        if elf.get_type() == ElfType::Relocatable {
+            for (index, sh) in elf.section_headers.iter().enumerate() {
+                if matches!(sh.get_type(), SectionType::Rel | SectionType::Rela) {
+                    for rel in elf.read_relocations(index)? {
+                        relocations.insert((sh.sh_info() as usize, rel.r_offset), rel);
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            elf,
+            machine: elf.get_machine(),
+            symbols: elf.get_symbols()?,
+            relocations,
+        })
+    }
+
+    fn read_word(&self, place: Place) -> Option<u32> {
+        let bytes = match place.section {
+            Some(index) => {
+                let sh = self.elf.section_headers.get(index)?;
+                let start = usize::try_from(place.address.checked_sub(sh.sh_addr())?).ok()?;
+                self.elf
+                    .get_section_data(index)?
+                    .get(start..start.checked_add(4)?)?
+            }
+            None => self.elf.get_virtual_data(place.address, 4)?,
+        };
+        let bytes: [u8; 4] = bytes.try_into().ok()?;
+        Some(if self.elf.is_big_endian() {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn section_address(&self, index: usize) -> u64 {
+        self.elf
+            .section_headers
+            .get(index)
+            .map_or(0, |sh| sh.sh_addr())
+    }
+
+    /// Resolves the prel31 field `word` stored at `place`.
+    fn resolve_prel31(&self, place: Place, word: u32) -> Target {
+        let reloc = place.section.and_then(|section| {
+            let offset = place.address.checked_sub(self.section_address(section))?;
+            self.relocations.get(&(section, offset))
+        });
+        if let Some(rel) = reloc
+            && rel.r_type == R_ARM_PREL31
+            && let Some(symbol) = self.symbols.get(rel.symbol as usize)
+        {
+            let addend = rel.addend.unwrap_or(prel31(word));
+            let section = symbol.section_index();
+            let address = section
+                .map_or(0, |s| self.section_address(s))
+                .wrapping_add(symbol.st_value)
+                .wrapping_add(addend as u64);
+            let named = !symbol.name.is_empty() && symbol.section_index().is_none()
+                || symbol.is_function() && addend == 0;
+            return Target {
+                place: Place { section, address },
+                symbol: named.then(|| symbol.name.clone()),
+            };
+        }
+
+        let address = place.address.wrapping_add(prel31(word) as u64) & 0xFFFF_FFFF;
+        let section = match place.section {
+            Some(_) => self.elf.section_headers.iter().position(|sh| {
+                sh.get_flags().contains(super::types::SectionFlags::ALLOC)
+                    && sh.sh_addr() <= address
+                    && sh
+                        .sh_addr()
+                        .checked_add(sh.sh_size())
+                        .is_some_and(|end| address < end)
+            }),
+            None => None,
+        };
+        Target {
+            place: Place { section, address },
+            symbol: None,
+        }
+    }
+
+    /// Finds the function symbol covering `place`, preferring one that
+    /// starts there.
+    fn function_symbol(&self, place: Place) -> Option<String> {
+        let relocatable = self.elf.get_type() == ElfType::Relocatable;
+        let mut best: Option<&Symbol> = None;
+        for symbol in &self.symbols {
+            if !symbol.is_function() || symbol.is_undefined() {
+                continue;
+            }
+            // sections overlap at address 0 in relocatable files
+            if relocatable && symbol.section_index() != place.section {
+                continue;
+            }
+            let base = if relocatable {
+                place.section.map_or(0, |s| self.section_address(s))
+            } else {
+                0
+            };
+            let Some(start) = symbol.address(self.machine).checked_add(base) else {
+                continue;
+            };
+            if start == place.address {
+                return Some(symbol.name.clone());
+            }
+            if start < place.address
+                && start
+                    .checked_add(symbol.st_size)
+                    .is_some_and(|end| place.address < end)
+            {
+                best = Some(symbol);
+            }
+        }
+        best.map(|symbol| symbol.name.clone())
+    }
+
+    /// Decodes the opcode words of a compact model description starting
+    /// with `word` at `place`.
+    fn compact(&self, place: Place, word: u32, inline: bool) -> UnwindDescription {
+        let index = ((word >> 24) & 0x0F) as u8;
+        let mut opcodes = Vec::new();
+        let extra = if index == 0 {
+            opcodes.extend_from_slice(&word.to_be_bytes()[1..]);
+            0
+        } else {
+            opcodes.extend_from_slice(&word.to_be_bytes()[2..]);
+            if inline { 0 } else { (word >> 16) & 0xFF }
+        };
+        self.extend_opcodes(&mut opcodes, place.offset(4), extra);
+        UnwindDescription {
+            personality: Personality::Compact(index),
+            opcodes,
+            lsda: None,
+        }
+    }
+
+    fn extend_opcodes(&self, opcodes: &mut Vec<u8>, place: Place, words: u32) {
+        for i in 0..words as u64 {
+            match self.read_word(place.offset(i * 4)) {
+                Some(word) => opcodes.extend_from_slice(&word.to_be_bytes()),
+                None => break,
+            }
+        }
+    }
+
+    fn extab(&self, place: Place) -> Option<UnwindDescription> {
+        let word = self.read_word(place)?;
+        if word & 0x8000_0000 != 0 {
+            return Some(self.compact(place, word, false));
+        }
+
+        let target = self.resolve_prel31(place, word);
+        let symbol = target.symbol.or_else(|| self.function_symbol(target.place));
+        let mut description = UnwindDescription {
+            personality: Personality::Routine {
+                address: target.place.address,
+                symbol: symbol.clone(),
+            },
+            opcodes: Vec::new(),
+            lsda: None,
+        };
+        let symbol = symbol.unwrap_or_default();
+        let symbol = symbol.trim_end_matches("@plt");
+        if GNU_PERSONALITIES.contains(&symbol) {
+            // count of extra words in the top byte, then the opcodes
+            let word = self.read_word(place.offset(4))?;
+            let extra = word >> 24;
+            description
+                .opcodes
+                .extend_from_slice(&word.to_be_bytes()[1..]);
+            self.extend_opcodes(&mut description.opcodes, place.offset(8), extra);
+            description.lsda = Some(place.address + 8 + extra as u64 * 4);
+        }
+        Some(description)
+    }
+
+    fn entries(&self, table: Place, size: u64) -> Vec<ExidxEntry> {
+        let mut entries = Vec::new();
+        for i in 0..size / 8 {
+            let place = table.offset(i * 8);
+            let (Some(first), Some(second)) =
+                (self.read_word(place), self.read_word(place.offset(4)))
+            else {
+                break;
+            };
+            let function = self.resolve_prel31(place, first);
+            let symbol = function
+                .symbol
+                .clone()
+                .or_else(|| self.function_symbol(function.place));
+
+            let unwind = if second == EXIDX_CANTUNWIND {
+                ExidxUnwind::CantUnwind
+            } else if second & 0x8000_0000 != 0 {
+                ExidxUnwind::Inline(self.compact(place.offset(4), second, true))
+            } else {
+                let target = self.resolve_prel31(place.offset(4), second);
+                ExidxUnwind::Table {
+                    address: target.place.address,
+                    description: self.extab(target.place),
+                }
+            };
+            entries.push(ExidxEntry {
+                function: function.place.address,
+                symbol,
+                unwind,
+            });
+        }
+        entries
+    }
+}
+
+impl ElfFile {
+    /// Decodes the ARM exception index tables: every `SHT_ARM_EXIDX`
+    /// section, or the `PT_ARM_EXIDX` segment if there are no section
+    /// headers.
+    pub fn arm_exidx_entries(&self) -> Result<Vec<ExidxEntry>> {
+        if self.get_machine() != Machine::Arm {
+            return Err(ElfError::ParseError(
+                "ARM unwind tables are only defined for ARM files.".to_string(),
+            ));
+        }
+        let reader = ExidxReader::new(self)?;
+
+        let mut entries = Vec::new();
+        let mut found = false;
+        for (index, sh) in self.section_headers.iter().enumerate() {
+            if sh.sh_type() == SHT_ARM_EXIDX {
+                found = true;
+                let table = Place {
+                    section: Some(index),
+                    address: sh.sh_addr(),
+                };
+                entries.extend(reader.entries(table, sh.sh_size()));
+            }
+        }
+        if !found
+            && let Some(ph) = self
+                .program_headers
+                .iter()
+                .find(|ph| ph.get_type() == SegmentType::ProcessorSpecific(PT_ARM_EXIDX))
+        {
+            found = true;
+            let table = Place {
+                section: None,
+                address: ph.p_vaddr(),
+            };
+            entries.extend(reader.entries(table, ph.p_filesz()));
+        }
+        if !found {
+            return Err(ElfError::ParseError(
+                "no .ARM.exidx section or PT_ARM_EXIDX segment found.".to_string(),
+            ));
+        }
+        Ok(entries)
+    }
+}
+
+/// Describes ARM EHABI unwind opcodes, one line per instruction, e.g.
+/// `0xa8      pop {r4, r14}`.
+pub fn describe_opcodes(opcodes: &[u8]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut i = 0;
+    while i < opcodes.len() {
+        let op = opcodes[i];
+        let next = opcodes.get(i + 1).copied();
+        let (len, text) = match op {
+            0x00..=0x3F => (1, format!("vsp = vsp + {}", ((op & 0x3F) as u32) * 4 + 4)),
+            0x40..=0x7F => (1, format!("vsp = vsp - {}", ((op & 0x3F) as u32) * 4 + 4)),
+            0x80..=0x8F => match next {
+                Some(0) if op == 0x80 => (2, "refuse to unwind".to_string()),
+                Some(next) => {
+                    let mask = ((op as u32 & 0x0F) << 8) | next as u32;
+                    (2, format!("pop {{{}}}", register_list("r", 4, mask)))
+                }
+                None => (2, String::new()),
+            },
+            0x9D | 0x9F => (1, "[Reserved]".to_string()),
+            0x90..=0x9F => (1, format!("vsp = r{}", op & 0x0F)),
+            0xA0..=0xA7 => (
+                1,
+                format!(
+                    "pop {{{}}}",
+                    register_list("r", 4, (1 << ((op & 0x07) + 1)) - 1)
+                ),
+            ),
+            0xA8..=0xAF => (
+                1,
+                format!(
+                    "pop {{{}, r14}}",
+                    register_list("r", 4, (1 << ((op & 0x07) + 1)) - 1)
+                ),
+            ),
+            0xB0 => (1, "finish".to_string()),
+            0xB1 => match next {
+                Some(next) if next != 0 && next & 0xF0 == 0 => {
+                    (2, format!("pop {{{}}}", register_list("r", 0, next as u32)))
+                }
+                Some(_) => (2, "[Spare]".to_string()),
+                None => (2, String::new()),
+            },
+            0xB2 => {
+                let mut value = 0u64;
+                let mut shift = 0;
+                let mut len = 1;
+                for &byte in &opcodes[i + 1..] {
+                    len += 1;
+                    if shift < 64 {
+                        value |= ((byte & 0x7F) as u64) << shift;
+                    }
+                    shift += 7;
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                }
+                (len, format!("vsp = vsp + {}", 0x204 + (value << 2)))
+            }
+            0xB3 | 0xC8 | 0xC9 | 0xC6 => match next {
+                Some(next) => {
+                    let (prefix, base, suffix) = match op {
+                        0xB3 => ("D", 0, " (FSTMFDX)"),
+                        0xC6 => ("wR", 0, ""),
+                        0xC8 => ("D", 16, ""),
+                        _ => ("D", 0, ""),
+                    };
+                    let first = base + (next >> 4) as u32;
+                    let last = first + (next & 0x0F) as u32;
+                    (
+                        2,
+                        format!("pop {{{}{}-{}{}}}{}", prefix, first, prefix, last, suffix),
+                    )
+                }
+                None => (2, String::new()),
+            },
+            0xB4 => (1, "pop {ra_auth_code}".to_string()),
+            0xB5 => (1, "vsp as modifier for PAC validation".to_string()),
+            0xB8..=0xBF => (1, format!("pop {{D8-D{}}} (FSTMFDX)", 8 + (op & 0x07))),
+            0xC0..=0xC5 => (1, format!("pop {{wR10-wR{}}}", 10 + (op & 0x07))),
+            0xC7 => match next {
+                Some(next) if next != 0 && next & 0xF0 == 0 => (
+                    2,
+                    format!("pop {{{}}}", register_list("wCGR", 0, next as u32)),
+                ),
+                Some(_) => (2, "[Spare]".to_string()),
+                None => (2, String::new()),
+            },
+            0xD0..=0xD7 => (1, format!("pop {{D8-D{}}}", 8 + (op & 0x07))),
+            _ => (1, "[Spare]".to_string()),
+        };
+
+        let end = (i + len).min(opcodes.len());
+        let bytes = opcodes[i..end]
+            .iter()
+            .map(|b| format!("0x{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let text = if end < i + len {
+            "[Truncated]".to_string()
+        } else {
+            text
+        };
+        lines.push(format!("{:<9} {}", bytes, text));
+        i = end;
+    }
+    lines
+}
+
+/// Formats the registers selected by `mask`, bit 0 naming `<prefix><base>`.
+fn register_list(prefix: &str, base: u32, mask: u32) -> String {
+    (0..16)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(|bit| format!("{}{}", prefix, base + bit))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,11 +1,10 @@
-use std::fmt;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum ElfError{
+pub enum ElfError {
     #[error("Invalid elf file")]
     InvalidMagic,
-    
+
     #[error("Unsupported ELF class: {0}")]
     UnsupportedClass(u8),
 
@@ -0,0 +1,347 @@
+//! Function boundary recovery. Starts and ends are collected from the
+//! symbol tables, `.eh_frame` FDEs, the entry point, `.init`/`.fini`, the
+//! init/fini arrays and PLT stubs, so stripped binaries still get a
+//! function list.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use super::types::{Machine, SectionFlags, SectionType, SegmentFlags, SegmentType};
+use super::{ElfFile, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FunctionSource {
+    Symtab,
+    Dynsym,
+    Plt,
+    EntryPoint,
+    Init,
+    Fini,
+    PreinitArray,
+    InitArray,
+    FiniArray,
+    EhFrame,
+}
+
+impl fmt::Display for FunctionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FunctionSource::Symtab => "symtab",
+            FunctionSource::Dynsym => "dynsym",
+            FunctionSource::Plt => "plt",
+            FunctionSource::EntryPoint => "entry",
+            FunctionSource::Init => "init",
+            FunctionSource::Fini => "fini",
+            FunctionSource::PreinitArray => "preinit_array",
+            FunctionSource::InitArray => "init_array",
+            FunctionSource::FiniArray => "fini_array",
+            FunctionSource::EhFrame => "eh_frame",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveredFunction {
+    pub start: u64,
+    /// End address (exclusive). Taken from the symbol size or FDE range when
+    /// known, otherwise the next function or the end of the code section.
+    pub end: u64,
+    /// Symbol name, or `sub_<address>` when none is known.
+    pub name: String,
+    /// Where the function was found, in order of name priority.
+    pub sources: Vec<FunctionSource>,
+}
+
+impl RecoveredFunction {
+    pub fn size(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Recovered functions sorted by start address.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionMap {
+    pub functions: Vec<RecoveredFunction>,
+}
+
+impl FunctionMap {
+    /// Returns the function containing `address`.
+    pub fn find(&self, address: u64) -> Option<&RecoveredFunction> {
+        let index = self.functions.partition_point(|f| f.start <= address);
+        self.functions[..index].last().filter(|f| address < f.end)
+    }
+
+    /// Formats `address` as `name+0xoffset`.
+    pub fn symbolize(&self, address: u64) -> Option<String> {
+        let function = self.find(address)?;
+        Some(match address - function.start {
+            0 => function.name.clone(),
+            offset => format!("{}+0x{:x}", function.name, offset),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Candidate {
+    end: Option<u64>,
+    /// Whether `end` comes from a symbol size rather than an FDE range,
+    /// which may span several functions (e.g. the whole PLT).
+    exact_end: bool,
+    name: Option<(FunctionSource, String)>,
+    sources: Vec<FunctionSource>,
+}
+
+impl Candidate {
+    fn add(&mut self, source: FunctionSource, name: Option<String>, end: Option<u64>) {
+        if !self.sources.contains(&source) {
+            self.sources.push(source);
+        }
+        if let Some(name) = name
+            && self.name.as_ref().is_none_or(|(s, _)| source < *s)
+        {
+            self.name = Some((source, name));
+        }
+        // symbol sizes take precedence over FDE ranges
+        if let Some(end) = end
+            && (self.end.is_none() || source < FunctionSource::EhFrame)
+        {
+            self.end = Some(end);
+            self.exact_end = source < FunctionSource::EhFrame;
+        }
+    }
+}
+
+/// PLT layout: the section holding the stubs, the size of its header and
+/// the size of each stub.
+fn plt_layout(elf: &ElfFile) -> Option<(usize, u64, u64)> {
+    let (header, entry) = match elf.get_machine() {
+        Machine::X86_64 | Machine::I386 => {
+            // with IBT the stubs move to .plt.sec, which has no header
+            if let Some(index) = elf.find_section(".plt.sec") {
+                return Some((index, 0, 16));
+            }
+            (16, 16)
+        }
+        Machine::AArch64 | Machine::RiscV | Machine::LoongArch => (32, 16),
+        Machine::Arm => (20, 12),
+        _ => return None,
+    };
+    Some((elf.find_section(".plt")?, header, entry))
+}
+
+impl ElfFile {
+    /// Address ranges of executable code: executable sections, or
+    /// executable segments if there are no section headers.
+    fn code_ranges(&self) -> Vec<(u64, u64)> {
+        let mut ranges: Vec<(u64, u64)> = self
+            .section_headers
+            .iter()
+            .filter(|sh| {
+                let flags = sh.get_flags();
+                flags.contains(SectionFlags::ALLOC) && flags.contains(SectionFlags::EXEC)
+            })
+            .filter_map(|sh| Some((sh.sh_addr(), sh.sh_addr().checked_add(sh.sh_size())?)))
+            .collect();
+        if ranges.is_empty() {
+            ranges = self
+                .program_headers
+                .iter()
+                .filter(|ph| {
+                    ph.get_type() == SegmentType::Load && ph.get_flags().contains(SegmentFlags::X)
+                })
+                .filter_map(|ph| Some((ph.p_vaddr(), ph.p_vaddr().checked_add(ph.p_memsz())?)))
+                .collect();
+        }
+        ranges.sort_unstable();
+        ranges
+    }
+
+    /// Reads the function pointers of an init/fini array section. Slots
+    /// left zero for the dynamic linker take their value from a relative
+    /// relocation's addend.
+    fn array_entries(&self, index: usize, addends: &HashMap<u64, i64>) -> Vec<u64> {
+        let sh = &self.section_headers[index];
+        let data = self.get_section_data(index).unwrap_or(&[]);
+        let size = if self.is_64() { 8 } else { 4 };
+        data.chunks_exact(size)
+            .enumerate()
+            .filter_map(|(i, chunk)| {
+                let mut bytes = [0u8; 8];
+                let value = if self.is_big_endian() {
+                    bytes[8 - size..].copy_from_slice(chunk);
+                    u64::from_be_bytes(bytes)
+                } else {
+                    bytes[..size].copy_from_slice(chunk);
+                    u64::from_le_bytes(bytes)
+                };
+                let slot = sh.sh_addr() + (i * size) as u64;
+                match value {
+                    0 => addends.get(&slot).map(|&a| a as u64),
+                    // -1 terminators of old-style .ctors lists
+                    v if v == u64::MAX >> (64 - size * 8) => None,
+                    v => Some(v),
+                }
+            })
+            .collect()
+    }
+
+    /// Synthesises the function list from every available source.
+    pub fn recover_functions(&self) -> Result<FunctionMap> {
+        let machine = self.get_machine();
+        let mut candidates: BTreeMap<u64, Candidate> = BTreeMap::new();
+        let mut add = |start: u64, source, name: Option<String>, end: Option<u64>| {
+            let start = if machine == Machine::Arm {
+                start & !1
+            } else {
+                start
+            };
+            if start != 0 {
+                candidates.entry(start).or_default().add(source, name, end);
+            }
+        };
+
+        for (source, symbols) in [
+            (FunctionSource::Symtab, self.get_symbols()?),
+            (FunctionSource::Dynsym, self.get_dynamic_symbols()?),
+        ] {
+            for symbol in symbols {
+                if !symbol.is_function() || symbol.is_undefined() || symbol.name.is_empty() {
+                    continue;
+                }
+                let start = symbol.address(machine);
+                let end = match symbol.st_size {
+                    0 => None,
+                    size => match start.checked_add(size) {
+                        Some(end) => Some(end),
+                        None => continue,
+                    },
+                };
+                add(start, source, Some(symbol.name), end);
+            }
+        }
+
+        if let Some(cfi) = self.get_eh_frame()? {
+            for fde in &cfi.fdes {
+                add(
+                    fde.pc_begin,
+                    FunctionSource::EhFrame,
+                    None,
+                    Some(fde.pc_end()),
+                );
+            }
+        }
+
+        add(
+            self.e_entry,
+            FunctionSource::EntryPoint,
+            Some("entry".to_string()),
+            None,
+        );
+        for (section, source, name) in [
+            (".init", FunctionSource::Init, "_init"),
+            (".fini", FunctionSource::Fini, "_fini"),
+        ] {
+            if let Some(index) = self.find_section(section)
+                && let sh = &self.section_headers[index]
+                && let Some(end) = sh.sh_addr().checked_add(sh.sh_size())
+            {
+                add(sh.sh_addr(), source, Some(name.to_string()), Some(end));
+            }
+        }
+
+        // addends of relative relocations, for position-independent arrays
+        let mut addends = HashMap::new();
+        let mut plt_relocations = None;
+        for (index, sh) in self.section_headers.iter().enumerate() {
+            if !matches!(sh.get_type(), SectionType::Rel | SectionType::Rela) {
+                continue;
+            }
+            let name = self.get_section_name(index).unwrap_or_default();
+            if name == ".rela.plt" || name == ".rel.plt" {
+                plt_relocations = Some(index);
+                continue;
+            }
+            for rel in self.read_relocations(index)? {
+                if rel.symbol == 0
+                    && let Some(addend) = rel.addend
+                {
+                    addends.insert(rel.r_offset, addend);
+                }
+            }
+        }
+        for (index, sh) in self.section_headers.iter().enumerate() {
+            let source = match sh.get_type() {
+                SectionType::PreinitArray => FunctionSource::PreinitArray,
+                SectionType::InitArray => FunctionSource::InitArray,
+                SectionType::FiniArray => FunctionSource::FiniArray,
+                _ => continue,
+            };
+            for address in self.array_entries(index, &addends) {
+                add(address, source, None, None);
+            }
+        }
+
+        // PLT stubs follow the order of the .rel[a].plt entries
+        if let (Some(rel_index), Some((plt, header, entry))) = (plt_relocations, plt_layout(self)) {
+            let symbols = self.read_symbols(self.section_headers[rel_index].sh_link() as usize)?;
+            let plt = &self.section_headers[plt];
+            if header > 0 {
+                let name = "_PROCEDURE_LINKAGE_TABLE_".to_string();
+                add(
+                    plt.sh_addr(),
+                    FunctionSource::Plt,
+                    Some(name),
+                    Some(plt.sh_addr() + header),
+                );
+            }
+            for (i, rel) in self.read_relocations(rel_index)?.iter().enumerate() {
+                let start = plt.sh_addr() + header + i as u64 * entry;
+                if start + entry > plt.sh_addr() + plt.sh_size() {
+                    break;
+                }
+                let name = match symbols.get(rel.symbol as usize) {
+                    Some(symbol) if rel.symbol != 0 => format!("{}@plt", symbol.name),
+                    _ => format!("*ABS*+0x{:x}@plt", rel.addend.unwrap_or(0)),
+                };
+                add(start, FunctionSource::Plt, Some(name), Some(start + entry));
+            }
+        }
+
+        let ranges = self.code_ranges();
+        let range_of = |address: u64| {
+            ranges
+                .iter()
+                .find(|(start, end)| *start <= address && address < *end)
+                .copied()
+        };
+        let starts: Vec<u64> = candidates.keys().copied().collect();
+        let mut functions = Vec::new();
+        for (i, (start, candidate)) in candidates.into_iter().enumerate() {
+            let Some((_, range_end)) = range_of(start) else {
+                continue;
+            };
+            let next = starts
+                .get(i + 1)
+                .copied()
+                .unwrap_or(range_end)
+                .min(range_end);
+            let end = match candidate.end {
+                Some(end) if candidate.exact_end => end,
+                Some(end) => end.min(next),
+                None => next,
+            };
+            let mut sources = candidate.sources;
+            sources.sort();
+            functions.push(RecoveredFunction {
+                start,
+                end,
+                name: candidate
+                    .name
+                    .map(|(_, name)| name)
+                    .unwrap_or_else(|| format!("sub_{:x}", start)),
+                sources,
+            });
+        }
+        Ok(FunctionMap { functions })
+    }
+}
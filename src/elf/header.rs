@@ -1,18 +1,24 @@
-pub const SHF_WRITE: u64 = (1 << 0);
-pub const SHF_ALLOC: u64 = (1 << 1);
-pub const SHF_EXEC: u64 = (1 << 2);
-pub const SHF_MERGE: u64 = (1 << 4);
-pub const SHF_STRINGS: u64 = (1 << 5);
-pub const SHF_INFO_LINK: u64 = (1 << 6);
-pub const SHF_LINK_ORDER: u64 = (1 << 7);
-pub const SHF_OS_NONCONFORMING: u64 = (1 << 8);
-pub const SHF_GROUP: u64 = (1 << 9);
-pub const SHF_TLS: u64 = (1 << 10);
-pub const SHF_COMPRESSED: u64 = (1 << 11);
-
-pub const PF_X:u32 = (1 << 0);
-pub const PF_W:u32 = (1 << 1);
-pub const PF_R:u32 = (1 << 2);
+use std::io::{self, Read, Write};
+
+use super::names;
+use super::types::{Machine, OsAbi, SectionFlags, SectionType, SegmentFlags, SegmentType};
+use super::{read_u32, read_u64, write_u32, write_u64};
+
+pub const SHF_WRITE: u64 = 1 << 0;
+pub const SHF_ALLOC: u64 = 1 << 1;
+pub const SHF_EXEC: u64 = 1 << 2;
+pub const SHF_MERGE: u64 = 1 << 4;
+pub const SHF_STRINGS: u64 = 1 << 5;
+pub const SHF_INFO_LINK: u64 = 1 << 6;
+pub const SHF_LINK_ORDER: u64 = 1 << 7;
+pub const SHF_OS_NONCONFORMING: u64 = 1 << 8;
+pub const SHF_GROUP: u64 = 1 << 9;
+pub const SHF_TLS: u64 = 1 << 10;
+pub const SHF_COMPRESSED: u64 = 1 << 11;
+
+pub const PF_X: u32 = 1 << 0;
+pub const PF_W: u32 = 1 << 1;
+pub const PF_R: u32 = 1 << 2;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Elf32Header {
@@ -102,13 +108,13 @@ pub struct SectionHeader64 {
     pub sh_entsize: u64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum ProgramHeader {
     Elf32(ProgramHeader32),
     Elf64(ProgramHeader64),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum SectionHeader {
     Elf32(SectionHeader32),
     Elf64(SectionHeader64),
@@ -127,268 +133,443 @@ pub struct ProgramHeader64 {
 } */
 
 impl ProgramHeader {
-    pub fn get_type(&self) -> &'static str {
-        match self {
-            ProgramHeader::Elf32(ph) => match ph.p_type {
-                0 => "NULL",
-                1 => "LOAD",
-                2 => "DYNAMIC",
-                3 => "INTERP",
-                4 => "NOTE",
-                5 => "SHLIB",
-                6 => "PHDR",
-                7 => "TLS",
-                8 => "NUM",
-                0x60000000 => "LOOS",
-                0x60000001..=0x6FFFFFFF => "OS spec",
-                0x70000000 => "LOPROC",
-                0x70000001..=0x7FFFFFFF => "Pr spec)",
-                _ => "Unknown",
-            },
-
-            ProgramHeader::Elf64(ph) => match ph.p_type {
-                0 => "NULL",
-                1 => "LOAD",
-                2 => "DYNAMIC",
-                3 => "INTERP",
-                4 => "NOTE",
-                5 => "SHLIB",
-                6 => "PHDR",
-                7 => "TLS",
-                8 => "NUM",
-                0x60000000 => "LOOS",
-                0x60000001..=0x6FFFFFFF => "OS spec",
-                0x70000000 => "LOPROC",
-                0x70000001..=0x7FFFFFFF => "Pr spec)",
-                _ => "Unknown",
-            },
-        }
-    }
-
-    pub fn get_flags(&self) -> String {
+    /// Reads one program header entry at the current position of `reader`.
+    pub fn read_from<R: Read>(reader: &mut R, is_64: bool, big_endian: bool) -> io::Result<Self> {
+        if is_64 {
+            let p_type = read_u32(reader, big_endian)?;
+            let p_flags = read_u32(reader, big_endian)?;
+            let p_offset = read_u64(reader, big_endian)?;
+            let p_vaddr = read_u64(reader, big_endian)?;
+            let p_paddr = read_u64(reader, big_endian)?;
+            let p_filesz = read_u64(reader, big_endian)?;
+            let p_memsz = read_u64(reader, big_endian)?;
+            let p_align = read_u64(reader, big_endian)?;
+
+            Ok(ProgramHeader::Elf64(ProgramHeader64 {
+                p_type,
+                p_flags,
+                p_offset,
+                p_vaddr,
+                p_paddr,
+                p_filesz,
+                p_memsz,
+                p_align,
+            }))
+        } else {
+            let p_type = read_u32(reader, big_endian)?;
+            let p_offset = read_u32(reader, big_endian)?;
+            let p_vaddr = read_u32(reader, big_endian)?;
+            let p_paddr = read_u32(reader, big_endian)?;
+            let p_filesz = read_u32(reader, big_endian)?;
+            let p_memsz = read_u32(reader, big_endian)?;
+            let p_flags = read_u32(reader, big_endian)?;
+            let p_align = read_u32(reader, big_endian)?;
+
+            Ok(ProgramHeader::Elf32(ProgramHeader32 {
+                p_type,
+                p_offset,
+                p_vaddr,
+                p_paddr,
+                p_filesz,
+                p_memsz,
+                p_flags,
+                p_align,
+            }))
+        }
+    }
+
+    /// Writes the entry in the layout `read_from` expects.
+    pub fn write_to<W: Write>(&self, writer: &mut W, big_endian: bool) -> io::Result<()> {
         match self {
+            ProgramHeader::Elf64(ph) => {
+                write_u32(writer, ph.p_type, big_endian)?;
+                write_u32(writer, ph.p_flags, big_endian)?;
+                write_u64(writer, ph.p_offset, big_endian)?;
+                write_u64(writer, ph.p_vaddr, big_endian)?;
+                write_u64(writer, ph.p_paddr, big_endian)?;
+                write_u64(writer, ph.p_filesz, big_endian)?;
+                write_u64(writer, ph.p_memsz, big_endian)?;
+                write_u64(writer, ph.p_align, big_endian)
+            }
             ProgramHeader::Elf32(ph) => {
-                let mut flags = String::new();
-                if ph.p_flags & PF_X != 0{
-                    flags.push('X');
-                }
-                if ph.p_flags & PF_W != 0{
-                    flags.push('W');
-                }
-                if ph.p_flags & PF_R != 0{
-                    flags.push('R');
-                }
-                flags
+                write_u32(writer, ph.p_type, big_endian)?;
+                write_u32(writer, ph.p_offset, big_endian)?;
+                write_u32(writer, ph.p_vaddr, big_endian)?;
+                write_u32(writer, ph.p_paddr, big_endian)?;
+                write_u32(writer, ph.p_filesz, big_endian)?;
+                write_u32(writer, ph.p_memsz, big_endian)?;
+                write_u32(writer, ph.p_flags, big_endian)?;
+                write_u32(writer, ph.p_align, big_endian)
             }
+        }
+    }
 
-            ProgramHeader::Elf64(ph) => {
-                let mut flags = String::new();
-                if ph.p_flags & PF_X != 0{
-                    flags.push('X');
-                }
-                if ph.p_flags & PF_W != 0{
-                    flags.push('W');
-                }
-                if ph.p_flags & PF_R != 0{
-                    flags.push('R');
-                }
-                flags
-            }
+    pub fn p_type(&self) -> u32 {
+        match self {
+            ProgramHeader::Elf32(ph) => ph.p_type,
+            ProgramHeader::Elf64(ph) => ph.p_type,
+        }
+    }
+
+    pub fn p_flags(&self) -> u32 {
+        match self {
+            ProgramHeader::Elf32(ph) => ph.p_flags,
+            ProgramHeader::Elf64(ph) => ph.p_flags,
+        }
+    }
+
+    pub fn p_offset(&self) -> u64 {
+        match self {
+            ProgramHeader::Elf32(ph) => ph.p_offset as u64,
+            ProgramHeader::Elf64(ph) => ph.p_offset,
+        }
+    }
+
+    pub fn p_vaddr(&self) -> u64 {
+        match self {
+            ProgramHeader::Elf32(ph) => ph.p_vaddr as u64,
+            ProgramHeader::Elf64(ph) => ph.p_vaddr,
+        }
+    }
+
+    pub fn p_paddr(&self) -> u64 {
+        match self {
+            ProgramHeader::Elf32(ph) => ph.p_paddr as u64,
+            ProgramHeader::Elf64(ph) => ph.p_paddr,
+        }
+    }
+
+    pub fn p_filesz(&self) -> u64 {
+        match self {
+            ProgramHeader::Elf32(ph) => ph.p_filesz as u64,
+            ProgramHeader::Elf64(ph) => ph.p_filesz,
+        }
+    }
+
+    pub fn p_memsz(&self) -> u64 {
+        match self {
+            ProgramHeader::Elf32(ph) => ph.p_memsz as u64,
+            ProgramHeader::Elf64(ph) => ph.p_memsz,
+        }
+    }
+
+    pub fn p_align(&self) -> u64 {
+        match self {
+            ProgramHeader::Elf32(ph) => ph.p_align as u64,
+            ProgramHeader::Elf64(ph) => ph.p_align,
+        }
+    }
+
+    pub fn get_type(&self) -> SegmentType {
+        SegmentType::from(self.p_type())
+    }
+
+    /// Returns the segment type name, resolving processor- and OS-specific
+    /// values for the given machine and OS/ABI.
+    pub fn get_type_name(&self, machine: Machine, osabi: OsAbi) -> String {
+        match names::segment_type_name(self.p_type(), machine, osabi) {
+            Some(name) => name.to_string(),
+            None => self.get_type().to_string(),
+        }
+    }
+
+    pub fn get_flags(&self) -> SegmentFlags {
+        SegmentFlags::from(self.p_flags())
+    }
+
+    pub fn set_p_type(&mut self, value: u32) {
+        match self {
+            ProgramHeader::Elf32(h) => h.p_type = value,
+            ProgramHeader::Elf64(h) => h.p_type = value,
+        }
+    }
+
+    pub fn set_p_flags(&mut self, value: u32) {
+        match self {
+            ProgramHeader::Elf32(h) => h.p_flags = value,
+            ProgramHeader::Elf64(h) => h.p_flags = value,
+        }
+    }
+
+    pub fn set_p_offset(&mut self, value: u64) {
+        match self {
+            ProgramHeader::Elf32(h) => h.p_offset = value as u32,
+            ProgramHeader::Elf64(h) => h.p_offset = value,
+        }
+    }
+
+    pub fn set_p_vaddr(&mut self, value: u64) {
+        match self {
+            ProgramHeader::Elf32(h) => h.p_vaddr = value as u32,
+            ProgramHeader::Elf64(h) => h.p_vaddr = value,
+        }
+    }
+
+    pub fn set_p_paddr(&mut self, value: u64) {
+        match self {
+            ProgramHeader::Elf32(h) => h.p_paddr = value as u32,
+            ProgramHeader::Elf64(h) => h.p_paddr = value,
+        }
+    }
+
+    pub fn set_p_filesz(&mut self, value: u64) {
+        match self {
+            ProgramHeader::Elf32(h) => h.p_filesz = value as u32,
+            ProgramHeader::Elf64(h) => h.p_filesz = value,
+        }
+    }
+
+    pub fn set_p_memsz(&mut self, value: u64) {
+        match self {
+            ProgramHeader::Elf32(h) => h.p_memsz = value as u32,
+            ProgramHeader::Elf64(h) => h.p_memsz = value,
+        }
+    }
+
+    pub fn set_p_align(&mut self, value: u64) {
+        match self {
+            ProgramHeader::Elf32(h) => h.p_align = value as u32,
+            ProgramHeader::Elf64(h) => h.p_align = value,
         }
     }
 }
 
 impl SectionHeader {
-    pub fn get_type(&self) -> &'static str {
-        match self {
-            SectionHeader::Elf32(sh) => match sh.sh_type {
-                0 => "NULL",
-                1 => "PROGBITS",
-                2 => "SYMTAB",
-                3 => "STRTAB",
-                4 => "RELA",
-                5 => "HASH",
-                6 => "DYNAMIC",
-                7 => "NOTE",
-                8 => "NOBITS",
-                9 => "REL",
-                10 => "SHLIB",
-                11 => "DYNSYM",
-                14 => "INIT_ARRAY",
-                15 => "FINI_ARRAY",
-                16 => "PREINIT_ARRAY",
-                17 => "GROUP",
-                18 => "SYMTAB_SHNDX",
-                19 => "RELR",
-                20 => "NUM",
-                0x60000000 => "LOOS",
-                0x60000001..=0x6FFFFFFF => "OS spec",
-                0x70000000 => "LOPROC",
-                0x70000001..=0x7FFFFFFF => "Pr spec",
-                _ => "Unknown",
-            },
-            SectionHeader::Elf64(sh) => match sh.sh_type {
-                0 => "NULL",
-                1 => "PROGBITS",
-                2 => "SYMTAB",
-                3 => "STRTAB",
-                4 => "RELA",
-                5 => "HASH",
-                6 => "DYNAMIC",
-                7 => "NOTE",
-                8 => "NOBITS",
-                9 => "REL",
-                10 => "SHLIB",
-                11 => "DYNSYM",
-                14 => "INIT_ARRAY",
-                15 => "FINI_ARRAY",
-                16 => "PREINIT_ARRAY",
-                17 => "GROUP",
-                18 => "SYMTAB_SHNDX",
-                19 => "RELR",
-                20 => "NUM",
-                0x60000000 => "LOOS",
-                0x60000001..=0x6FFFFFFF => "OS spec",
-                0x70000000 => "LOPROC",
-                0x70000001..=0x7FFFFFFF => "Pr spec",
-                _ => "Unknown",
-            },
-        }
-    }
-
-    pub fn get_flags(&self) -> String {
+    /// Reads one section header entry at the current position of `reader`.
+    pub fn read_from<R: Read>(reader: &mut R, is_64: bool, big_endian: bool) -> io::Result<Self> {
+        let sh_name = read_u32(reader, big_endian)?;
+        let sh_type = read_u32(reader, big_endian)?;
+        if is_64 {
+            let sh_flags = read_u64(reader, big_endian)?;
+            let sh_addr = read_u64(reader, big_endian)?;
+            let sh_offset = read_u64(reader, big_endian)?;
+            let sh_size = read_u64(reader, big_endian)?;
+            let sh_link = read_u32(reader, big_endian)?;
+            let sh_info = read_u32(reader, big_endian)?;
+            let sh_addralign = read_u64(reader, big_endian)?;
+            let sh_entsize = read_u64(reader, big_endian)?;
+
+            Ok(SectionHeader::Elf64(SectionHeader64 {
+                sh_name,
+                sh_type,
+                sh_flags,
+                sh_addr,
+                sh_offset,
+                sh_size,
+                sh_link,
+                sh_info,
+                sh_addralign,
+                sh_entsize,
+            }))
+        } else {
+            let sh_flags = read_u32(reader, big_endian)?;
+            let sh_addr = read_u32(reader, big_endian)?;
+            let sh_offset = read_u32(reader, big_endian)?;
+            let sh_size = read_u32(reader, big_endian)?;
+            let sh_link = read_u32(reader, big_endian)?;
+            let sh_info = read_u32(reader, big_endian)?;
+            let sh_addralign = read_u32(reader, big_endian)?;
+            let sh_entsize = read_u32(reader, big_endian)?;
+
+            Ok(SectionHeader::Elf32(SectionHeader32 {
+                sh_name,
+                sh_type,
+                sh_flags,
+                sh_addr,
+                sh_offset,
+                sh_size,
+                sh_link,
+                sh_info,
+                sh_addralign,
+                sh_entsize,
+            }))
+        }
+    }
+
+    /// Writes the entry in the layout `read_from` expects.
+    pub fn write_to<W: Write>(&self, writer: &mut W, big_endian: bool) -> io::Result<()> {
         match self {
-            SectionHeader::Elf32(sh) => {
-                let mut flags = Vec::new();
-                if sh.sh_flags & SHF_WRITE as u32 != 0 {
-                    flags.push("W");
-                }
-                if sh.sh_flags & SHF_ALLOC as u32 != 0 {
-                    flags.push("A");
-                }
-                if sh.sh_flags & SHF_EXEC as u32 != 0 {
-                    flags.push("X");
-                }
-                if sh.sh_flags & SHF_MERGE as u32 != 0 {
-                    flags.push("M");
-                }
-                if sh.sh_flags & SHF_STRINGS as u32 != 0 {
-                    flags.push("S");
-                }
-                if sh.sh_flags & SHF_INFO_LINK as u32 != 0 {
-                    flags.push("I");
-                }
-                if sh.sh_flags & SHF_LINK_ORDER as u32 != 0 {
-                    flags.push("L");
-                }
-                if sh.sh_flags & SHF_OS_NONCONFORMING as u32 != 0 {
-                    flags.push("O");
-                }
-                if sh.sh_flags & SHF_GROUP as u32 != 0 {
-                    flags.push("G");
-                }
-                if sh.sh_flags & SHF_TLS as u32 != 0 {
-                    flags.push("T");
-                }
-                if sh.sh_flags & SHF_COMPRESSED as u32 != 0 {
-                    flags.push("C");
-                }
-
-                // check processor flag
-                let processor_specific = (sh.sh_flags & 0x0FF00000) >> 20;
-                if processor_specific != 0 {
-                    flags.push("p");
-                }
-
-                // check unknown
-                let known_mask = SHF_WRITE
-                    | SHF_ALLOC
-                    | SHF_EXEC
-                    | SHF_MERGE
-                    | SHF_STRINGS
-                    | SHF_INFO_LINK
-                    | SHF_LINK_ORDER
-                    | SHF_OS_NONCONFORMING
-                    | SHF_GROUP
-                    | SHF_TLS
-                    | SHF_COMPRESSED
-                    | 0x0FF00000;
-
-                let unknown = sh.sh_flags & !known_mask as u32;
-                if unknown != 0 {
-                    flags.push("x");
-                }
-                if flags.is_empty() {
-                    return "-".to_string();
-                }
-
-                flags.join("")
-            }
             SectionHeader::Elf64(sh) => {
-                let mut flags = Vec::new();
-                if sh.sh_flags & SHF_WRITE != 0 {
-                    flags.push("W");
-                }
-                if sh.sh_flags & SHF_ALLOC != 0 {
-                    flags.push("A");
-                }
-                if sh.sh_flags & SHF_EXEC != 0 {
-                    flags.push("X");
-                }
-                if sh.sh_flags & SHF_MERGE != 0 {
-                    flags.push("M");
-                }
-                if sh.sh_flags & SHF_STRINGS != 0 {
-                    flags.push("S");
-                }
-                if sh.sh_flags & SHF_INFO_LINK != 0 {
-                    flags.push("I");
-                }
-                if sh.sh_flags & SHF_LINK_ORDER != 0 {
-                    flags.push("L");
-                }
-                if sh.sh_flags & SHF_OS_NONCONFORMING != 0 {
-                    flags.push("O");
-                }
-                if sh.sh_flags & SHF_GROUP != 0 {
-                    flags.push("G");
-                }
-                if sh.sh_flags & SHF_TLS != 0 {
-                    flags.push("T");
-                }
-                if sh.sh_flags & SHF_COMPRESSED != 0 {
-                    flags.push("C");
-                }
-                // check processor flag
-                let processor_specific = (sh.sh_flags & 0x0FF00000) >> 20;
-                if processor_specific != 0 {
-                    flags.push("p");
-                }
-
-                // check unknown
-                let known_mask = SHF_WRITE
-                    | SHF_ALLOC
-                    | SHF_EXEC
-                    | SHF_MERGE
-                    | SHF_STRINGS
-                    | SHF_INFO_LINK
-                    | SHF_LINK_ORDER
-                    | SHF_OS_NONCONFORMING
-                    | SHF_GROUP
-                    | SHF_TLS
-                    | SHF_COMPRESSED
-                    | 0x0FF00000;
-
-                let unknown = sh.sh_flags & !known_mask;
-                if unknown != 0 {
-                    flags.push("x");
-                }
-                if flags.is_empty() {
-                    return "-".to_string();
-                }
-
-                flags.join("")
+                write_u32(writer, sh.sh_name, big_endian)?;
+                write_u32(writer, sh.sh_type, big_endian)?;
+                write_u64(writer, sh.sh_flags, big_endian)?;
+                write_u64(writer, sh.sh_addr, big_endian)?;
+                write_u64(writer, sh.sh_offset, big_endian)?;
+                write_u64(writer, sh.sh_size, big_endian)?;
+                write_u32(writer, sh.sh_link, big_endian)?;
+                write_u32(writer, sh.sh_info, big_endian)?;
+                write_u64(writer, sh.sh_addralign, big_endian)?;
+                write_u64(writer, sh.sh_entsize, big_endian)
+            }
+            SectionHeader::Elf32(sh) => {
+                write_u32(writer, sh.sh_name, big_endian)?;
+                write_u32(writer, sh.sh_type, big_endian)?;
+                write_u32(writer, sh.sh_flags, big_endian)?;
+                write_u32(writer, sh.sh_addr, big_endian)?;
+                write_u32(writer, sh.sh_offset, big_endian)?;
+                write_u32(writer, sh.sh_size, big_endian)?;
+                write_u32(writer, sh.sh_link, big_endian)?;
+                write_u32(writer, sh.sh_info, big_endian)?;
+                write_u32(writer, sh.sh_addralign, big_endian)?;
+                write_u32(writer, sh.sh_entsize, big_endian)
             }
         }
     }
+
+    pub fn sh_name(&self) -> u32 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_name,
+            SectionHeader::Elf64(sh) => sh.sh_name,
+        }
+    }
+
+    pub fn sh_type(&self) -> u32 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_type,
+            SectionHeader::Elf64(sh) => sh.sh_type,
+        }
+    }
+
+    pub fn sh_addr(&self) -> u64 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_addr as u64,
+            SectionHeader::Elf64(sh) => sh.sh_addr,
+        }
+    }
+
+    pub fn sh_offset(&self) -> u64 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_offset as u64,
+            SectionHeader::Elf64(sh) => sh.sh_offset,
+        }
+    }
+
+    pub fn sh_size(&self) -> u64 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_size as u64,
+            SectionHeader::Elf64(sh) => sh.sh_size,
+        }
+    }
+
+    pub fn sh_flags(&self) -> u64 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_flags as u64,
+            SectionHeader::Elf64(sh) => sh.sh_flags,
+        }
+    }
+
+    pub fn sh_link(&self) -> u32 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_link,
+            SectionHeader::Elf64(sh) => sh.sh_link,
+        }
+    }
+
+    pub fn sh_info(&self) -> u32 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_info,
+            SectionHeader::Elf64(sh) => sh.sh_info,
+        }
+    }
+
+    pub fn sh_addralign(&self) -> u64 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_addralign as u64,
+            SectionHeader::Elf64(sh) => sh.sh_addralign,
+        }
+    }
+
+    pub fn sh_entsize(&self) -> u64 {
+        match self {
+            SectionHeader::Elf32(sh) => sh.sh_entsize as u64,
+            SectionHeader::Elf64(sh) => sh.sh_entsize,
+        }
+    }
+
+    pub fn get_type(&self) -> SectionType {
+        SectionType::from(self.sh_type())
+    }
+
+    /// Returns the section type name, resolving processor- and OS-specific
+    /// values for the given machine and OS/ABI.
+    pub fn get_type_name(&self, machine: Machine, osabi: OsAbi) -> String {
+        match names::section_type_name(self.sh_type(), machine, osabi) {
+            Some(name) => name.to_string(),
+            None => self.get_type().to_string(),
+        }
+    }
+
+    pub fn get_flags(&self) -> SectionFlags {
+        SectionFlags::from(self.sh_flags())
+    }
+
+    pub fn set_sh_name(&mut self, value: u32) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_name = value,
+            SectionHeader::Elf64(h) => h.sh_name = value,
+        }
+    }
+
+    pub fn set_sh_type(&mut self, value: u32) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_type = value,
+            SectionHeader::Elf64(h) => h.sh_type = value,
+        }
+    }
+
+    pub fn set_sh_flags(&mut self, value: u64) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_flags = value as u32,
+            SectionHeader::Elf64(h) => h.sh_flags = value,
+        }
+    }
+
+    pub fn set_sh_addr(&mut self, value: u64) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_addr = value as u32,
+            SectionHeader::Elf64(h) => h.sh_addr = value,
+        }
+    }
+
+    pub fn set_sh_offset(&mut self, value: u64) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_offset = value as u32,
+            SectionHeader::Elf64(h) => h.sh_offset = value,
+        }
+    }
+
+    pub fn set_sh_size(&mut self, value: u64) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_size = value as u32,
+            SectionHeader::Elf64(h) => h.sh_size = value,
+        }
+    }
+
+    pub fn set_sh_link(&mut self, value: u32) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_link = value,
+            SectionHeader::Elf64(h) => h.sh_link = value,
+        }
+    }
+
+    pub fn set_sh_info(&mut self, value: u32) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_info = value,
+            SectionHeader::Elf64(h) => h.sh_info = value,
+        }
+    }
+
+    pub fn set_sh_addralign(&mut self, value: u64) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_addralign = value as u32,
+            SectionHeader::Elf64(h) => h.sh_addralign = value,
+        }
+    }
+
+    pub fn set_sh_entsize(&mut self, value: u64) {
+        match self {
+            SectionHeader::Elf32(h) => h.sh_entsize = value as u32,
+            SectionHeader::Elf64(h) => h.sh_entsize = value,
+        }
+    }
 }
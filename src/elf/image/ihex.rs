@@ -0,0 +1,145 @@
+//! Intel HEX with extended linear address records (32-bit addresses).
+
+use std::fmt::Write;
+
+use super::{Chunk, normalize};
+use crate::elf::Result;
+use crate::elf::error::ElfError;
+
+const DATA: u8 = 0x00;
+const END_OF_FILE: u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const START_LINEAR_ADDRESS: u8 = 0x05;
+
+/// Data bytes per record.
+const RECORD_SIZE: usize = 16;
+
+fn record(out: &mut String, kind: u8, address: u16, data: &[u8]) {
+    let mut sum = data.len() as u8;
+    sum = sum
+        .wrapping_add((address >> 8) as u8)
+        .wrapping_add(address as u8);
+    sum = sum.wrapping_add(kind);
+    let _ = write!(out, ":{:02X}{:04X}{:02X}", data.len(), address, kind);
+    for &byte in data {
+        sum = sum.wrapping_add(byte);
+        let _ = write!(out, "{:02X}", byte);
+    }
+    let _ = writeln!(out, "{:02X}", sum.wrapping_neg());
+}
+
+/// Encodes `chunks`, with a start linear address record for `entry` if it
+/// is given.
+pub fn write(chunks: &[Chunk], entry: Option<u64>) -> Result<String> {
+    if let Some(chunk) = chunks
+        .iter()
+        .find(|chunk| chunk.end().is_none_or(|end| end > 1 << 32))
+    {
+        return Err(ElfError::ParseError(format!(
+            "data at 0x{:X} is beyond the 32-bit range of Intel HEX.",
+            chunk.address
+        )));
+    }
+    let mut out = String::new();
+    let mut upper = 0u16;
+    for chunk in chunks {
+        let mut address = chunk.address;
+        let mut data = chunk.data.as_slice();
+        while !data.is_empty() {
+            if (address >> 16) as u16 != upper {
+                upper = (address >> 16) as u16;
+                record(&mut out, EXTENDED_LINEAR_ADDRESS, 0, &upper.to_be_bytes());
+            }
+            // records do not cross a 64 KiB boundary
+            let room = 0x10000 - (address & 0xFFFF) as usize;
+            let len = data.len().min(RECORD_SIZE).min(room);
+            record(&mut out, DATA, address as u16, &data[..len]);
+            address += len as u64;
+            data = &data[len..];
+        }
+    }
+    if let Some(entry) = entry {
+        let entry = u32::try_from(entry).map_err(|_| {
+            ElfError::ParseError(format!(
+                "entry point 0x{:X} does not fit in 32 bits.",
+                entry
+            ))
+        })?;
+        record(&mut out, START_LINEAR_ADDRESS, 0, &entry.to_be_bytes());
+    }
+    record(&mut out, END_OF_FILE, 0, &[]);
+    Ok(out)
+}
+
+fn parse_record(line: &str, number: usize) -> Result<(u8, u16, Vec<u8>)> {
+    let error = |what: &str| ElfError::ParseError(format!("line {}: {}.", number, what));
+    let hex = line.strip_prefix(':').ok_or_else(|| error("missing ':'"))?;
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return Err(error("malformed record"));
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<std::result::Result<Vec<u8>, _>>()
+        .map_err(|_| error("invalid hex digit"))?;
+    if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+        return Err(error("wrong record length"));
+    }
+    if bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+        return Err(error("checksum mismatch"));
+    }
+    let address = u16::from_be_bytes([bytes[1], bytes[2]]);
+    Ok((bytes[3], address, bytes[4..bytes.len() - 1].to_vec()))
+}
+
+/// Decodes Intel HEX text into chunks and the start address, if any.
+pub fn parse(text: &str) -> Result<(Vec<Chunk>, Option<u64>)> {
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut base = 0u64;
+    let mut entry = None;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (kind, address, data) = parse_record(line, i + 1)?;
+        let value = |len: usize| -> Result<u64> {
+            if data.len() != len {
+                return Err(ElfError::ParseError(format!(
+                    "line {}: record type {:02X} needs {} data bytes.",
+                    i + 1,
+                    kind,
+                    len
+                )));
+            }
+            Ok(data.iter().fold(0u64, |v, &b| (v << 8) | b as u64))
+        };
+        match kind {
+            DATA => {
+                let address = base + address as u64;
+                match chunks.last_mut() {
+                    Some(last) if last.end() == Some(address) => last.data.extend_from_slice(&data),
+                    _ => chunks.push(Chunk { address, data }),
+                }
+            }
+            END_OF_FILE => break,
+            EXTENDED_SEGMENT_ADDRESS => base = value(2)? << 4,
+            START_SEGMENT_ADDRESS => {
+                let value = value(4)?;
+                entry = Some(((value >> 16) << 4) + (value & 0xFFFF));
+            }
+            EXTENDED_LINEAR_ADDRESS => base = value(2)? << 16,
+            START_LINEAR_ADDRESS => entry = Some(value(4)?),
+            _ => {
+                return Err(ElfError::ParseError(format!(
+                    "line {}: unknown record type {:02X}.",
+                    i + 1,
+                    kind
+                )));
+            }
+        }
+    }
+    Ok((normalize(chunks)?, entry))
+}
@@ -0,0 +1,296 @@
+//! Memory images of the loadable segments for flashing tools: flat binary,
+//! Intel HEX, Motorola S-records and UF2, and minimal ELF files built back from
+//! them.
+
+pub mod ihex;
+pub mod srec;
+pub mod uf2;
+
+use std::io::Cursor;
+
+use super::error::ElfError;
+use super::header::{
+    PF_R, PF_W, PF_X, ProgramHeader, ProgramHeader32, ProgramHeader64, SHF_ALLOC, SHF_EXEC,
+    SHF_WRITE, SectionHeader, SectionHeader32, SectionHeader64,
+};
+use super::types::{SectionType, SegmentType};
+use super::{ElfFile, Result, write_u16, write_u32, write_word};
+
+/// Flat images larger than this are refused, since they are most likely
+/// caused by segments far apart (e.g. flash and RAM).
+const MAX_BINARY_SIZE: u64 = 1 << 30;
+
+/// Contiguous bytes at a load address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    pub address: u64,
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    /// Returns the address after the last byte, or `None` if the chunk
+    /// runs past the end of the address space.
+    pub fn end(&self) -> Option<u64> {
+        self.address.checked_add(self.data.len() as u64)
+    }
+}
+
+/// Sorts `chunks` by address and merges adjacent ones. Overlapping chunks
+/// are an error.
+pub fn normalize(mut chunks: Vec<Chunk>) -> Result<Vec<Chunk>> {
+    chunks.retain(|chunk| !chunk.data.is_empty());
+    chunks.sort_by_key(|chunk| chunk.address);
+    let mut merged: Vec<Chunk> = Vec::with_capacity(chunks.len());
+    // end of the last merged chunk
+    let mut last_end = 0;
+    for chunk in chunks {
+        let Some(end) = chunk.end() else {
+            return Err(ElfError::ParseError(format!(
+                "data at 0x{:X} with size 0x{:X} exceeds the address space.",
+                chunk.address,
+                chunk.data.len()
+            )));
+        };
+        match merged.last_mut() {
+            Some(last) if chunk.address < last_end => {
+                return Err(ElfError::ParseError(format!(
+                    "data at 0x{:X} overlaps data at 0x{:X}..0x{:X}.",
+                    chunk.address, last.address, last_end
+                )));
+            }
+            Some(last) if chunk.address == last_end => last.data.extend_from_slice(&chunk.data),
+            _ => merged.push(chunk),
+        }
+        last_end = end;
+    }
+    Ok(merged)
+}
+
+/// Lays `chunks` out as one image starting at the lowest address, with the
+/// gaps filled with `gap_fill`.
+pub fn to_binary(chunks: &[Chunk], gap_fill: u8) -> Result<Vec<u8>> {
+    let (Some(first), Some(last)) = (chunks.first(), chunks.last()) else {
+        return Ok(Vec::new());
+    };
+    let end = last.end().ok_or_else(|| {
+        ElfError::ParseError(format!(
+            "data at 0x{:X} exceeds the address space.",
+            last.address
+        ))
+    })?;
+    let size = end - first.address;
+    if size > MAX_BINARY_SIZE {
+        return Err(ElfError::ParseError(format!(
+            "the image spans 0x{:X}..0x{:X}, which is too large for a flat binary.",
+            first.address, end
+        )));
+    }
+    let mut image = vec![gap_fill; size as usize];
+    for chunk in chunks {
+        let start = (chunk.address - first.address) as usize;
+        image[start..start + chunk.data.len()].copy_from_slice(&chunk.data);
+    }
+    Ok(image)
+}
+
+/// Header fields of an ELF file built from a memory image.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ElfImageOptions {
+    pub machine: u16,
+    pub entry: u64,
+    pub is_64: bool,
+    pub big_endian: bool,
+}
+
+/// Builds an executable with one `PT_LOAD` segment and one `.secN` section
+/// per chunk, like `objcopy -I ihex -O elf32-little` does.
+pub fn build_elf(chunks: &[Chunk], options: &ElfImageOptions) -> Result<Vec<u8>> {
+    let is_64 = options.is_64;
+    let be = options.big_endian;
+    if !is_64
+        && chunks
+            .iter()
+            .any(|chunk| chunk.end().is_none_or(|end| end > 1 << 32))
+    {
+        return Err(ElfError::ParseError(
+            "addresses above 4 GiB need a 64-bit ELF file.".to_string(),
+        ));
+    }
+    let (ehsize, phentsize, shentsize) = if is_64 {
+        (64u64, 56u64, 64u64)
+    } else {
+        (52, 32, 40)
+    };
+
+    let mut shstrtab = vec![0u8];
+    let mut names = Vec::with_capacity(chunks.len());
+    for i in 0..chunks.len() {
+        names.push(shstrtab.len() as u32);
+        shstrtab.extend_from_slice(format!(".sec{}", i + 1).as_bytes());
+        shstrtab.push(0);
+    }
+    let shstrtab_name = shstrtab.len() as u32;
+    shstrtab.extend_from_slice(b".shstrtab\0");
+
+    let mut offset = ehsize + chunks.len() as u64 * phentsize;
+    let mut offsets = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        offset = offset.next_multiple_of(4);
+        offsets.push(offset);
+        offset += chunk.data.len() as u64;
+    }
+    let shstrtab_offset = offset;
+    let shoff = (shstrtab_offset + shstrtab.len() as u64).next_multiple_of(8);
+    let shnum = chunks.len() as u64 + 2;
+
+    let mut out = vec![0u8; (shoff + shnum * shentsize) as usize];
+    let mut writer = Cursor::new(&mut out);
+    writer.get_mut()[..4].copy_from_slice(b"\x7FELF");
+    writer.get_mut()[4] = if is_64 { 2 } else { 1 };
+    writer.get_mut()[5] = if be { 2 } else { 1 };
+    writer.get_mut()[6] = 1;
+    writer.set_position(16);
+    write_u16(&mut writer, 2, be)?; // ET_EXEC
+    write_u16(&mut writer, options.machine, be)?;
+    write_u32(&mut writer, 1, be)?;
+    write_word(&mut writer, options.entry, is_64, be)?;
+    write_word(
+        &mut writer,
+        if chunks.is_empty() { 0 } else { ehsize },
+        is_64,
+        be,
+    )?;
+    write_word(&mut writer, shoff, is_64, be)?;
+    write_u32(&mut writer, 0, be)?;
+    write_u16(&mut writer, ehsize as u16, be)?;
+    write_u16(&mut writer, phentsize as u16, be)?;
+    write_u16(&mut writer, chunks.len() as u16, be)?;
+    write_u16(&mut writer, shentsize as u16, be)?;
+    write_u16(&mut writer, shnum as u16, be)?;
+    write_u16(&mut writer, shnum as u16 - 1, be)?;
+
+    for (chunk, &offset) in chunks.iter().zip(&offsets) {
+        let size = chunk.data.len() as u64;
+        let ph = if is_64 {
+            ProgramHeader::Elf64(ProgramHeader64 {
+                p_type: SegmentType::Load.into(),
+                p_flags: PF_R | PF_W | PF_X,
+                p_offset: offset,
+                p_vaddr: chunk.address,
+                p_paddr: chunk.address,
+                p_filesz: size,
+                p_memsz: size,
+                p_align: 1,
+            })
+        } else {
+            ProgramHeader::Elf32(ProgramHeader32 {
+                p_type: SegmentType::Load.into(),
+                p_offset: offset as u32,
+                p_vaddr: chunk.address as u32,
+                p_paddr: chunk.address as u32,
+                p_filesz: size as u32,
+                p_memsz: size as u32,
+                p_flags: PF_R | PF_W | PF_X,
+                p_align: 1,
+            })
+        };
+        ph.write_to(&mut writer, be)?;
+    }
+    for (chunk, &offset) in chunks.iter().zip(&offsets) {
+        let start = offset as usize;
+        writer.get_mut()[start..start + chunk.data.len()].copy_from_slice(&chunk.data);
+    }
+    let start = shstrtab_offset as usize;
+    writer.get_mut()[start..start + shstrtab.len()].copy_from_slice(&shstrtab);
+
+    let section =
+        |name: u32, sh_type: SectionType, flags: u64, addr: u64, offset: u64, size: u64| {
+            if is_64 {
+                SectionHeader::Elf64(SectionHeader64 {
+                    sh_name: name,
+                    sh_type: sh_type.into(),
+                    sh_flags: flags,
+                    sh_addr: addr,
+                    sh_offset: offset,
+                    sh_size: size,
+                    sh_link: 0,
+                    sh_info: 0,
+                    sh_addralign: 1,
+                    sh_entsize: 0,
+                })
+            } else {
+                SectionHeader::Elf32(SectionHeader32 {
+                    sh_name: name,
+                    sh_type: sh_type.into(),
+                    sh_flags: flags as u32,
+                    sh_addr: addr as u32,
+                    sh_offset: offset as u32,
+                    sh_size: size as u32,
+                    sh_link: 0,
+                    sh_info: 0,
+                    sh_addralign: 1,
+                    sh_entsize: 0,
+                })
+            }
+        };
+    writer.set_position(shoff);
+    section(0, SectionType::Null, 0, 0, 0, 0).write_to(&mut writer, be)?;
+    for ((chunk, &offset), &name) in chunks.iter().zip(&offsets).zip(&names) {
+        let flags = SHF_ALLOC | SHF_WRITE | SHF_EXEC;
+        section(
+            name,
+            SectionType::Progbits,
+            flags,
+            chunk.address,
+            offset,
+            chunk.data.len() as u64,
+        )
+        .write_to(&mut writer, be)?;
+    }
+    section(
+        shstrtab_name,
+        SectionType::Strtab,
+        0,
+        0,
+        shstrtab_offset,
+        shstrtab.len() as u64,
+    )
+    .write_to(&mut writer, be)?;
+    Ok(out)
+}
+
+impl ElfFile {
+    /// Returns the file contents of the `PT_LOAD` segments at their
+    /// physical (load) addresses, sorted and merged. Zero-filled memory
+    /// beyond `p_filesz` is not part of the image.
+    pub fn load_image(&self) -> Result<Vec<Chunk>> {
+        let mut chunks = Vec::new();
+        for ph in &self.program_headers {
+            if ph.get_type() != SegmentType::Load || ph.p_filesz() == 0 {
+                continue;
+            }
+            let data = usize::try_from(ph.p_offset())
+                .ok()
+                .zip(usize::try_from(ph.p_filesz()).ok())
+                .and_then(|(start, len)| self.data.get(start..start.checked_add(len)?))
+                .ok_or_else(|| {
+                    ElfError::ParseError(format!(
+                        "segment at 0x{:X} lies outside the file.",
+                        ph.p_paddr()
+                    ))
+                })?;
+            if ph.p_paddr().checked_add(ph.p_filesz()).is_none() {
+                return Err(ElfError::ParseError(format!(
+                    "segment at 0x{:X} with size 0x{:X} exceeds the address space.",
+                    ph.p_paddr(),
+                    ph.p_filesz()
+                )));
+            }
+            chunks.push(Chunk {
+                address: ph.p_paddr(),
+                data: data.to_vec(),
+            });
+        }
+        normalize(chunks)
+    }
+}
@@ -0,0 +1,116 @@
+//! Motorola S-records. Data records are S1, S2 or S3 (16, 24 or 32-bit
+//! addresses), chosen by the highest address.
+
+use std::fmt::Write;
+
+use super::{Chunk, normalize};
+use crate::elf::Result;
+use crate::elf::error::ElfError;
+
+/// Data bytes per record.
+const RECORD_SIZE: usize = 16;
+
+fn record(out: &mut String, kind: u8, address: u64, address_size: usize, data: &[u8]) {
+    let count = (address_size + data.len() + 1) as u8;
+    let address_bytes = &address.to_be_bytes()[8 - address_size..];
+    let mut sum = count;
+    let _ = write!(out, "S{}{:02X}", kind, count);
+    for &byte in address_bytes.iter().chain(data) {
+        sum = sum.wrapping_add(byte);
+        let _ = write!(out, "{:02X}", byte);
+    }
+    let _ = writeln!(out, "{:02X}", !sum);
+}
+
+/// Encodes `chunks` with an S0 record holding `header` and a termination
+/// record holding `entry`.
+pub fn write(chunks: &[Chunk], header: &str, entry: u64) -> Result<String> {
+    let highest = chunks
+        .iter()
+        .map(|chunk| chunk.end().map_or(u64::MAX, |end| end.saturating_sub(1)))
+        .max()
+        .unwrap_or(0)
+        .max(entry);
+    let (data_kind, end_kind, address_size) = match highest {
+        0..=0xFFFF => (1, 9, 2),
+        0x1_0000..=0xFF_FFFF => (2, 8, 3),
+        0x100_0000..=0xFFFF_FFFF => (3, 7, 4),
+        _ => {
+            return Err(ElfError::ParseError(format!(
+                "address 0x{:X} is beyond the 32-bit range of S-records.",
+                highest
+            )));
+        }
+    };
+
+    let mut out = String::new();
+    record(&mut out, 0, 0, 2, header.as_bytes());
+    for chunk in chunks {
+        for (i, data) in chunk.data.chunks(RECORD_SIZE).enumerate() {
+            let address = chunk.address + (i * RECORD_SIZE) as u64;
+            record(&mut out, data_kind, address, address_size, data);
+        }
+    }
+    record(&mut out, end_kind, entry, address_size, &[]);
+    Ok(out)
+}
+
+/// Decodes S-records into chunks and the start address, if any.
+pub fn parse(text: &str) -> Result<(Vec<Chunk>, Option<u64>)> {
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut entry = None;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |what: &str| ElfError::ParseError(format!("line {}: {}.", i + 1, what));
+        if !line.is_ascii() {
+            return Err(error("malformed record"));
+        }
+        let (Some(b'S'), Some(kind)) = (line.as_bytes().first(), line.as_bytes().get(1)) else {
+            return Err(error("missing 'S'"));
+        };
+        let hex = &line[2..];
+        if hex.len() % 2 != 0 {
+            return Err(error("malformed record"));
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<u8>, _>>()
+            .map_err(|_| error("invalid hex digit"))?;
+        if bytes.len() < 2 || bytes.len() != bytes[0] as usize + 1 {
+            return Err(error("wrong record length"));
+        }
+        if bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0xFF {
+            return Err(error("checksum mismatch"));
+        }
+        let address_size = match kind {
+            b'0' | b'1' | b'5' | b'9' => 2,
+            b'2' | b'6' | b'8' => 3,
+            b'3' | b'7' => 4,
+            _ => return Err(error("unknown record type")),
+        };
+        if bytes.len() < address_size + 2 {
+            return Err(error("record too short"));
+        }
+        let address = bytes[1..1 + address_size]
+            .iter()
+            .fold(0u64, |v, &b| (v << 8) | b as u64);
+        let data = &bytes[1 + address_size..bytes.len() - 1];
+        match kind {
+            b'1'..=b'3' => match chunks.last_mut() {
+                Some(last) if last.end() == Some(address) => last.data.extend_from_slice(data),
+                _ => chunks.push(Chunk {
+                    address,
+                    data: data.to_vec(),
+                }),
+            },
+            b'7'..=b'9' => entry = Some(address),
+            // header and record counts
+            _ => {}
+        }
+    }
+    Ok((normalize(chunks)?, entry))
+}
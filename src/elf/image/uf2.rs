@@ -0,0 +1,193 @@
+//! UF2, the USB flashing format of many microcontroller bootloaders: a
+//! sequence of 512-byte blocks, each carrying up to 476 bytes of payload
+//! for one target address.
+
+use std::collections::BTreeMap;
+
+use super::{Chunk, normalize};
+use crate::elf::Result;
+use crate::elf::error::ElfError;
+
+pub const BLOCK_SIZE: usize = 512;
+pub const MAX_PAYLOAD: u32 = 476;
+
+const MAGIC_START0: u32 = 0x0A32_4655;
+const MAGIC_START1: u32 = 0x9E5D_5157;
+const MAGIC_END: u32 = 0x0AB1_6F30;
+
+/// The block is not meant for the main flash (e.g. comments).
+const FLAG_NOT_MAIN_FLASH: u32 = 0x0000_0001;
+/// The file size field holds a family ID.
+const FLAG_FAMILY_ID: u32 = 0x0000_2000;
+
+/// Family IDs of common boards, from the UF2 specification.
+const FAMILIES: &[(&str, u32)] = &[
+    ("RP2040", 0xE48B_FF56),
+    ("RP2350-ARM-S", 0xE48B_FF59),
+    ("RP2350-RISCV", 0xE48B_FF5A),
+    ("RP2350-ARM-NS", 0xE48B_FF5B),
+    ("NRF52", 0x1B57_745F),
+    ("NRF52833", 0x621E_937A),
+    ("NRF52840", 0xADA5_2840),
+    ("SAMD21", 0x68ED_2B88),
+    ("SAMD51", 0x5511_4460),
+    ("STM32F1", 0x5EE2_1072),
+    ("STM32F4", 0x5775_5A57),
+    ("ESP32S2", 0xBFDD_4EEE),
+    ("ESP32S3", 0xC47E_5767),
+];
+
+/// Looks up a family ID by board name (case-insensitive).
+pub fn family_id(name: &str) -> Option<u32> {
+    FAMILIES
+        .iter()
+        .find(|(family, _)| family.eq_ignore_ascii_case(name))
+        .map(|&(_, id)| id)
+}
+
+/// Returns the board name of a family ID.
+pub fn family_name(id: u32) -> Option<&'static str> {
+    FAMILIES
+        .iter()
+        .find(|&&(_, family)| family == id)
+        .map(|&(name, _)| name)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Uf2Options {
+    pub family_id: Option<u32>,
+    /// Payload bytes per block; blocks start at multiples of it.
+    pub page_size: u32,
+    /// Start and size of the flash; data outside it is refused.
+    pub flash: Option<(u64, u64)>,
+}
+
+impl Default for Uf2Options {
+    fn default() -> Self {
+        Self {
+            family_id: None,
+            page_size: 256,
+            flash: None,
+        }
+    }
+}
+
+/// Encodes `chunks` as UF2 blocks. Parts of a page not covered by any
+/// chunk are zero.
+pub fn write(chunks: &[Chunk], options: &Uf2Options) -> Result<Vec<u8>> {
+    let page_size = options.page_size as u64;
+    if page_size == 0 || options.page_size > MAX_PAYLOAD {
+        return Err(ElfError::ParseError(format!(
+            "the page size must be between 1 and {} bytes.",
+            MAX_PAYLOAD
+        )));
+    }
+    let flash = match options.flash {
+        Some((start, size)) => match start.checked_add(size) {
+            Some(end) => Some((start, end)),
+            None => {
+                return Err(ElfError::ParseError(format!(
+                    "the flash at 0x{:X} with size 0x{:X} exceeds the address space.",
+                    start, size
+                )));
+            }
+        },
+        None => None,
+    };
+    for chunk in chunks {
+        let Some(chunk_end) = chunk.end().filter(|&end| end <= 1 << 32) else {
+            return Err(ElfError::ParseError(format!(
+                "data at 0x{:X} is beyond the 32-bit range of UF2.",
+                chunk.address
+            )));
+        };
+        if let Some((start, end)) = flash
+            && (chunk.address < start || chunk_end > end)
+        {
+            return Err(ElfError::ParseError(format!(
+                "data at 0x{:X}..0x{:X} lies outside the flash at 0x{:X}..0x{:X}.",
+                chunk.address, chunk_end, start, end
+            )));
+        }
+    }
+
+    let mut pages: BTreeMap<u64, Vec<u8>> = BTreeMap::new();
+    for chunk in chunks {
+        for (i, &byte) in chunk.data.iter().enumerate() {
+            let address = chunk.address + i as u64;
+            let page = pages
+                .entry(address - address % page_size)
+                .or_insert_with(|| vec![0; page_size as usize]);
+            page[(address % page_size) as usize] = byte;
+        }
+    }
+
+    let mut out = Vec::with_capacity(pages.len() * BLOCK_SIZE);
+    let flags = if options.family_id.is_some() {
+        FLAG_FAMILY_ID
+    } else {
+        0
+    };
+    for (number, (address, payload)) in pages.iter().enumerate() {
+        let header = [
+            MAGIC_START0,
+            MAGIC_START1,
+            flags,
+            *address as u32,
+            options.page_size,
+            number as u32,
+            pages.len() as u32,
+            options.family_id.unwrap_or(0),
+        ];
+        for word in header {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+        out.extend_from_slice(payload);
+        out.resize(out.len() + MAX_PAYLOAD as usize - payload.len(), 0);
+        out.extend_from_slice(&MAGIC_END.to_le_bytes());
+    }
+    Ok(out)
+}
+
+/// Decodes UF2 blocks into chunks and the family ID, if any. Blocks not
+/// meant for the main flash are skipped.
+pub fn parse(data: &[u8]) -> Result<(Vec<Chunk>, Option<u32>)> {
+    if !data.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(ElfError::ParseError(format!(
+            "UF2 files consist of {}-byte blocks.",
+            BLOCK_SIZE
+        )));
+    }
+    let mut chunks = Vec::new();
+    let mut family = None;
+    for (number, block) in data.chunks_exact(BLOCK_SIZE).enumerate() {
+        let word = |i: usize| u32::from_le_bytes(block[i * 4..i * 4 + 4].try_into().unwrap());
+        if word(0) != MAGIC_START0
+            || word(1) != MAGIC_START1
+            || u32::from_le_bytes(block[BLOCK_SIZE - 4..].try_into().unwrap()) != MAGIC_END
+        {
+            return Err(ElfError::ParseError(format!(
+                "block {} has a bad magic number.",
+                number
+            )));
+        }
+        let (flags, address, size) = (word(2), word(3), word(4));
+        if flags & FLAG_NOT_MAIN_FLASH != 0 {
+            continue;
+        }
+        if size > MAX_PAYLOAD {
+            return Err(ElfError::ParseError(format!(
+                "block {} has a payload of {} bytes.",
+                number, size
+            )));
+        }
+        if flags & FLAG_FAMILY_ID != 0 {
+            family = Some(word(7));
+        }
+        chunks.push(Chunk {
+            address: address as u64,
+            data: block[32..32 + size as usize].to_vec(),
+        });
+    }
+    Ok((normalize(chunks)?, family))
+}
@@ -0,0 +1,196 @@
+//! Symbol resolution across a set of files, as a sanity check before
+//! deploying them together: undefined symbols no file defines and symbols
+//! with a strong definition in more than one file.
+//!
+//! Executables and shared libraries are checked through `.dynsym` with
+//! symbol versions, relocatable objects through `.symtab`.
+
+use std::collections::BTreeMap;
+
+use super::symbols::{SHN_COMMON, Symbol};
+use super::types::{ElfType, SymbolBinding, SymbolType};
+use super::versions::SymbolVersion;
+use super::{ElfFile, Result};
+
+/// Symbols the static linker defines when linking relocatable objects.
+const LINKER_DEFINED: &[&str] = &[
+    "_GLOBAL_OFFSET_TABLE_",
+    "_DYNAMIC",
+    "_PROCEDURE_LINKAGE_TABLE_",
+    "__ehdr_start",
+    "__executable_start",
+    "_etext",
+    "_edata",
+    "_end",
+    "__bss_start",
+    "__preinit_array_start",
+    "__preinit_array_end",
+    "__init_array_start",
+    "__init_array_end",
+    "__fini_array_start",
+    "__fini_array_end",
+];
+
+/// A symbol defined or referenced by a file of the set.
+#[derive(Debug, Clone)]
+pub struct LinkSymbol {
+    /// Index of the file in the set.
+    pub file: usize,
+    pub name: String,
+    pub version: Option<SymbolVersion>,
+    pub binding: SymbolBinding,
+    /// A tentative definition (`SHN_COMMON`), which the linker merges.
+    pub common: bool,
+}
+
+impl LinkSymbol {
+    pub fn is_weak(&self) -> bool {
+        self.binding == SymbolBinding::Weak
+    }
+
+    pub fn display_name(&self) -> String {
+        SymbolVersion::qualify(self.version.as_ref(), &self.name)
+    }
+}
+
+/// A symbol with a strong definition in more than one file.
+#[derive(Debug, Clone)]
+pub struct DuplicateDefinition {
+    pub name: String,
+    pub version: Option<String>,
+    /// Indices of the defining files.
+    pub files: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkCheck {
+    /// References no file defines, leaving out weak ones.
+    pub undefined: Vec<LinkSymbol>,
+    /// Weak references no file defines, which resolve to zero.
+    pub weak_undefined: Vec<LinkSymbol>,
+    pub duplicates: Vec<DuplicateDefinition>,
+}
+
+impl LinkCheck {
+    pub fn is_clean(&self) -> bool {
+        self.undefined.is_empty() && self.duplicates.is_empty()
+    }
+}
+
+impl ElfFile {
+    /// Returns the global and weak symbols `self` defines and references,
+    /// as file `file` of a set.
+    pub fn link_symbols(&self, file: usize) -> Result<(Vec<LinkSymbol>, Vec<LinkSymbol>)> {
+        let link_symbol = |symbol: Symbol, version: Option<SymbolVersion>| LinkSymbol {
+            file,
+            common: symbol.st_shndx == SHN_COMMON,
+            binding: symbol.get_binding(),
+            name: symbol.name,
+            version,
+        };
+        if self.get_type() == ElfType::Relocatable {
+            let mut defined = Vec::new();
+            let mut undefined = Vec::new();
+            for symbol in self.get_symbols()? {
+                if symbol.name.is_empty()
+                    || symbol.get_binding() == SymbolBinding::Local
+                    || matches!(symbol.get_type(), SymbolType::Section | SymbolType::File)
+                {
+                    continue;
+                }
+                if symbol.is_undefined() {
+                    if !LINKER_DEFINED.contains(&symbol.name.as_str()) {
+                        undefined.push(link_symbol(symbol, None));
+                    }
+                } else {
+                    defined.push(link_symbol(symbol, None));
+                }
+            }
+            return Ok((defined, undefined));
+        }
+        let defined = self
+            .get_exported_symbols()?
+            .into_iter()
+            .map(|(symbol, version)| link_symbol(symbol, version))
+            .collect();
+        let undefined = self
+            .get_imported_symbols()?
+            .into_iter()
+            .filter(|(symbol, _)| symbol.get_type() != SymbolType::Section)
+            .map(|(symbol, version)| link_symbol(symbol, version))
+            .collect();
+        Ok((defined, undefined))
+    }
+}
+
+/// Resolves the references of each file against the definitions of all
+/// files of the set.
+pub fn check_links(files: &[ElfFile]) -> Result<LinkCheck> {
+    let mut definitions: BTreeMap<String, Vec<LinkSymbol>> = BTreeMap::new();
+    let mut references = Vec::new();
+    for (index, elf) in files.iter().enumerate() {
+        let (defined, undefined) = elf.link_symbols(index)?;
+        for symbol in defined {
+            definitions
+                .entry(symbol.name.clone())
+                .or_default()
+                .push(symbol);
+        }
+        references.extend(undefined);
+    }
+
+    let mut undefined = Vec::new();
+    let mut weak_undefined = Vec::new();
+    for reference in references {
+        let defined = definitions.get(&reference.name).is_some_and(|candidates| {
+            candidates.iter().any(|definition| {
+                SymbolVersion::satisfies(definition.version.as_ref(), reference.version.as_ref())
+            })
+        });
+        if defined {
+            continue;
+        }
+        if reference.is_weak() {
+            weak_undefined.push(reference);
+        } else {
+            undefined.push(reference);
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for (name, candidates) in &definitions {
+        // keyed by version, as `foo@V1` and `foo@V2` are different symbols
+        let mut by_version: BTreeMap<Option<&str>, Vec<usize>> = BTreeMap::new();
+        for definition in candidates {
+            // copy relocations and interposers carry a version the file needs
+            // from elsewhere rather than one it defines
+            if definition.is_weak()
+                || definition.common
+                || definition.version.as_ref().is_some_and(|v| !v.defined)
+            {
+                continue;
+            }
+            let files = by_version
+                .entry(definition.version.as_ref().map(|v| v.name.as_str()))
+                .or_default();
+            if !files.contains(&definition.file) {
+                files.push(definition.file);
+            }
+        }
+        for (version, files) in by_version {
+            if files.len() > 1 {
+                duplicates.push(DuplicateDefinition {
+                    name: name.clone(),
+                    version: version.map(str::to_string),
+                    files,
+                });
+            }
+        }
+    }
+
+    Ok(LinkCheck {
+        undefined,
+        weak_undefined,
+        duplicates,
+    })
+}
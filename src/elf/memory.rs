@@ -0,0 +1,441 @@
+//! Memory region usage of firmware: how much of each flash and RAM region
+//! the allocated sections take up, with regions taken from the `MEMORY`
+//! block of a GNU linker script or from a plain list.
+
+use super::error::ElfError;
+use super::header::{SHF_ALLOC, SHF_TLS};
+use super::types::{SectionType, SegmentType};
+use super::{ElfFile, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub name: String,
+    pub origin: u64,
+    pub length: u64,
+}
+
+impl MemoryRegion {
+    pub fn end(&self) -> u64 {
+        self.origin.saturating_add(self.length)
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.origin && address < self.end()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Punct(char),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(Token::Number(parse_number(&word)?));
+        } else if c.is_alphabetic() || "_.$".contains(c) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || "_.$".contains(chars[i])) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses a linker script number: hexadecimal with `0x`, octal with a
+/// leading `0`, otherwise decimal, optionally scaled by a `K` or `M` suffix.
+fn parse_number(word: &str) -> Result<u64> {
+    let (digits, scale) = match word.as_bytes().last() {
+        Some(b'K' | b'k') => (&word[..word.len() - 1], 1024),
+        Some(b'M' | b'm') => (&word[..word.len() - 1], 1024 * 1024),
+        _ => (word, 1),
+    };
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        u64::from_str_radix(&digits[1..], 8)
+    } else {
+        digits.parse()
+    };
+    value
+        .ok()
+        .and_then(|value| value.checked_mul(scale))
+        .ok_or_else(|| ElfError::ParseError(format!("invalid number '{}'.", word)))
+}
+
+/// Recursive descent over the tokens of an expression list. `ORIGIN()` and
+/// `LENGTH()` refer to the regions parsed so far.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    regions: &'a [MemoryRegion],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.pos += 1;
+        self.tokens.get(self.pos - 1)
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", c)))
+        }
+    }
+
+    fn error(&self, expected: &str) -> ElfError {
+        let found = match self.peek() {
+            Some(Token::Ident(name)) => format!("'{}'", name),
+            Some(Token::Number(value)) => format!("{}", value),
+            Some(Token::Punct(c)) => format!("'{}'", c),
+            None => "the end".to_string(),
+        };
+        ElfError::ParseError(format!("expected {} but found {}.", expected, found))
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("a name")),
+        }
+    }
+
+    fn expression(&mut self) -> Result<u64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value = value.wrapping_add(self.term()?);
+            } else if self.eat('-') {
+                value = value.wrapping_sub(self.term()?);
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<u64> {
+        let mut value = self.factor()?;
+        loop {
+            if self.eat('*') {
+                value = value.wrapping_mul(self.factor()?);
+            } else if self.eat('/') {
+                let divisor = self.factor()?;
+                value = value
+                    .checked_div(divisor)
+                    .ok_or_else(|| ElfError::ParseError("division by zero.".to_string()))?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<u64> {
+        match self.next().cloned() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Punct('(')) => {
+                let value = self.expression()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some(Token::Ident(function)) if function == "ORIGIN" || function == "LENGTH" => {
+                self.expect('(')?;
+                let name = self.ident()?;
+                self.expect(')')?;
+                let region = self
+                    .regions
+                    .iter()
+                    .find(|region| region.name == name)
+                    .ok_or_else(|| {
+                        ElfError::ParseError(format!("unknown memory region '{}'.", name))
+                    })?;
+                Ok(if function == "ORIGIN" {
+                    region.origin
+                } else {
+                    region.length
+                })
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.error("a number"))
+            }
+        }
+    }
+
+    /// Parses `name [(attributes)] : ORIGIN = expr, LENGTH = expr`.
+    fn region(&mut self) -> Result<MemoryRegion> {
+        let name = self.ident()?;
+        if self.eat('(') {
+            while !self.eat(')') {
+                if self.next().is_none() {
+                    return Err(self.error("')'"));
+                }
+            }
+        }
+        self.expect(':')?;
+        self.keyword(&["ORIGIN", "org", "o"])?;
+        let origin = self.expression()?;
+        self.eat(',');
+        self.keyword(&["LENGTH", "len", "l"])?;
+        let length = self.expression()?;
+        self.eat(',');
+        Ok(MemoryRegion {
+            name,
+            origin,
+            length,
+        })
+    }
+
+    fn keyword(&mut self, names: &[&str]) -> Result<()> {
+        match self.peek() {
+            Some(Token::Ident(name)) if names.contains(&name.as_str()) => {
+                self.pos += 1;
+                self.expect('=')
+            }
+            _ => Err(self.error(&format!("'{}'", names[0]))),
+        }
+    }
+}
+
+/// Returns the position of the `MEMORY {` tokens.
+fn memory_block(tokens: &[Token]) -> Option<usize> {
+    tokens.windows(2).position(|pair| {
+        matches!(&pair[0], Token::Ident(name) if name == "MEMORY") && pair[1] == Token::Punct('{')
+    })
+}
+
+/// Reads the `MEMORY` block of a GNU linker script.
+pub fn parse_linker_script(text: &str) -> Result<Vec<MemoryRegion>> {
+    let tokens = tokenize(text)?;
+    let start = memory_block(&tokens).ok_or_else(|| {
+        ElfError::ParseError("the linker script has no MEMORY block.".to_string())
+    })?;
+    let mut regions = Vec::new();
+    let mut index = start + 2;
+    loop {
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: index,
+            regions: &regions,
+        };
+        if parser.eat('}') {
+            break;
+        }
+        if parser.at_end() {
+            return Err(parser.error("'}'"));
+        }
+        let region = parser.region()?;
+        index = parser.pos;
+        regions.push(region);
+    }
+    Ok(regions)
+}
+
+/// Reads a region list with one `NAME ORIGIN LENGTH` line per region.
+/// `#` starts a comment.
+pub fn parse_region_list(text: &str) -> Result<Vec<MemoryRegion>> {
+    let mut regions = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let tokens = tokenize(line)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            regions: &regions,
+        };
+        let region = (|| {
+            let name = parser.ident()?;
+            let origin = parser.expression()?;
+            let length = parser.expression()?;
+            if !parser.at_end() {
+                return Err(parser.error("the end of the line"));
+            }
+            Ok(MemoryRegion {
+                name,
+                origin,
+                length,
+            })
+        })()
+        .map_err(|e| match e {
+            ElfError::ParseError(msg) => ElfError::ParseError(format!("line {}: {}", i + 1, msg)),
+            e => e,
+        })?;
+        regions.push(region);
+    }
+    Ok(regions)
+}
+
+/// Reads regions from a linker script if the text has a `MEMORY` block, and
+/// from a region list otherwise.
+pub fn parse_regions(text: &str) -> Result<Vec<MemoryRegion>> {
+    let has_memory = tokenize(text).is_ok_and(|tokens| memory_block(&tokens).is_some());
+    let regions = if has_memory {
+        parse_linker_script(text)?
+    } else {
+        parse_region_list(text)?
+    };
+    if regions.is_empty() {
+        return Err(ElfError::ParseError(
+            "no memory regions are defined.".to_string(),
+        ));
+    }
+    Ok(regions)
+}
+
+/// Where an allocated section lives at run time (VMA) and where it is
+/// stored (LMA). Sections without file contents have no LMA.
+#[derive(Debug, Clone)]
+pub struct SectionPlacement {
+    pub index: usize,
+    pub size: u64,
+    pub vma: u64,
+    pub lma: Option<u64>,
+    pub vma_region: Option<usize>,
+    pub lma_region: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RegionUsage {
+    pub region: MemoryRegion,
+    pub used: u64,
+}
+
+impl RegionUsage {
+    pub fn percent(&self) -> f64 {
+        if self.region.length == 0 {
+            if self.used == 0 { 0.0 } else { f64::INFINITY }
+        } else {
+            self.used as f64 * 100.0 / self.region.length as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MemoryUsage {
+    pub regions: Vec<RegionUsage>,
+    pub sections: Vec<SectionPlacement>,
+}
+
+impl ElfFile {
+    /// Returns the load address of section `index`: its address moved by
+    /// the `p_paddr - p_vaddr` of the `PT_LOAD` segment whose file contents
+    /// hold it, or its address if no segment does.
+    pub fn section_lma(&self, index: usize) -> Option<u64> {
+        let sh = self.section_headers.get(index)?;
+        if sh.get_type() == SectionType::Nobits {
+            return None;
+        }
+        let segment = self.program_headers.iter().find(|ph| {
+            ph.get_type() == SegmentType::Load
+                && sh.sh_offset() >= ph.p_offset()
+                && sh
+                    .sh_offset()
+                    .checked_add(sh.sh_size())
+                    .zip(ph.p_offset().checked_add(ph.p_filesz()))
+                    .is_some_and(|(section_end, segment_end)| section_end <= segment_end)
+        });
+        Some(match segment {
+            Some(ph) => ph.p_paddr().wrapping_add(sh.sh_offset() - ph.p_offset()),
+            None => sh.sh_addr(),
+        })
+    }
+
+    /// Charges every allocated section to the region holding its VMA and,
+    /// when it is stored elsewhere (e.g. `.data` copied from flash to RAM),
+    /// to the region holding its LMA as well.
+    pub fn memory_usage(&self, regions: &[MemoryRegion]) -> MemoryUsage {
+        let mut usage: Vec<RegionUsage> = regions
+            .iter()
+            .map(|region| RegionUsage {
+                region: region.clone(),
+                used: 0,
+            })
+            .collect();
+        let find = |address: u64| regions.iter().position(|region| region.contains(address));
+        let mut sections = Vec::new();
+        for (index, sh) in self.section_headers.iter().enumerate() {
+            let nobits = sh.get_type() == SectionType::Nobits;
+            if sh.sh_flags() & SHF_ALLOC == 0 || sh.sh_size() == 0 {
+                continue;
+            }
+            // .tbss only describes the per-thread copies
+            if nobits && sh.sh_flags() & SHF_TLS != 0 {
+                continue;
+            }
+            let lma = self.section_lma(index);
+            let placement = SectionPlacement {
+                index,
+                size: sh.sh_size(),
+                vma: sh.sh_addr(),
+                lma,
+                vma_region: find(sh.sh_addr()),
+                lma_region: lma.and_then(find),
+            };
+            if let Some(region) = placement.vma_region {
+                usage[region].used = usage[region].used.saturating_add(placement.size);
+            }
+            if let Some(region) = placement.lma_region
+                && placement.lma_region != placement.vma_region
+            {
+                usage[region].used = usage[region].used.saturating_add(placement.size);
+            }
+            sections.push(placement);
+        }
+        MemoryUsage {
+            regions: usage,
+            sections,
+        }
+    }
+}
@@ -1,5 +1,34 @@
+pub mod abi;
+pub mod attributes;
+pub mod bloat;
+pub mod compress;
+pub mod demangle;
+pub mod deps;
+pub mod diff;
+pub mod dwarf;
+pub mod dynamic;
+pub mod edit;
+pub mod eflags;
+pub mod ehabi;
 pub mod error;
+pub mod functions;
 pub mod header;
+pub mod image;
+pub mod linkcheck;
+pub mod memory;
+pub mod names;
+pub mod notes;
+pub mod relocations;
+pub mod sizediff;
+pub mod stream;
+pub mod strip;
+pub mod symbols;
+pub mod types;
+pub mod versions;
+pub mod writer;
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::OnceLock;
 
 type Result<T> = std::result::Result<T, error::ElfError>;
 
@@ -22,23 +51,152 @@ pub struct ElfFile {
     pub program_headers: Vec<header::ProgramHeader>,
     pub section_headers: Vec<header::SectionHeader>,
     pub data: Vec<u8>,
+    /// Decompressed contents of compressed sections, filled on first access
+    /// and indexed like `section_headers`.
+    decompressed: Vec<OnceLock<std::result::Result<Vec<u8>, String>>>,
+}
+
+pub(crate) fn read_u16<R: Read>(reader: &mut R, big_endian: bool) -> std::io::Result<u16> {
+    if big_endian {
+        byteorder::ReadBytesExt::read_u16::<byteorder::BigEndian>(reader)
+    } else {
+        byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)
+    }
+}
+
+pub(crate) fn read_u32<R: Read>(reader: &mut R, big_endian: bool) -> std::io::Result<u32> {
+    if big_endian {
+        byteorder::ReadBytesExt::read_u32::<byteorder::BigEndian>(reader)
+    } else {
+        byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(reader)
+    }
+}
+
+pub(crate) fn read_u64<R: Read>(reader: &mut R, big_endian: bool) -> std::io::Result<u64> {
+    if big_endian {
+        byteorder::ReadBytesExt::read_u64::<byteorder::BigEndian>(reader)
+    } else {
+        byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(reader)
+    }
+}
+
+pub(crate) fn write_u16<W: Write>(
+    writer: &mut W,
+    value: u16,
+    big_endian: bool,
+) -> std::io::Result<()> {
+    if big_endian {
+        byteorder::WriteBytesExt::write_u16::<byteorder::BigEndian>(writer, value)
+    } else {
+        byteorder::WriteBytesExt::write_u16::<byteorder::LittleEndian>(writer, value)
+    }
+}
+
+pub(crate) fn write_u32<W: Write>(
+    writer: &mut W,
+    value: u32,
+    big_endian: bool,
+) -> std::io::Result<()> {
+    if big_endian {
+        byteorder::WriteBytesExt::write_u32::<byteorder::BigEndian>(writer, value)
+    } else {
+        byteorder::WriteBytesExt::write_u32::<byteorder::LittleEndian>(writer, value)
+    }
+}
+
+pub(crate) fn write_u64<W: Write>(
+    writer: &mut W,
+    value: u64,
+    big_endian: bool,
+) -> std::io::Result<()> {
+    if big_endian {
+        byteorder::WriteBytesExt::write_u64::<byteorder::BigEndian>(writer, value)
+    } else {
+        byteorder::WriteBytesExt::write_u64::<byteorder::LittleEndian>(writer, value)
+    }
+}
+
+/// Writes a class-sized word: 4 bytes for ELF32, 8 bytes for ELF64.
+pub(crate) fn write_word<W: Write>(
+    writer: &mut W,
+    value: u64,
+    is_64: bool,
+    big_endian: bool,
+) -> std::io::Result<()> {
+    if is_64 {
+        write_u64(writer, value, big_endian)
+    } else {
+        write_u32(writer, value as u32, big_endian)
+    }
+}
+
+/// Reads a class-sized word: 4 bytes for ELF32, 8 bytes for ELF64.
+pub(crate) fn read_word<R: Read>(
+    reader: &mut R,
+    is_64: bool,
+    big_endian: bool,
+) -> std::io::Result<u64> {
+    if is_64 {
+        read_u64(reader, big_endian)
+    } else {
+        read_u32(reader, big_endian).map(|v| v as u64)
+    }
+}
+
+/// Reads the `u16` at `position` of `data`, if it is in bounds.
+pub(crate) fn field_u16(data: &[u8], position: usize, big_endian: bool) -> Option<u16> {
+    read_u16(
+        &mut std::io::Cursor::new(data.get(position..position.checked_add(2)?)?),
+        big_endian,
+    )
+    .ok()
+}
+
+/// Reads the `u32` at `position` of `data`, if it is in bounds.
+pub(crate) fn field_u32(data: &[u8], position: usize, big_endian: bool) -> Option<u32> {
+    read_u32(
+        &mut std::io::Cursor::new(data.get(position..position.checked_add(4)?)?),
+        big_endian,
+    )
+    .ok()
+}
+
+/// Reads an unsigned LEB128-encoded integer.
+pub(crate) fn read_uleb128<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = byteorder::ReadBytesExt::read_u8(reader)?;
+        if shift < 64 {
+            result |= ((byte & 0x7F) as u64) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
 }
 
 impl ElfFile {
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
-        // magic number
-        if &data[0..4] != b"\x7FELF" {
-            return Err(error::ElfError::InvalidMagic);
-        }
+        let mut elf_file = Self::parse_headers(&mut std::io::Cursor::new(data), data.len() as u64)?;
+        elf_file.data = data.to_vec();
+        Ok(elf_file)
+    }
 
+    /// Parses the file header, program header table and section header table
+    /// from `reader`. Section contents are not read, so `data` is left empty.
+    pub(crate) fn parse_headers<R: Read + Seek>(reader: &mut R, len: u64) -> Result<Self> {
         let mut e_ident = [0u8; 16];
-        e_ident.copy_from_slice(&data[0..16]);
+        reader.seek(SeekFrom::Start(0))?;
+        if reader.read_exact(&mut e_ident).is_err() || &e_ident[0..4] != b"\x7FELF" {
+            // magic number
+            return Err(error::ElfError::InvalidMagic);
+        }
 
         let elf_class = e_ident[4];
         let elf_data = e_ident[5];
         let elf_version = e_ident[6];
-        let elf_osabi = e_ident[7];
-        let elf_abiversion = e_ident[8];
 
         // check elf class
         if elf_class != 1 && elf_class != 2 {
@@ -55,256 +213,79 @@ impl ElfFile {
             return Err(error::ElfError::UnsupportedVersion(elf_version));
         }
 
-        let elf_file = if elf_class == 1 {
-            let reader = &mut std::io::Cursor::new(&data[16..]);
-            let e_type = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_machine = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_version = byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(reader)?;
-            let e_entry =
-                byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(reader)? as u64;
-            let e_phoff =
-                byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(reader)? as u64;
-            let e_shoff =
-                byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(reader)? as u64;
-            let e_flags = byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(reader)?;
-            let e_ehsize = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_phentsize = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_phnum = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_shentsize = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_shnum = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_shstrndx = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-
-            // parse program header
-            let mut program_headers = Vec::new();
-            for i in 0..e_phnum {
-                let offset = (e_phoff + (i as u64 * e_phentsize as u64)) as usize;
-                if offset + 32 > data.len() {
-                    return Err(error::ElfError::ParseError(
-                        "program header exceeds file range".to_string(),
-                    ));
-                }
-
-                let ph_reader = &mut std::io::Cursor::new(&data[offset..offset + 32]);
-                let p_type =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_offset =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_vaddr =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_paddr =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_filesz =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_memsz =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_flags =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_align =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-
-                program_headers.push(header::ProgramHeader::Elf32(header::ProgramHeader32 {
-                    p_type,
-                    p_offset,
-                    p_vaddr,
-                    p_paddr,
-                    p_filesz,
-                    p_memsz,
-                    p_flags,
-                    p_align,
-                }));
-            }
-
-            // parse section header
-            let mut section_headers = Vec::new();
-            for i in 0..e_shnum {
-                let offset = (e_shoff + (i as u64 * e_shentsize as u64)) as usize;
-                if offset + 40 > data.len() {
-                    return Err(error::ElfError::ParseError(
-                        "section header exceeds file range.".to_string(),
-                    ));
-                }
-
-                let sh_reader = &mut std::io::Cursor::new(&data[offset..offset + 40]);
-                let sh_name =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_type =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_flags =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_addr =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_offset =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_size =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_link =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_info =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_addralign =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_entsize =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-
-                section_headers.push(header::SectionHeader::Elf32(header::SectionHeader32 {
-                    sh_name,
-                    sh_type,
-                    sh_flags,
-                    sh_addr,
-                    sh_offset,
-                    sh_size,
-                    sh_link,
-                    sh_info,
-                    sh_addralign,
-                    sh_entsize,
-                }));
-            }
-
-            // return ElfFile
-            ElfFile {
-                e_ident,
-                e_type,
-                e_machine,
-                e_version,
-                e_entry,
-                e_phoff,
-                e_shoff,
-                e_flags,
-                e_ehsize,
-                e_phentsize,
-                e_phnum,
-                e_shentsize,
-                e_shnum,
-                e_shstrndx,
-                program_headers,
-                section_headers,
-                data: data.to_vec(),
-            }
-        } else {
-            // parse elf64
-            let reader = &mut std::io::Cursor::new(&data[16..]);
-            let e_type = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_machine = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_version = byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(reader)?;
-            let e_entry = byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(reader)?;
-            let e_phoff = byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(reader)?;
-            let e_shoff = byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(reader)?;
-            let e_flags = byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(reader)?;
-            let e_ehsize = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_phentsize = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_phnum = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_shentsize = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_shnum = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-            let e_shstrndx = byteorder::ReadBytesExt::read_u16::<byteorder::LittleEndian>(reader)?;
-
-            // program header
-            let mut program_headers = Vec::new();
-            for i in 0..e_phnum {
-                let offset = (e_phoff + (i as u64 * e_phentsize as u64)) as usize;
-                if offset + 56 > data.len() {
-                    return Err(error::ElfError::ParseError(
-                        "program header exceeds file range.".to_string(),
-                    ));
-                }
-
-                let ph_reader = &mut std::io::Cursor::new(&data[offset..offset + 56]);
-                let p_type =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_flags =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(ph_reader)?;
-                let p_offset =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(ph_reader)?;
-                let p_vaddr =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(ph_reader)?;
-                let p_paddr =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(ph_reader)?;
-                let p_filesz =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(ph_reader)?;
-                let p_memsz =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(ph_reader)?;
-                let p_align =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(ph_reader)?;
-
-                program_headers.push(header::ProgramHeader::Elf64(header::ProgramHeader64 {
-                    p_type,
-                    p_flags,
-                    p_offset,
-                    p_vaddr,
-                    p_paddr,
-                    p_filesz,
-                    p_memsz,
-                    p_align,
-                }));
-            }
-
-            // section header
-            let mut section_headers = Vec::new();
-            for i in 0..e_shnum {
-                let offset = (e_shoff + (i as u64 * e_shentsize as u64)) as usize;
-                if offset + 64 > data.len() {
-                    return Err(error::ElfError::ParseError(
-                        "section header exceeds file range.".to_string(),
-                    ));
-                }
-
-                let sh_reader = &mut std::io::Cursor::new(&data[offset..offset + 64]);
-                let sh_name =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_type =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_flags =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_addr =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_offset =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_size =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_link =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_info =
-                    byteorder::ReadBytesExt::read_u32::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_addralign =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(sh_reader)?;
-                let sh_entsize =
-                    byteorder::ReadBytesExt::read_u64::<byteorder::LittleEndian>(sh_reader)?;
-
-                section_headers.push(header::SectionHeader::Elf64(header::SectionHeader64 {
-                    sh_name,
-                    sh_type,
-                    sh_flags,
-                    sh_addr,
-                    sh_offset,
-                    sh_size,
-                    sh_link,
-                    sh_info,
-                    sh_addralign,
-                    sh_entsize,
-                }));
-            }
+        let is_64 = elf_class == 2;
+        let be = elf_data == 2;
+
+        let e_type = read_u16(reader, be)?;
+        let e_machine = read_u16(reader, be)?;
+        let e_version = read_u32(reader, be)?;
+        let e_entry = read_word(reader, is_64, be)?;
+        let e_phoff = read_word(reader, is_64, be)?;
+        let e_shoff = read_word(reader, is_64, be)?;
+        let e_flags = read_u32(reader, be)?;
+        let e_ehsize = read_u16(reader, be)?;
+        let e_phentsize = read_u16(reader, be)?;
+        let e_phnum = read_u16(reader, be)?;
+        let e_shentsize = read_u16(reader, be)?;
+        let e_shnum = read_u16(reader, be)?;
+        let e_shstrndx = read_u16(reader, be)?;
+
+        // parse program header
+        let ph_size = if is_64 { 56 } else { 32 };
+        let mut program_headers = Vec::new();
+        for i in 0..e_phnum {
+            let offset = (i as u64)
+                .checked_mul(e_phentsize as u64)
+                .and_then(|position| e_phoff.checked_add(position))
+                .filter(|offset| offset.checked_add(ph_size).is_some_and(|end| end <= len));
+            let Some(offset) = offset else {
+                return Err(error::ElfError::ParseError(
+                    "program header exceeds file range.".to_string(),
+                ));
+            };
+
+            reader.seek(SeekFrom::Start(offset))?;
+            program_headers.push(header::ProgramHeader::read_from(reader, is_64, be)?);
+        }
 
-            ElfFile {
-                e_ident,
-                e_type,
-                e_machine,
-                e_version,
-                e_entry,
-                e_phoff,
-                e_shoff,
-                e_flags,
-                e_ehsize,
-                e_phentsize,
-                e_phnum,
-                e_shentsize,
-                e_shnum,
-                e_shstrndx,
-                program_headers,
-                section_headers,
-                data: data.to_vec(),
-            }
-        };
+        // parse section header
+        let sh_size = if is_64 { 64 } else { 40 };
+        let mut section_headers = Vec::new();
+        for i in 0..e_shnum {
+            let offset = (i as u64)
+                .checked_mul(e_shentsize as u64)
+                .and_then(|position| e_shoff.checked_add(position))
+                .filter(|offset| offset.checked_add(sh_size).is_some_and(|end| end <= len));
+            let Some(offset) = offset else {
+                return Err(error::ElfError::ParseError(
+                    "section header exceeds file range.".to_string(),
+                ));
+            };
+
+            reader.seek(SeekFrom::Start(offset))?;
+            section_headers.push(header::SectionHeader::read_from(reader, is_64, be)?);
+        }
 
-        Ok(elf_file)
+        Ok(ElfFile {
+            e_ident,
+            e_type,
+            e_machine,
+            e_version,
+            e_entry,
+            e_phoff,
+            e_shoff,
+            e_flags,
+            e_ehsize,
+            e_phentsize,
+            e_phnum,
+            e_shentsize,
+            e_shnum,
+            e_shstrndx,
+            program_headers,
+            decompressed: section_headers.iter().map(|_| OnceLock::new()).collect(),
+            section_headers,
+            data: Vec::new(),
+        })
     }
 
     pub fn get_class(&self) -> &'static str {
@@ -323,107 +304,106 @@ impl ElfFile {
         }
     }
 
-    pub fn get_osabi(&self) -> &'static str {
-        match self.e_ident[7] {
-            0 => "System V",
-            1 => "HP-UX",
-            2 => "NetBSD",
-            3 => "GNU/Linux",
-            6 => "Solaris",
-            7 => "AIX",
-            8 => "IRIX",
-            9 => "FreeBSD",
-            10 => "Tru64",
-            11 => "Novell Modesto",
-            12 => "OpenBSD",
-            13 => "OpenVMS",
-            14 => "NonStop Kernel",
-            15 => "AROS",
-            16 => "Fenix OS",
-            17 => "CloudABI",
-            18 => "Stratus Technologies OpenVOS",
-            64 => "ARM EABI",
-            97 => "ARM",
-            _ => "Unknown",
-        }
+    pub fn get_osabi(&self) -> types::OsAbi {
+        types::OsAbi::from(self.e_ident[7])
     }
 
-    pub fn get_type(&self) -> &'static str {
-        match self.e_type {
-            0 => "None",
-            1 => "Relocatable",
-            2 => "Executable",
-            3 => "Shared",
-            4 => "Core",
-            0xFF00..=0xFFFF => "Processor-specific",
-            _ => "Unknown",
+    pub fn get_type(&self) -> types::ElfType {
+        types::ElfType::from(self.e_type)
+    }
+
+    pub fn get_machine(&self) -> types::Machine {
+        types::Machine::from(self.e_machine)
+    }
+
+    pub fn is_64(&self) -> bool {
+        self.e_ident[4] == 2
+    }
+
+    pub fn is_big_endian(&self) -> bool {
+        self.e_ident[5] == 2
+    }
+
+    pub fn get_flags(&self) -> eflags::EFlags {
+        eflags::EFlags::new(self.get_machine(), self.e_flags)
+    }
+
+    /// Returns the contents of section `index`, decompressed if it is a
+    /// `SHF_COMPRESSED` or `.zdebug_*` section. Returns `None` if the section
+    /// occupies no file space, lies outside the file or fails to decompress.
+    pub fn get_section_data(&self, index: usize) -> Option<&[u8]> {
+        self.try_get_section_data(index).ok().flatten()
+    }
+
+    /// Like [`ElfFile::get_section_data`], but reports decompression errors.
+    pub fn try_get_section_data(&self, index: usize) -> Result<Option<&[u8]>> {
+        let raw = match self.get_raw_section_data(index) {
+            Some(raw) => raw,
+            None => return Ok(None),
+        };
+        let name = self.get_section_name(index).unwrap_or_default();
+        let compression = match compress::Compression::from_section(
+            &self.section_headers[index],
+            &name,
+            raw,
+            self.is_64(),
+            self.is_big_endian(),
+        )? {
+            Some(compression) => compression,
+            None => return Ok(Some(raw)),
+        };
+
+        let cached = match self.decompressed.get(index) {
+            Some(cell) => cell.get_or_init(|| {
+                compression.decompress(raw).map_err(|e| match e {
+                    error::ElfError::ParseError(msg) => msg,
+                    e => e.to_string(),
+                })
+            }),
+            // the header table was modified after parsing
+            None => {
+                return Err(error::ElfError::ParseError(
+                    "section cache is out of date.".to_string(),
+                ));
+            }
+        };
+        match cached {
+            Ok(data) => Ok(Some(data)),
+            Err(e) => Err(error::ElfError::ParseError(e.clone())),
         }
     }
 
-    pub fn get_machine(&self) -> &'static str {
-        match self.e_machine {
-            0 => "None",
-            1 => "M32",
-            2 => "SPARC",
-            3 => "Intel 80386",
-            4 => "Motorola 68K",
-            5 => "Motorola 88K",
-            6 => "Intel MCU",
-            7 => "Intel 80860",
-            8 => "MIPS",
-            9 => "S370",
-            10=>"MIPS RS3 LE",
-            15=> "PA-RISC",
-            17=> "VPP500",
-            18=> "SPARC32 Plus",
-            19=>"Intel 80960",
-            20 => "PowerPC",
-            21 => "PowerPC64",
-            22 => "IBM S/390",
-            23=>"IBM SPU",
-            40 => "ARM",
-            42 => "SuperH",
-            43 => "SparcV9",
-            44 => "Tricore",
-            45 => "ARC",
-            46 => "H8/300",
-            47=>"H8/300H",
-            48=>"H8S",
-            49=>"H8/500",
-            50 => "IA-64",
-            51 => "MIPS-X",
-            52 => "Coldfire",
-            53 => "M68HC12",
-            54 => "MMA",
-            55 => "PCP",
-            56 => "Sony nCPU",
-            57 => "Denso NDR1",
-            58 => "Start*Core",
-            59 => "ME16",
-            60=>"ST100",
-            61 => "Tinyj",
-            62 => "x86-64",
-            63 => "PDSP",
-            64 => "PDP-10",
-            65 => "PDP-11",
-            66 => "FX66",
-            67 => "ST9+",
-            68 => "ST7",
-            69 => "MC68HC16",
-            70 => "MC68HC11",
-            71 => "MC68HC08",
-            72 => "MC68HC05",
-            73 => "SVx",
-            74 => "ST19",
-            75 => "VAX",
-            76 => "CRIS",
-
-            183 => "AArch64",
-            224=>"AMD GPU",
-            243 => "RISC-V",
-            258 => "LoongArch",
-            _ => "Unknown",
+    /// Returns the file contents of section `index` as stored, without
+    /// decompression, or `None` if the section occupies no file space or lies
+    /// outside the file.
+    pub fn get_raw_section_data(&self, index: usize) -> Option<&[u8]> {
+        let sh = self.section_headers.get(index)?;
+        if sh.get_type() == types::SectionType::Nobits {
+            return None;
         }
+        let start = usize::try_from(sh.sh_offset()).ok()?;
+        let end = start.checked_add(usize::try_from(sh.sh_size()).ok()?)?;
+        self.data.get(start..end)
+    }
+
+    /// Returns `len` bytes of the file image at virtual address `address`,
+    /// looked up through the `PT_LOAD` segments.
+    pub fn get_virtual_data(&self, address: u64, len: u64) -> Option<&[u8]> {
+        let ph = self.program_headers.iter().find(|ph| {
+            ph.get_type() == types::SegmentType::Load
+                && address >= ph.p_vaddr()
+                && address
+                    .checked_add(len)
+                    .is_some_and(|end| end <= ph.p_vaddr() + ph.p_filesz())
+        })?;
+        let start = usize::try_from(ph.p_offset() + (address - ph.p_vaddr())).ok()?;
+        self.data
+            .get(start..start.checked_add(usize::try_from(len).ok()?)?)
+    }
+
+    /// Finds the index of the first section called `name`.
+    pub fn find_section(&self, name: &str) -> Option<usize> {
+        (0..self.section_headers.len()).find(|&i| self.get_section_name(i).as_deref() == Some(name))
     }
 
     pub fn get_section_name(&self, index: usize) -> Option<String> {
@@ -433,29 +413,22 @@ impl ElfFile {
             return None;
         }
 
-        let shstrtab = match &self.section_headers[self.e_shstrndx as usize] {
-            header::SectionHeader::Elf32(sh) => {
-                &self.data[sh.sh_offset as usize..(sh.sh_offset + sh.sh_size) as usize]
-            }
-            header::SectionHeader::Elf64(sh) => {
-                &self.data[sh.sh_offset as usize..(sh.sh_offset + sh.sh_size) as usize]
-            }
-        };
-
-        let name_offset = match &self.section_headers[index] {
-            header::SectionHeader::Elf32(sh) => sh.sh_name as usize,
-            header::SectionHeader::Elf64(sh) => sh.sh_name as usize,
-        };
-
-        if name_offset >= shstrtab.len() {
-            return None;
-        }
+        let shstrtab = self.get_raw_section_data(self.e_shstrndx as usize)?;
+        let name_offset = self.section_headers[index].sh_name() as usize;
+        read_cstr(shstrtab, name_offset)
+    }
+}
 
-        // find '\0'
-        let end = shstrtab[name_offset..]
-            .iter()
-            .position(|&c| c == 0)
-            .unwrap_or(shstrtab.len() - name_offset);
-        String::from_utf8(shstrtab[name_offset..name_offset + end].to_vec()).ok()
+/// Reads the NUL-terminated string starting at `offset` in a string table.
+pub(crate) fn read_cstr(table: &[u8], offset: usize) -> Option<String> {
+    if offset >= table.len() {
+        return None;
     }
+
+    // find '\0'
+    let end = table[offset..]
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(table.len() - offset);
+    String::from_utf8(table[offset..offset + end].to_vec()).ok()
 }
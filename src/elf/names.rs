@@ -0,0 +1,115 @@
+//! Names for segment and section types whose meaning depends on the target
+//! machine or OS/ABI. Types with a single well-known meaning are covered by
+//! `SegmentType` and `SectionType` directly.
+
+use super::types::{Machine, OsAbi};
+
+/// Looks up a processor- or OS-specific program header type.
+///
+/// HP-UX assigns its own types at the bottom of the OS range, so those are
+/// only named when the file declares the HP-UX OS/ABI.
+pub fn segment_type_name(p_type: u32, machine: Machine, osabi: OsAbi) -> Option<&'static str> {
+    if osabi == OsAbi::HpUx {
+        let name = match p_type {
+            0x60000000 => Some("HP_TLS"),
+            0x60000001 => Some("HP_CORE_NONE"),
+            0x60000002 => Some("HP_CORE_VERSION"),
+            0x60000003 => Some("HP_CORE_KERNEL"),
+            0x60000004 => Some("HP_CORE_COMM"),
+            0x60000005 => Some("HP_CORE_PROC"),
+            0x60000006 => Some("HP_CORE_LOADABLE"),
+            0x60000007 => Some("HP_CORE_STACK"),
+            0x60000008 => Some("HP_CORE_SHM"),
+            0x60000009 => Some("HP_CORE_MMF"),
+            0x60000010 => Some("HP_PARALLEL"),
+            0x60000011 => Some("HP_FASTBIND"),
+            0x60000012 => Some("HP_OPT_ANNOT"),
+            0x60000013 => Some("HP_HSL_ANNOT"),
+            0x60000014 => Some("HP_STACK"),
+            _ => None,
+        };
+        if name.is_some() {
+            return name;
+        }
+    }
+
+    let name = match (machine, p_type) {
+        (Machine::Arm, 0x70000000) => "ARM_ARCHEXT",
+        (Machine::Arm, 0x70000001) => "ARM_EXIDX",
+        (Machine::AArch64, 0x70000002) => "AARCH64_MEMTAG_MTE",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000000) => "MIPS_REGINFO",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000001) => "MIPS_RTPROC",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000002) => "MIPS_OPTIONS",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000003) => "MIPS_ABIFLAGS",
+        (Machine::RiscV, 0x70000003) => "RISCV_ATTRIBUTES",
+        (Machine::Parisc, 0x70000000) => "PARISC_ARCHEXT",
+        (Machine::Parisc, 0x70000001) => "PARISC_UNWIND",
+        (Machine::Ia64, 0x70000000) => "IA_64_ARCHEXT",
+        (Machine::Ia64, 0x70000001) => "IA_64_UNWIND",
+        (Machine::PowerPc64, 0x70000000) => "PPC64_OPT",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Looks up a processor- or OS-specific section header type.
+///
+/// Solaris reuses part of the GNU range for its own types, so those are only
+/// named when the file declares the Solaris OS/ABI.
+pub fn section_type_name(sh_type: u32, machine: Machine, osabi: OsAbi) -> Option<&'static str> {
+    if osabi == OsAbi::Solaris {
+        let name = match sh_type {
+            0x6FFFFFEE => Some("SUNW_ancillary"),
+            0x6FFFFFEF => Some("SUNW_capchain"),
+            0x6FFFFFF0 => Some("SUNW_capinfo"),
+            0x6FFFFFF1 => Some("SUNW_symsort"),
+            0x6FFFFFF2 => Some("SUNW_tlssort"),
+            0x6FFFFFF3 => Some("SUNW_LDYNSYM"),
+            0x6FFFFFF4 => Some("SUNW_dof"),
+            0x6FFFFFF5 => Some("SUNW_cap"),
+            0x6FFFFFF6 => Some("SUNW_SIGNATURE"),
+            0x6FFFFFF7 => Some("SUNW_ANNOTATE"),
+            0x6FFFFFF8 => Some("SUNW_DEBUGSTR"),
+            0x6FFFFFF9 => Some("SUNW_DEBUG"),
+            _ => None,
+        };
+        if name.is_some() {
+            return name;
+        }
+    }
+
+    let name = match (machine, sh_type) {
+        (Machine::Arm, 0x70000001) => "ARM_EXIDX",
+        (Machine::Arm, 0x70000002) => "ARM_PREEMPTMAP",
+        (Machine::Arm, 0x70000003) => "ARM_ATTRIBUTES",
+        (Machine::Arm, 0x70000004) => "ARM_DEBUGOVERLAY",
+        (Machine::Arm, 0x70000005) => "ARM_OVERLAYSECTION",
+        (Machine::AArch64, 0x70000003) => "AARCH64_ATTRIBUTES",
+        (Machine::AArch64, 0x70000004) => "AARCH64_AUTH_RELR",
+        (Machine::AArch64, 0x70000007) => "AARCH64_MEMTAG_GLOBALS_STATIC",
+        (Machine::AArch64, 0x70000008) => "AARCH64_MEMTAG_GLOBALS_DYNAMIC",
+        (Machine::X86_64, 0x70000001) => "X86_64_UNWIND",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000000) => "MIPS_LIBLIST",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000001) => "MIPS_MSYM",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000002) => "MIPS_CONFLICT",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000003) => "MIPS_GPTAB",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000004) => "MIPS_UCODE",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000005) => "MIPS_DEBUG",
+        (Machine::Mips | Machine::MipsRs3Le, 0x70000006) => "MIPS_REGINFO",
+        (Machine::Mips | Machine::MipsRs3Le, 0x7000000D) => "MIPS_OPTIONS",
+        (Machine::Mips | Machine::MipsRs3Le, 0x7000001E) => "MIPS_DWARF",
+        (Machine::Mips | Machine::MipsRs3Le, 0x7000002A) => "MIPS_ABIFLAGS",
+        (Machine::Mips | Machine::MipsRs3Le, 0x7000002B) => "MIPS_XHASH",
+        (Machine::RiscV, 0x70000003) => "RISCV_ATTRIBUTES",
+        (Machine::Parisc, 0x70000000) => "PARISC_EXT",
+        (Machine::Parisc, 0x70000001) => "PARISC_UNWIND",
+        (Machine::Parisc, 0x70000002) => "PARISC_DOC",
+        (Machine::Ia64, 0x70000000) => "IA_64_EXT",
+        (Machine::Ia64, 0x70000001) => "IA_64_UNWIND",
+        (Machine::Msp430, 0x70000003) => "MSP430_ATTRIBUTES",
+        (Machine::Hexagon, 0x70000000) => "HEX_ORDERED",
+        (Machine::Csky, 0x70000001) => "CSKY_ATTRIBUTES",
+        _ => return None,
+    };
+    Some(name)
+}
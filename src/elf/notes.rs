@@ -0,0 +1,119 @@
+//! ELF notes (`SHT_NOTE` sections, or `PT_NOTE` segments in files without
+//! section headers).
+
+use super::types::{SectionType, SegmentType};
+use super::{ElfFile, field_u32};
+
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+pub const NT_FDO_PACKAGING_METADATA: u32 = 0xCAFE_1A7E;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Note {
+    /// Section or segment the note was read from.
+    pub source: String,
+    pub owner: String,
+    pub n_type: u32,
+    pub desc: Vec<u8>,
+}
+
+impl Note {
+    pub fn type_name(&self) -> Option<&'static str> {
+        match (self.owner.as_str(), self.n_type) {
+            ("GNU", NT_GNU_ABI_TAG) => Some("NT_GNU_ABI_TAG"),
+            ("GNU", NT_GNU_HWCAP) => Some("NT_GNU_HWCAP"),
+            ("GNU", NT_GNU_BUILD_ID) => Some("NT_GNU_BUILD_ID"),
+            ("GNU", NT_GNU_GOLD_VERSION) => Some("NT_GNU_GOLD_VERSION"),
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => Some("NT_GNU_PROPERTY_TYPE_0"),
+            ("Go", 4) => Some("NT_GO_BUILD_ID"),
+            ("FDO", NT_FDO_PACKAGING_METADATA) => Some("NT_FDO_PACKAGING_METADATA"),
+            ("stapsdt", 3) => Some("NT_STAPSDT"),
+            _ => None,
+        }
+    }
+
+    pub fn is_build_id(&self) -> bool {
+        self.owner == "GNU" && self.n_type == NT_GNU_BUILD_ID
+    }
+}
+
+/// Parses the notes in `data`, whose name and descriptor fields are padded
+/// to `align` (4, or 8 for some 64-bit notes).
+pub fn parse_notes(source: &str, data: &[u8], align: u64, big_endian: bool) -> Vec<Note> {
+    let align = if align == 8 { 8 } else { 4 };
+    let mut notes = Vec::new();
+    let mut position = 0usize;
+    while let (Some(namesz), Some(descsz), Some(n_type)) = (
+        field_u32(data, position, big_endian),
+        field_u32(data, position + 4, big_endian),
+        field_u32(data, position + 8, big_endian),
+    ) {
+        let name_start = position + 12;
+        let desc_start = (name_start + namesz as usize).next_multiple_of(align);
+        let desc_end = desc_start + descsz as usize;
+        let (Some(name), Some(desc)) = (
+            data.get(name_start..name_start + namesz as usize),
+            data.get(desc_start..desc_end),
+        ) else {
+            break;
+        };
+        let owner = name.split(|&b| b == 0).next().unwrap_or_default();
+        notes.push(Note {
+            source: source.to_string(),
+            owner: String::from_utf8_lossy(owner).into_owned(),
+            n_type,
+            desc: desc.to_vec(),
+        });
+        position = desc_end.next_multiple_of(align);
+    }
+    notes
+}
+
+impl ElfFile {
+    /// Returns the notes of all `SHT_NOTE` sections, or of the `PT_NOTE`
+    /// segments if there are no section headers.
+    pub fn get_notes(&self) -> Vec<Note> {
+        let be = self.is_big_endian();
+        let mut notes = Vec::new();
+        for (index, sh) in self.section_headers.iter().enumerate() {
+            if sh.get_type() != SectionType::Note {
+                continue;
+            }
+            if let Some(data) = self.get_section_data(index) {
+                let name = self.get_section_name(index).unwrap_or_default();
+                notes.extend(parse_notes(&name, data, sh.sh_addralign(), be));
+            }
+        }
+        if self.section_headers.is_empty() {
+            for (index, ph) in self.program_headers.iter().enumerate() {
+                if ph.get_type() != SegmentType::Note {
+                    continue;
+                }
+                let start = ph.p_offset() as usize;
+                if let Some(data) = start
+                    .checked_add(ph.p_filesz() as usize)
+                    .and_then(|end| self.data.get(start..end))
+                {
+                    notes.extend(parse_notes(
+                        &format!("PT_NOTE#{}", index),
+                        data,
+                        ph.p_align(),
+                        be,
+                    ));
+                }
+            }
+        }
+        notes
+    }
+
+    /// Returns the GNU build ID.
+    pub fn get_build_id(&self) -> Option<Vec<u8>> {
+        self.get_notes()
+            .into_iter()
+            .find(Note::is_build_id)
+            .map(|note| note.desc)
+    }
+}
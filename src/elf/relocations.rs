@@ -0,0 +1,78 @@
+//! Relocation sections (`SHT_REL` and `SHT_RELA`).
+
+use std::io::Cursor;
+
+use super::types::SectionType;
+use super::{ElfFile, Result, read_u32, read_u64, read_word};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relocation {
+    /// Offset of the relocated field in its section (relocatable files) or
+    /// its virtual address (linked files).
+    pub r_offset: u64,
+    /// Index of the referenced symbol in the associated symbol table.
+    pub symbol: u32,
+    pub r_type: u32,
+    /// Explicit addend of `SHT_RELA` entries. `SHT_REL` addends are stored
+    /// in the relocated field.
+    pub addend: Option<i64>,
+}
+
+impl ElfFile {
+    /// Reads the entries of the `SHT_REL` or `SHT_RELA` section `index`.
+    pub fn read_relocations(&self, index: usize) -> Result<Vec<Relocation>> {
+        let sh = match self.section_headers.get(index) {
+            Some(sh) => sh,
+            None => return Ok(Vec::new()),
+        };
+        let is_rela = match sh.get_type() {
+            SectionType::Rela => true,
+            SectionType::Rel => false,
+            _ => return Ok(Vec::new()),
+        };
+        let is_64 = self.is_64();
+        let be = self.is_big_endian();
+        let word = if is_64 { 8 } else { 4 };
+        let entsize = if is_rela { word * 3 } else { word * 2 };
+
+        let data = self.get_section_data(index).unwrap_or(&[]);
+        let mut relocations = Vec::with_capacity(data.len() / entsize);
+        for entry in data.chunks_exact(entsize) {
+            let mut reader = Cursor::new(entry);
+            let r_offset = read_word(&mut reader, is_64, be)?;
+            let (symbol, r_type) = if is_64 {
+                let info = read_u64(&mut reader, be)?;
+                ((info >> 32) as u32, info as u32)
+            } else {
+                let info = read_u32(&mut reader, be)?;
+                (info >> 8, info & 0xFF)
+            };
+            let addend = if is_rela {
+                Some(read_word(&mut reader, is_64, be)? as i64)
+            } else {
+                None
+            };
+            relocations.push(Relocation {
+                r_offset,
+                symbol,
+                r_type,
+                addend: addend.map(|a| if is_64 { a } else { a as i32 as i64 }),
+            });
+        }
+        Ok(relocations)
+    }
+
+    /// Returns the indices of the relocation sections that apply to section
+    /// `target`.
+    pub fn relocation_sections_for(&self, target: usize) -> Vec<usize> {
+        self.section_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, sh)| {
+                matches!(sh.get_type(), SectionType::Rel | SectionType::Rela)
+                    && sh.sh_info() as usize == target
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
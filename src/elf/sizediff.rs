@@ -0,0 +1,197 @@
+//! Size differences between two builds of a binary, by allocated section
+//! and by symbol.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::demangle;
+use super::header::SHF_ALLOC;
+use super::{ElfFile, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeChangeKind {
+    Added,
+    Removed,
+    Grown,
+    Shrunk,
+    Unchanged,
+}
+
+impl SizeChangeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SizeChangeKind::Added => "added",
+            SizeChangeKind::Removed => "removed",
+            SizeChangeKind::Grown => "grown",
+            SizeChangeKind::Shrunk => "shrunk",
+            SizeChangeKind::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// The size of a section or symbol in both builds; `None` where it does
+/// not exist.
+#[derive(Debug, Clone)]
+pub struct SizeChange {
+    pub name: String,
+    pub old: Option<u64>,
+    pub new: Option<u64>,
+}
+
+impl SizeChange {
+    pub fn delta(&self) -> i64 {
+        size_delta(self.old.unwrap_or(0), self.new.unwrap_or(0))
+    }
+
+    pub fn kind(&self) -> SizeChangeKind {
+        match (self.old, self.new) {
+            (None, _) => SizeChangeKind::Added,
+            (_, None) => SizeChangeKind::Removed,
+            (Some(old), Some(new)) if new > old => SizeChangeKind::Grown,
+            (Some(old), Some(new)) if new < old => SizeChangeKind::Shrunk,
+            _ => SizeChangeKind::Unchanged,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SizeDiff {
+    /// All allocated sections of either build, in the order of the new one.
+    pub sections: Vec<SizeChange>,
+    /// Changed symbols, largest change first.
+    pub symbols: Vec<SizeChange>,
+    /// Total size of the allocated sections.
+    pub old_total: u64,
+    pub new_total: u64,
+}
+
+impl SizeDiff {
+    pub fn total_delta(&self) -> i64 {
+        size_delta(self.old_total, self.new_total)
+    }
+
+    pub fn count(&self, kind: SizeChangeKind) -> usize {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.kind() == kind)
+            .count()
+    }
+}
+
+/// Returns `new - old`, saturated to the range of `i64`.
+fn size_delta(old: u64, new: u64) -> i64 {
+    let delta = i128::from(new) - i128::from(old);
+    delta.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
+impl ElfFile {
+    /// Returns the names and sizes of the allocated sections.
+    fn allocated_sections(&self) -> Vec<(String, u64)> {
+        (0..self.section_headers.len())
+            .filter(|&index| self.section_headers[index].sh_flags() & SHF_ALLOC != 0)
+            .map(|index| {
+                let name = self.get_section_name(index).unwrap_or_default();
+                (name, self.section_headers[index].sh_size())
+            })
+            .collect()
+    }
+
+    /// Returns the symbol names and sizes keyed by mangled name, without the
+    /// legacy Rust hash so that a rebuilt function is not seen as a removed
+    /// and an added one. Symbols with the same key are summed. Names are
+    /// demangled unless that makes two keys (C++ overloads) look the same.
+    fn symbol_sizes(&self) -> Result<BTreeMap<String, (String, u64)>> {
+        let mut sizes: BTreeMap<String, (String, u64)> = BTreeMap::new();
+        let report = match self.bloat_report() {
+            Ok(report) => report,
+            // stripped
+            Err(_) if self.get_symbols()?.is_empty() && self.get_dynamic_symbols()?.is_empty() => {
+                return Ok(sizes);
+            }
+            Err(e) => return Err(e),
+        };
+        for symbol in &report.symbols {
+            let key = demangle::strip_rust_hash(&symbol.name).to_string();
+            let entry = sizes
+                .entry(key)
+                .or_insert_with(|| (symbol.display_name().to_string(), 0));
+            entry.1 += symbol.size;
+        }
+        let mut keys_per_name: HashMap<String, usize> = HashMap::new();
+        for (name, _) in sizes.values() {
+            *keys_per_name.entry(name.clone()).or_default() += 1;
+        }
+        for (key, (name, _)) in sizes.iter_mut() {
+            if keys_per_name[name.as_str()] > 1 {
+                *name = key.clone();
+            }
+        }
+        Ok(sizes)
+    }
+
+    /// Compares the section and symbol sizes of `self` (the old build) with
+    /// `new`.
+    pub fn diff_sizes(&self, new: &ElfFile) -> Result<SizeDiff> {
+        let old_sections = self.allocated_sections();
+        let new_sections = new.allocated_sections();
+        let mut sections: Vec<SizeChange> = new_sections
+            .iter()
+            .map(|(name, size)| SizeChange {
+                name: name.clone(),
+                old: old_sections
+                    .iter()
+                    .find(|(old, _)| old == name)
+                    .map(|&(_, size)| size),
+                new: Some(*size),
+            })
+            .collect();
+        for (name, size) in &old_sections {
+            if !new_sections.iter().any(|(new, _)| new == name) {
+                sections.push(SizeChange {
+                    name: name.clone(),
+                    old: Some(*size),
+                    new: None,
+                });
+            }
+        }
+
+        let old_symbols = self.symbol_sizes()?;
+        let new_symbols = new.symbol_sizes()?;
+        let mut symbols: Vec<SizeChange> = Vec::new();
+        for (key, (name, size)) in &new_symbols {
+            let old = old_symbols.get(key).map(|&(_, size)| size);
+            if old != Some(*size) {
+                symbols.push(SizeChange {
+                    name: name.clone(),
+                    old,
+                    new: Some(*size),
+                });
+            }
+        }
+        for (key, (name, size)) in &old_symbols {
+            if !new_symbols.contains_key(key) {
+                symbols.push(SizeChange {
+                    name: name.clone(),
+                    old: Some(*size),
+                    new: None,
+                });
+            }
+        }
+        symbols.sort_by(|a, b| {
+            b.delta()
+                .unsigned_abs()
+                .cmp(&a.delta().unsigned_abs())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Ok(SizeDiff {
+            sections,
+            symbols,
+            old_total: old_sections
+                .iter()
+                .fold(0, |total, &(_, size)| total.saturating_add(size)),
+            new_total: new_sections
+                .iter()
+                .fold(0, |total, &(_, size)| total.saturating_add(size)),
+        })
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use super::compress::Compression;
+use super::error::ElfError;
+use super::types::SectionType;
+use super::{ElfFile, Result, read_cstr};
+
+/// ELF parser over any `Read + Seek` source.
+///
+/// Only the file header and the program/section header tables are read up
+/// front. Section contents are fetched on demand, so large files never have
+/// to be buffered in memory.
+pub struct ElfReader<R: Read + Seek> {
+    reader: R,
+    len: u64,
+    elf: ElfFile,
+    shstrtab: Option<Vec<u8>>,
+}
+
+impl<R: Read + Seek> ElfReader<R> {
+    pub fn new(mut reader: R) -> Result<Self> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        let elf = ElfFile::parse_headers(&mut reader, len)?;
+        Ok(Self {
+            reader,
+            len,
+            elf,
+            shstrtab: None,
+        })
+    }
+
+    /// The parsed file header and header tables.
+    ///
+    /// `data` is always empty here; use `read_section` or `read_range` to
+    /// fetch file contents.
+    pub fn elf(&self) -> &ElfFile {
+        &self.elf
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads `size` bytes starting at file offset `offset`.
+    pub fn read_range(&mut self, offset: u64, size: u64) -> Result<Vec<u8>> {
+        if offset.checked_add(size).is_none_or(|end| end > self.len) {
+            return Err(ElfError::ParseError(
+                "requested range exceeds file range.".to_string(),
+            ));
+        }
+
+        let mut buf = vec![0u8; size as usize];
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reads the contents of section `index`, decompressing `SHF_COMPRESSED`
+    /// and `.zdebug_*` sections. `SHT_NOBITS` sections yield an empty buffer.
+    pub fn read_section(&mut self, index: usize) -> Result<Vec<u8>> {
+        let data = self.read_raw_section(index)?;
+        let name = self.get_section_name(index)?.unwrap_or_default();
+        let compression = Compression::from_section(
+            &self.elf.section_headers[index],
+            &name,
+            &data,
+            self.elf.is_64(),
+            self.elf.is_big_endian(),
+        )?;
+        match compression {
+            Some(compression) => compression.decompress(&data),
+            None => Ok(data),
+        }
+    }
+
+    /// Reads the file contents of section `index` as stored, without
+    /// decompression.
+    pub fn read_raw_section(&mut self, index: usize) -> Result<Vec<u8>> {
+        let sh = self
+            .elf
+            .section_headers
+            .get(index)
+            .ok_or_else(|| ElfError::ParseError(format!("no section with index {}", index)))?;
+        if sh.get_type() == SectionType::Nobits {
+            return Ok(Vec::new());
+        }
+
+        let (offset, size) = (sh.sh_offset(), sh.sh_size());
+        self.read_range(offset, size)
+    }
+
+    pub fn get_section_name(&mut self, index: usize) -> Result<Option<String>> {
+        let shstrndx = self.elf.e_shstrndx as usize;
+        if index >= self.elf.section_headers.len() || shstrndx >= self.elf.section_headers.len() {
+            return Ok(None);
+        }
+
+        if self.shstrtab.is_none() {
+            self.shstrtab = Some(self.read_raw_section(shstrndx)?);
+        }
+
+        let name_offset = self.elf.section_headers[index].sh_name() as usize;
+        Ok(self
+            .shstrtab
+            .as_deref()
+            .and_then(|table| read_cstr(table, name_offset)))
+    }
+
+    /// Finds the index of the first section called `name`.
+    pub fn find_section(&mut self, name: &str) -> Result<Option<usize>> {
+        for i in 0..self.elf.section_headers.len() {
+            if self.get_section_name(i)?.as_deref() == Some(name) {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,221 @@
+//! Removal of symbols, debug information and other sections that are not
+//! loaded (`SHF_ALLOC`), for executables and shared objects.
+//!
+//! The loaded segments are left untouched; the remaining sections, the
+//! section header table and `.shstrtab` are packed after them. The removed
+//! sections can be kept in a separate debug file, which the stripped file
+//! then names in `.gnu_debuglink`.
+
+use std::io::Cursor;
+
+use super::error::ElfError;
+use super::symbols::SHN_UNDEF;
+use super::types::{ElfType, SectionFlags, SectionType};
+use super::writer::ElfWriter;
+use super::{ElfFile, Result, read_u16, write_u16, write_u32};
+
+/// Non-allocated sections kept unless named in the remove list.
+const DEFAULT_KEEP: &[&str] = &[
+    ".gnu_debuglink",
+    ".gnu_debugaltlink",
+    ".ARM.attributes",
+    ".riscv.attributes",
+    ".gnu.attributes",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct StripOptions {
+    /// Section names (`*` matches any run of characters) to keep.
+    pub keep: Vec<String>,
+    /// Section names to remove even if they are kept by default.
+    pub remove: Vec<String>,
+    /// Name of the separate debug file. When set, the removed sections are
+    /// written to it and the stripped file gets a `.gnu_debuglink`.
+    pub debuglink: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StripResult {
+    pub stripped: Vec<u8>,
+    pub debug: Option<Vec<u8>>,
+    /// Indices of the removed sections in the original file.
+    pub removed: Vec<usize>,
+}
+
+/// Matches `name` against `pattern`, where `*` matches any run of
+/// characters.
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=name.len())
+                .filter(|&i| name.is_char_boundary(i))
+                .any(|i| matches_pattern(rest, &name[i..]))
+        }
+    }
+}
+
+/// CRC-32 (IEEE 802.3) as used by `.gnu_debuglink`.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+impl ElfFile {
+    /// Decides which sections to remove: every non-allocated section except
+    /// the section name table and `DEFAULT_KEEP`, adjusted by the keep and
+    /// remove lists. Sections linked from a kept section are kept too.
+    fn sections_to_strip(&self, options: &StripOptions) -> Result<Vec<bool>> {
+        let any =
+            |patterns: &[String], name: &str| patterns.iter().any(|p| matches_pattern(p, name));
+        let shstrndx = self.e_shstrndx as usize;
+        let mut remove = Vec::with_capacity(self.section_headers.len());
+        for (index, sh) in self.section_headers.iter().enumerate() {
+            let name = self.get_section_name(index).unwrap_or_default();
+            let alloc = sh.get_flags().contains(SectionFlags::ALLOC);
+            let removed = if index == 0 || index == shstrndx || any(&options.keep, &name) {
+                false
+            } else if any(&options.remove, &name) {
+                if alloc {
+                    return Err(ElfError::ParseError(format!(
+                        "{} is loaded; removing it would change the segments.",
+                        name
+                    )));
+                }
+                true
+            } else {
+                !alloc && !DEFAULT_KEEP.contains(&name.as_str())
+            };
+            remove.push(removed);
+        }
+
+        loop {
+            let mut changed = false;
+            for index in 0..remove.len() {
+                let link = self.section_headers[index].sh_link() as usize;
+                if !remove[index] && link < remove.len() && remove[link] {
+                    remove[link] = false;
+                    changed = true;
+                }
+            }
+            if !changed {
+                return Ok(remove);
+            }
+        }
+    }
+
+    /// Strips the file as described by `options`.
+    pub fn strip(&self, options: &StripOptions) -> Result<StripResult> {
+        if self.get_type() == ElfType::Relocatable {
+            return Err(ElfError::ParseError(
+                "relocatable files are not supported; only executables and shared objects can be stripped."
+                    .to_string(),
+            ));
+        }
+        if self.section_headers.is_empty() {
+            return Err(ElfError::ParseError(
+                "the file has no section headers.".to_string(),
+            ));
+        }
+        let remove = self.sections_to_strip(options)?;
+        let removed: Vec<usize> = (0..remove.len()).filter(|&i| remove[i]).collect();
+
+        // the debug file keeps every section header, with the contents of
+        // the loaded sections (but notes, for the build ID) left out
+        let debug = match &options.debuglink {
+            Some(_) => {
+                let mut writer = ElfWriter::new(self);
+                writer.program_headers.clear();
+                for (index, section) in writer.sections.iter_mut().enumerate() {
+                    let sh_type = section.header.get_type();
+                    if !remove[index]
+                        && section.header.get_flags().contains(SectionFlags::ALLOC)
+                        && !matches!(sh_type, SectionType::Nobits | SectionType::Note)
+                    {
+                        section.header.set_sh_type(SectionType::Nobits.into());
+                        section.data.clear();
+                    }
+                }
+                writer.discard_unmapped()?;
+                Some(writer.write()?)
+            }
+            None => None,
+        };
+
+        let mut writer = ElfWriter::new(self);
+        self.renumber_symbol_sections(&mut writer, &remove)?;
+        for &index in removed.iter().rev() {
+            writer.remove_section(index);
+        }
+        if let (Some(name), Some(debug)) = (&options.debuglink, &debug) {
+            let mut data = name.as_bytes().to_vec();
+            data.push(0);
+            data.resize(data.len().next_multiple_of(4), 0);
+            let mut crc = Vec::new();
+            write_u32(&mut crc, crc32(debug), self.is_big_endian())?;
+            data.extend_from_slice(&crc);
+            match writer.find_section(".gnu_debuglink") {
+                Some(index) => writer.sections[index].data = data,
+                None => {
+                    let mut header = writer.new_section_header(SectionType::Progbits);
+                    header.set_sh_addralign(4);
+                    writer.add_section(".gnu_debuglink", header, data);
+                }
+            }
+        }
+        writer.discard_unmapped()?;
+
+        Ok(StripResult {
+            stripped: writer.write()?,
+            debug,
+            removed,
+        })
+    }
+
+    /// Rewrites `st_shndx` in the kept symbol tables for the section
+    /// numbers after removal. Symbols of removed sections become undefined.
+    fn renumber_symbol_sections(&self, writer: &mut ElfWriter, remove: &[bool]) -> Result<()> {
+        let mut mapping = Vec::with_capacity(remove.len());
+        let mut next = 0u16;
+        for &removed in remove {
+            mapping.push(if removed { SHN_UNDEF } else { next });
+            if !removed {
+                next += 1;
+            }
+        }
+        if mapping.iter().enumerate().all(|(i, &m)| m as usize == i) {
+            return Ok(());
+        }
+
+        let be = self.is_big_endian();
+        let (entsize, shndx_at) = if self.is_64() { (24, 6) } else { (16, 14) };
+        for (index, section) in writer.sections.iter_mut().enumerate() {
+            if remove[index]
+                || !matches!(
+                    section.header.get_type(),
+                    SectionType::Symtab | SectionType::Dynsym
+                )
+            {
+                continue;
+            }
+            for entry in section.data.chunks_exact_mut(entsize) {
+                let field = &mut entry[shndx_at..shndx_at + 2];
+                let shndx = read_u16(&mut Cursor::new(&*field), be)?;
+                // SHN_UNDEF and the reserved indices stay as they are
+                if shndx != SHN_UNDEF && (shndx as usize) < mapping.len().min(0xFF00) {
+                    write_u16(&mut Cursor::new(field), mapping[shndx as usize], be)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+//! Symbol tables (`SHT_SYMTAB` and `SHT_DYNSYM`).
+
+use std::io::Cursor;
+
+use byteorder::ReadBytesExt;
+
+use super::error::ElfError;
+use super::types::{Machine, SectionType, SymbolBinding, SymbolType, SymbolVisibility};
+use super::{ElfFile, Result, read_cstr, read_u16, read_u32, read_u64};
+
+pub const SHN_UNDEF: u16 = 0;
+pub const SHN_ABS: u16 = 0xFFF1;
+pub const SHN_COMMON: u16 = 0xFFF2;
+pub const SHN_XINDEX: u16 = 0xFFFF;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub st_value: u64,
+    pub st_size: u64,
+    pub st_info: u8,
+    pub st_other: u8,
+    pub st_shndx: u16,
+}
+
+impl Symbol {
+    pub fn get_type(&self) -> SymbolType {
+        SymbolType::from(self.st_info & 0x0F)
+    }
+
+    pub fn get_binding(&self) -> SymbolBinding {
+        SymbolBinding::from(self.st_info >> 4)
+    }
+
+    pub fn get_visibility(&self) -> SymbolVisibility {
+        SymbolVisibility::from(self.st_other & 0x03)
+    }
+
+    pub fn is_undefined(&self) -> bool {
+        self.st_shndx == SHN_UNDEF
+    }
+
+    /// Returns the section the symbol is defined in, if it is defined
+    /// relative to one.
+    pub fn section_index(&self) -> Option<usize> {
+        match self.st_shndx {
+            SHN_UNDEF => None,
+            // reserved indices (ABS, COMMON, XINDEX, ...)
+            ndx if ndx >= 0xFF00 => None,
+            ndx => Some(ndx as usize),
+        }
+    }
+
+    /// Returns the address of the symbol. The Thumb bit of ARM function
+    /// symbols is cleared.
+    pub fn address(&self, machine: Machine) -> u64 {
+        if machine == Machine::Arm && self.get_type() == SymbolType::Func {
+            self.st_value & !1
+        } else {
+            self.st_value
+        }
+    }
+
+    /// Whether the symbol is a function or a GNU indirect function.
+    pub fn is_function(&self) -> bool {
+        matches!(self.get_type(), SymbolType::Func | SymbolType::GnuIfunc)
+    }
+}
+
+impl ElfFile {
+    /// Reads the symbols of the symbol table in section `index`, including
+    /// the null symbol at index 0.
+    pub fn read_symbols(&self, index: usize) -> Result<Vec<Symbol>> {
+        let sh = self.section_headers.get(index).ok_or_else(|| {
+            ElfError::ParseError(format!("symbol table section {} does not exist.", index))
+        })?;
+        let data = self.get_section_data(index).unwrap_or(&[]);
+        let strtab = self.get_section_data(sh.sh_link() as usize).unwrap_or(&[]);
+        let is_64 = self.is_64();
+        let be = self.is_big_endian();
+        let entsize = if is_64 { 24 } else { 16 };
+
+        let mut symbols = Vec::with_capacity(data.len() / entsize);
+        for entry in data.chunks_exact(entsize) {
+            let mut reader = Cursor::new(entry);
+            let st_name = read_u32(&mut reader, be)?;
+            let (st_value, st_size, st_info, st_other, st_shndx) = if is_64 {
+                let st_info = reader.read_u8()?;
+                let st_other = reader.read_u8()?;
+                let st_shndx = read_u16(&mut reader, be)?;
+                let st_value = read_u64(&mut reader, be)?;
+                let st_size = read_u64(&mut reader, be)?;
+                (st_value, st_size, st_info, st_other, st_shndx)
+            } else {
+                let st_value = read_u32(&mut reader, be)? as u64;
+                let st_size = read_u32(&mut reader, be)? as u64;
+                let st_info = reader.read_u8()?;
+                let st_other = reader.read_u8()?;
+                let st_shndx = read_u16(&mut reader, be)?;
+                (st_value, st_size, st_info, st_other, st_shndx)
+            };
+            symbols.push(Symbol {
+                name: read_cstr(strtab, st_name as usize).unwrap_or_default(),
+                st_value,
+                st_size,
+                st_info,
+                st_other,
+                st_shndx,
+            });
+        }
+        Ok(symbols)
+    }
+
+    fn find_section_by_type(&self, sh_type: SectionType) -> Option<usize> {
+        self.section_headers
+            .iter()
+            .position(|sh| sh.get_type() == sh_type)
+    }
+
+    /// Returns the symbols of `.symtab`, or an empty list if the file has
+    /// been stripped.
+    pub fn get_symbols(&self) -> Result<Vec<Symbol>> {
+        match self.find_section_by_type(SectionType::Symtab) {
+            Some(index) => self.read_symbols(index),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the symbols of `.dynsym`, or an empty list if there is none.
+    pub fn get_dynamic_symbols(&self) -> Result<Vec<Symbol>> {
+        match self.find_section_by_type(SectionType::Dynsym) {
+            Some(index) => self.read_symbols(index),
+            None => Ok(Vec::new()),
+        }
+    }
+}
@@ -0,0 +1,481 @@
+use std::fmt;
+
+/// Declares a C-like ELF constant enum backed by a raw integer.
+///
+/// Every enum gets `From<raw>`, `From<enum> for raw` and `Display`. Values
+/// inside one of the reserved `ranges` keep their raw value in the matching
+/// range variant, and any other unrecognised value is kept in `Unknown`.
+macro_rules! elf_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident: $raw:ty {
+            $($variant:ident = $value:literal => $display:literal,)*
+        }
+        ranges {
+            $($range_variant:ident = $lo:literal..=$hi:literal => $range_display:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            $($range_variant($raw),)*
+            Unknown($raw),
+        }
+
+        impl From<$raw> for $name {
+            fn from(value: $raw) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    $($lo..=$hi => $name::$range_variant(value),)*
+                    _ => $name::Unknown(value),
+                }
+            }
+        }
+
+        impl From<$name> for $raw {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $value,)*
+                    $($name::$range_variant(v) => v,)*
+                    $name::Unknown(v) => v,
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $($name::$variant => f.write_str($display),)*
+                    $($name::$range_variant(v) => write!(f, "{} (0x{:X})", $range_display, v),)*
+                    $name::Unknown(v) => write!(f, "Unknown (0x{:X})", v),
+                }
+            }
+        }
+    };
+}
+
+elf_enum! {
+    /// Object file type (`e_type`).
+    pub enum ElfType: u16 {
+        None = 0 => "None",
+        Relocatable = 1 => "Relocatable",
+        Executable = 2 => "Executable",
+        Shared = 3 => "Shared",
+        Core = 4 => "Core",
+    }
+    ranges {
+        OsSpecific = 0xFE00..=0xFEFF => "OS-specific",
+        ProcessorSpecific = 0xFF00..=0xFFFF => "Processor-specific",
+    }
+}
+
+elf_enum! {
+    /// Target instruction set architecture (`e_machine`).
+    pub enum Machine: u16 {
+        None = 0 => "None",
+        M32 = 1 => "M32",
+        Sparc = 2 => "SPARC",
+        I386 = 3 => "Intel 80386",
+        M68k = 4 => "Motorola 68K",
+        M88k = 5 => "Motorola 88K",
+        IntelMcu = 6 => "Intel MCU",
+        I860 = 7 => "Intel 80860",
+        Mips = 8 => "MIPS",
+        S370 = 9 => "S370",
+        MipsRs3Le = 10 => "MIPS RS3 LE",
+        Parisc = 15 => "PA-RISC",
+        Vpp500 = 17 => "VPP500",
+        Sparc32Plus = 18 => "SPARC32 Plus",
+        I960 = 19 => "Intel 80960",
+        PowerPc = 20 => "PowerPC",
+        PowerPc64 = 21 => "PowerPC64",
+        S390 = 22 => "IBM S/390",
+        Spu = 23 => "IBM SPU",
+        Arm = 40 => "ARM",
+        SuperH = 42 => "SuperH",
+        SparcV9 = 43 => "SparcV9",
+        Tricore = 44 => "Tricore",
+        Arc = 45 => "ARC",
+        H8300 = 46 => "H8/300",
+        H8300H = 47 => "H8/300H",
+        H8S = 48 => "H8S",
+        H8500 = 49 => "H8/500",
+        Ia64 = 50 => "IA-64",
+        MipsX = 51 => "MIPS-X",
+        Coldfire = 52 => "Coldfire",
+        M68hc12 = 53 => "M68HC12",
+        Mma = 54 => "MMA",
+        Pcp = 55 => "PCP",
+        Ncpu = 56 => "Sony nCPU",
+        Ndr1 = 57 => "Denso NDR1",
+        StarCore = 58 => "Start*Core",
+        Me16 = 59 => "ME16",
+        St100 = 60 => "ST100",
+        Tinyj = 61 => "Tinyj",
+        X86_64 = 62 => "x86-64",
+        Pdsp = 63 => "PDSP",
+        Pdp10 = 64 => "PDP-10",
+        Pdp11 = 65 => "PDP-11",
+        Fx66 = 66 => "FX66",
+        St9Plus = 67 => "ST9+",
+        St7 = 68 => "ST7",
+        Mc68hc16 = 69 => "MC68HC16",
+        Mc68hc11 = 70 => "MC68HC11",
+        Mc68hc08 = 71 => "MC68HC08",
+        Mc68hc05 = 72 => "MC68HC05",
+        Svx = 73 => "SVx",
+        St19 = 74 => "ST19",
+        Vax = 75 => "VAX",
+        Cris = 76 => "CRIS",
+        Avr = 83 => "Atmel AVR",
+        Xtensa = 94 => "Xtensa",
+        Msp430 = 105 => "MSP430",
+        Hexagon = 164 => "Hexagon",
+        AArch64 = 183 => "AArch64",
+        AmdGpu = 224 => "AMD GPU",
+        RiscV = 243 => "RISC-V",
+        Bpf = 247 => "BPF",
+        Csky = 252 => "C-SKY",
+        LoongArch = 258 => "LoongArch",
+    }
+    ranges {}
+}
+
+elf_enum! {
+    /// Operating system / ABI identification (`e_ident[EI_OSABI]`).
+    pub enum OsAbi: u8 {
+        SystemV = 0 => "System V",
+        HpUx = 1 => "HP-UX",
+        NetBsd = 2 => "NetBSD",
+        Gnu = 3 => "GNU/Linux",
+        Solaris = 6 => "Solaris",
+        Aix = 7 => "AIX",
+        Irix = 8 => "IRIX",
+        FreeBsd = 9 => "FreeBSD",
+        Tru64 = 10 => "Tru64",
+        Modesto = 11 => "Novell Modesto",
+        OpenBsd = 12 => "OpenBSD",
+        OpenVms = 13 => "OpenVMS",
+        Nsk = 14 => "NonStop Kernel",
+        Aros = 15 => "AROS",
+        FenixOs = 16 => "Fenix OS",
+        CloudAbi = 17 => "CloudABI",
+        OpenVos = 18 => "Stratus Technologies OpenVOS",
+        ArmAeabi = 64 => "ARM EABI",
+        Arm = 97 => "ARM",
+        Standalone = 255 => "Standalone",
+    }
+    ranges {}
+}
+
+elf_enum! {
+    /// Program header segment type (`p_type`).
+    pub enum SegmentType: u32 {
+        Null = 0 => "NULL",
+        Load = 1 => "LOAD",
+        Dynamic = 2 => "DYNAMIC",
+        Interp = 3 => "INTERP",
+        Note = 4 => "NOTE",
+        Shlib = 5 => "SHLIB",
+        Phdr = 6 => "PHDR",
+        Tls = 7 => "TLS",
+        Num = 8 => "NUM",
+        SunwUnwind = 0x6464E550 => "SUNW_UNWIND",
+        GnuEhFrame = 0x6474E550 => "GNU_EH_FRAME",
+        GnuStack = 0x6474E551 => "GNU_STACK",
+        GnuRelro = 0x6474E552 => "GNU_RELRO",
+        GnuProperty = 0x6474E553 => "GNU_PROPERTY",
+        GnuSframe = 0x6474E554 => "GNU_SFRAME",
+        PaxFlags = 0x65041580 => "PAX_FLAGS",
+        OpenBsdMutable = 0x65A3DBE5 => "OPENBSD_MUTABLE",
+        OpenBsdRandomize = 0x65A3DBE6 => "OPENBSD_RANDOMIZE",
+        OpenBsdWxNeeded = 0x65A3DBE7 => "OPENBSD_WXNEEDED",
+        OpenBsdNoBtCfi = 0x65A3DBE8 => "OPENBSD_NOBTCFI",
+        OpenBsdSyscalls = 0x65A3DBE9 => "OPENBSD_SYSCALLS",
+        OpenBsdBootData = 0x65A41BE6 => "OPENBSD_BOOTDATA",
+        SunwBss = 0x6FFFFFFA => "SUNWBSS",
+        SunwStack = 0x6FFFFFFB => "SUNWSTACK",
+        SunwDtrace = 0x6FFFFFFC => "SUNWDTRACE",
+        SunwCap = 0x6FFFFFFD => "SUNWCAP",
+    }
+    ranges {
+        OsSpecific = 0x60000000..=0x6FFFFFFF => "OS spec",
+        ProcessorSpecific = 0x70000000..=0x7FFFFFFF => "Pr spec",
+    }
+}
+
+elf_enum! {
+    /// Section header type (`sh_type`).
+    pub enum SectionType: u32 {
+        Null = 0 => "NULL",
+        Progbits = 1 => "PROGBITS",
+        Symtab = 2 => "SYMTAB",
+        Strtab = 3 => "STRTAB",
+        Rela = 4 => "RELA",
+        Hash = 5 => "HASH",
+        Dynamic = 6 => "DYNAMIC",
+        Note = 7 => "NOTE",
+        Nobits = 8 => "NOBITS",
+        Rel = 9 => "REL",
+        Shlib = 10 => "SHLIB",
+        Dynsym = 11 => "DYNSYM",
+        InitArray = 14 => "INIT_ARRAY",
+        FiniArray = 15 => "FINI_ARRAY",
+        PreinitArray = 16 => "PREINIT_ARRAY",
+        Group = 17 => "GROUP",
+        SymtabShndx = 18 => "SYMTAB_SHNDX",
+        Relr = 19 => "RELR",
+        Num = 20 => "NUM",
+        AndroidRel = 0x60000001 => "ANDROID_REL",
+        AndroidRela = 0x60000002 => "ANDROID_RELA",
+        GnuIncrementalInputs = 0x6FFF4700 => "GNU_INCREMENTAL_INPUTS",
+        LlvmOdrtab = 0x6FFF4C00 => "LLVM_ODRTAB",
+        LlvmLinkerOptions = 0x6FFF4C01 => "LLVM_LINKER_OPTIONS",
+        LlvmAddrsig = 0x6FFF4C03 => "LLVM_ADDRSIG",
+        LlvmDependentLibraries = 0x6FFF4C04 => "LLVM_DEPENDENT_LIBRARIES",
+        LlvmSympart = 0x6FFF4C05 => "LLVM_SYMPART",
+        LlvmPartEhdr = 0x6FFF4C06 => "LLVM_PART_EHDR",
+        LlvmPartPhdr = 0x6FFF4C07 => "LLVM_PART_PHDR",
+        LlvmBbAddrMapV0 = 0x6FFF4C08 => "LLVM_BB_ADDR_MAP_V0",
+        LlvmCallGraphProfile = 0x6FFF4C09 => "LLVM_CALL_GRAPH_PROFILE",
+        LlvmBbAddrMap = 0x6FFF4C0A => "LLVM_BB_ADDR_MAP",
+        LlvmOffloading = 0x6FFF4C0B => "LLVM_OFFLOADING",
+        LlvmLto = 0x6FFF4C0C => "LLVM_LTO",
+        AndroidRelr = 0x6FFFFF00 => "ANDROID_RELR",
+        GnuSframe = 0x6FFFFFF4 => "GNU_SFRAME",
+        GnuAttributes = 0x6FFFFFF5 => "GNU_ATTRIBUTES",
+        GnuHash = 0x6FFFFFF6 => "GNU_HASH",
+        GnuLiblist = 0x6FFFFFF7 => "GNU_LIBLIST",
+        Checksum = 0x6FFFFFF8 => "CHECKSUM",
+        SunwMove = 0x6FFFFFFA => "SUNW_MOVE",
+        SunwComdat = 0x6FFFFFFB => "SUNW_COMDAT",
+        SunwSyminfo = 0x6FFFFFFC => "SUNW_SYMINFO",
+        GnuVerdef = 0x6FFFFFFD => "VERDEF",
+        GnuVerneed = 0x6FFFFFFE => "VERNEED",
+        GnuVersym = 0x6FFFFFFF => "VERSYM",
+    }
+    ranges {
+        OsSpecific = 0x60000000..=0x6FFFFFFF => "OS spec",
+        ProcessorSpecific = 0x70000000..=0x7FFFFFFF => "Pr spec",
+    }
+}
+
+elf_enum! {
+    /// Symbol type (`ELF_ST_TYPE(st_info)`).
+    pub enum SymbolType: u8 {
+        NoType = 0 => "NOTYPE",
+        Object = 1 => "OBJECT",
+        Func = 2 => "FUNC",
+        Section = 3 => "SECTION",
+        File = 4 => "FILE",
+        Common = 5 => "COMMON",
+        Tls = 6 => "TLS",
+        GnuIfunc = 10 => "IFUNC",
+    }
+    ranges {
+        OsSpecific = 10..=12 => "OS spec",
+        ProcessorSpecific = 13..=15 => "Pr spec",
+    }
+}
+
+elf_enum! {
+    /// Symbol binding (`ELF_ST_BIND(st_info)`).
+    pub enum SymbolBinding: u8 {
+        Local = 0 => "LOCAL",
+        Global = 1 => "GLOBAL",
+        Weak = 2 => "WEAK",
+        GnuUnique = 10 => "UNIQUE",
+    }
+    ranges {
+        OsSpecific = 10..=12 => "OS spec",
+        ProcessorSpecific = 13..=15 => "Pr spec",
+    }
+}
+
+elf_enum! {
+    /// Symbol visibility (`ELF_ST_VISIBILITY(st_other)`).
+    pub enum SymbolVisibility: u8 {
+        Default = 0 => "DEFAULT",
+        Internal = 1 => "INTERNAL",
+        Hidden = 2 => "HIDDEN",
+        Protected = 3 => "PROTECTED",
+    }
+    ranges {}
+}
+
+/// Segment permission bits (`p_flags`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SegmentFlags(pub u32);
+
+impl SegmentFlags {
+    pub const X: u32 = super::header::PF_X;
+    pub const W: u32 = super::header::PF_W;
+    pub const R: u32 = super::header::PF_R;
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn contains(&self, flag: u32) -> bool {
+        self.0 & flag == flag
+    }
+}
+
+impl From<u32> for SegmentFlags {
+    fn from(value: u32) -> Self {
+        SegmentFlags(value)
+    }
+}
+
+impl fmt::Display for SegmentFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.contains(Self::X) {
+            f.write_str("X")?;
+        }
+        if self.contains(Self::W) {
+            f.write_str("W")?;
+        }
+        if self.contains(Self::R) {
+            f.write_str("R")?;
+        }
+        Ok(())
+    }
+}
+
+/// Section attribute bits (`sh_flags`), widened to 64 bits for ELF32.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SectionFlags(pub u64);
+
+impl SectionFlags {
+    pub const WRITE: u64 = super::header::SHF_WRITE;
+    pub const ALLOC: u64 = super::header::SHF_ALLOC;
+    pub const EXEC: u64 = super::header::SHF_EXEC;
+    pub const MERGE: u64 = super::header::SHF_MERGE;
+    pub const STRINGS: u64 = super::header::SHF_STRINGS;
+    pub const INFO_LINK: u64 = super::header::SHF_INFO_LINK;
+    pub const LINK_ORDER: u64 = super::header::SHF_LINK_ORDER;
+    pub const OS_NONCONFORMING: u64 = super::header::SHF_OS_NONCONFORMING;
+    pub const GROUP: u64 = super::header::SHF_GROUP;
+    pub const TLS: u64 = super::header::SHF_TLS;
+    pub const COMPRESSED: u64 = super::header::SHF_COMPRESSED;
+    pub const MASKPROC: u64 = 0x0FF00000;
+
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub fn contains(&self, flag: u64) -> bool {
+        self.0 & flag == flag
+    }
+}
+
+impl From<u64> for SectionFlags {
+    fn from(value: u64) -> Self {
+        SectionFlags(value)
+    }
+}
+
+impl From<u32> for SectionFlags {
+    fn from(value: u32) -> Self {
+        SectionFlags(value as u64)
+    }
+}
+
+impl fmt::Display for SectionFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (Self::WRITE, 'W'),
+            (Self::ALLOC, 'A'),
+            (Self::EXEC, 'X'),
+            (Self::MERGE, 'M'),
+            (Self::STRINGS, 'S'),
+            (Self::INFO_LINK, 'I'),
+            (Self::LINK_ORDER, 'L'),
+            (Self::OS_NONCONFORMING, 'O'),
+            (Self::GROUP, 'G'),
+            (Self::TLS, 'T'),
+            (Self::COMPRESSED, 'C'),
+        ];
+
+        let mut flags = String::new();
+        let mut known_mask = Self::MASKPROC;
+        for (bit, c) in names {
+            known_mask |= bit;
+            if self.contains(bit) {
+                flags.push(c);
+            }
+        }
+
+        // check processor flag
+        if self.0 & Self::MASKPROC != 0 {
+            flags.push('p');
+        }
+
+        // check unknown
+        if self.0 & !known_mask != 0 {
+            flags.push('x');
+        }
+
+        if flags.is_empty() {
+            return f.write_str("-");
+        }
+        f.write_str(&flags)
+    }
+}
+
+elf_enum! {
+    /// Dynamic entry tag (`d_tag`).
+    pub enum DynamicTag: u64 {
+        Null = 0 => "NULL",
+        Needed = 1 => "NEEDED",
+        PltRelSz = 2 => "PLTRELSZ",
+        PltGot = 3 => "PLTGOT",
+        Hash = 4 => "HASH",
+        StrTab = 5 => "STRTAB",
+        SymTab = 6 => "SYMTAB",
+        Rela = 7 => "RELA",
+        RelaSz = 8 => "RELASZ",
+        RelaEnt = 9 => "RELAENT",
+        StrSz = 10 => "STRSZ",
+        SymEnt = 11 => "SYMENT",
+        Init = 12 => "INIT",
+        Fini = 13 => "FINI",
+        SoName = 14 => "SONAME",
+        RPath = 15 => "RPATH",
+        Symbolic = 16 => "SYMBOLIC",
+        Rel = 17 => "REL",
+        RelSz = 18 => "RELSZ",
+        RelEnt = 19 => "RELENT",
+        PltRel = 20 => "PLTREL",
+        Debug = 21 => "DEBUG",
+        TextRel = 22 => "TEXTREL",
+        JmpRel = 23 => "JMPREL",
+        BindNow = 24 => "BIND_NOW",
+        InitArray = 25 => "INIT_ARRAY",
+        FiniArray = 26 => "FINI_ARRAY",
+        InitArraySz = 27 => "INIT_ARRAYSZ",
+        FiniArraySz = 28 => "FINI_ARRAYSZ",
+        RunPath = 29 => "RUNPATH",
+        Flags = 30 => "FLAGS",
+        PreinitArray = 32 => "PREINIT_ARRAY",
+        PreinitArraySz = 33 => "PREINIT_ARRAYSZ",
+        SymTabShndx = 34 => "SYMTAB_SHNDX",
+        RelrSz = 35 => "RELRSZ",
+        Relr = 36 => "RELR",
+        RelrEnt = 37 => "RELRENT",
+        GnuHash = 0x6FFFFEF5 => "GNU_HASH",
+        VerSym = 0x6FFFFFF0 => "VERSYM",
+        RelaCount = 0x6FFFFFF9 => "RELACOUNT",
+        RelCount = 0x6FFFFFFA => "RELCOUNT",
+        Flags1 = 0x6FFFFFFB => "FLAGS_1",
+        VerDef = 0x6FFFFFFC => "VERDEF",
+        VerDefNum = 0x6FFFFFFD => "VERDEFNUM",
+        VerNeed = 0x6FFFFFFE => "VERNEED",
+        VerNeedNum = 0x6FFFFFFF => "VERNEEDNUM",
+    }
+    ranges {
+        OsSpecific = 0x6000000D..=0x6FFFFFFF => "OS spec",
+        ProcessorSpecific = 0x70000000..=0x7FFFFFFF => "Pr spec",
+    }
+}
@@ -0,0 +1,327 @@
+//! GNU symbol versioning: the version of each dynamic symbol
+//! (`SHT_GNU_versym`), the versions a library defines (`SHT_GNU_verdef`)
+//! and the versions it needs from others (`SHT_GNU_verneed`).
+
+use std::collections::HashMap;
+
+use super::symbols::Symbol;
+use super::types::{SectionType, SymbolBinding, SymbolVisibility};
+use super::{ElfFile, Result, field_u16, field_u32, read_cstr};
+
+/// `vd_flags` of the definition naming the file itself.
+pub const VER_FLG_BASE: u16 = 0x1;
+/// `vna_flags` of a weak version reference.
+pub const VER_FLG_WEAK: u16 = 0x2;
+/// `versym` bit of a hidden (non-default) version.
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionDefinition {
+    pub index: u16,
+    pub flags: u16,
+    pub name: String,
+    /// Versions this one inherits from.
+    pub parents: Vec<String>,
+}
+
+impl VersionDefinition {
+    pub fn is_base(&self) -> bool {
+        self.flags & VER_FLG_BASE != 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionNeed {
+    pub file: String,
+    /// Index, flags and name of each version needed from `file`.
+    pub versions: Vec<(u16, u16, String)>,
+}
+
+/// The version of a dynamic symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolVersion {
+    pub name: String,
+    /// Only reachable by explicit version (`foo@V1` rather than `foo@@V1`).
+    pub hidden: bool,
+    /// Defined by this file rather than needed from another one.
+    pub defined: bool,
+}
+
+impl SymbolVersion {
+    /// Formats `name` with the version, as `name@@VERSION` for the default
+    /// version and `name@VERSION` for a hidden one or a reference.
+    pub fn qualify(version: Option<&SymbolVersion>, name: &str) -> String {
+        match version {
+            Some(version) if version.hidden || !version.defined => {
+                format!("{}@{}", name, version.name)
+            }
+            Some(version) => format!("{}@@{}", name, version.name),
+            None => name.to_string(),
+        }
+    }
+
+    /// Returns whether a definition with version `definition` satisfies a
+    /// reference with version `reference`. Unversioned references bind to
+    /// the default version, and unversioned definitions satisfy any
+    /// reference.
+    pub fn satisfies(
+        definition: Option<&SymbolVersion>,
+        reference: Option<&SymbolVersion>,
+    ) -> bool {
+        match (definition, reference) {
+            (None, _) => true,
+            (Some(definition), None) => !definition.hidden,
+            (Some(definition), Some(reference)) => definition.name == reference.name,
+        }
+    }
+}
+
+/// The exported symbols of a file by name, for resolving references from
+/// other files.
+#[derive(Debug, Clone, Default)]
+pub struct ExportedSymbols {
+    symbols: HashMap<String, Vec<(Option<SymbolVersion>, SymbolBinding)>>,
+}
+
+impl ExportedSymbols {
+    pub fn new(elf: &ElfFile) -> Result<Self> {
+        let mut symbols: HashMap<String, Vec<_>> = HashMap::new();
+        for (symbol, version) in elf.get_exported_symbols()? {
+            let binding = symbol.get_binding();
+            symbols
+                .entry(symbol.name)
+                .or_default()
+                .push((version, binding));
+        }
+        Ok(Self { symbols })
+    }
+
+    /// Returns the binding of the definition `name` with a version
+    /// satisfying `version`, if there is one.
+    pub fn find(&self, name: &str, version: Option<&SymbolVersion>) -> Option<SymbolBinding> {
+        self.symbols
+            .get(name)?
+            .iter()
+            .find(|(definition, _)| SymbolVersion::satisfies(definition.as_ref(), version))
+            .map(|&(_, binding)| binding)
+    }
+}
+
+/// The position of a `Verdef` or `Verneed` entry in its section and the
+/// positions of its `Verdaux` or `Vernaux` entries.
+#[derive(Debug, Clone)]
+pub(crate) struct VersionEntry {
+    pub position: usize,
+    pub aux: Vec<usize>,
+}
+
+/// Walks the entries of a `SHT_GNU_verdef` section, or of a
+/// `SHT_GNU_verneed` section if `verneed` is set, following the `next`
+/// offsets. Entries that do not fit in `data` end the walk.
+pub(crate) fn version_entries(data: &[u8], verneed: bool, be: bool) -> Vec<VersionEntry> {
+    let (count_at, aux_at, entry_size) = if verneed { (2, 8, 16) } else { (6, 12, 20) };
+    let (aux_next_at, aux_size) = if verneed { (12, 16) } else { (4, 8) };
+    let mut entries = Vec::new();
+    let mut position = 0usize;
+    // every entry takes some bytes, which bounds malformed chains
+    for _ in 0..data.len() / entry_size {
+        if position
+            .checked_add(entry_size)
+            .is_none_or(|end| end > data.len())
+        {
+            break;
+        }
+        let (Some(count), Some(aux), Some(next)) = (
+            field_u16(data, position + count_at, be),
+            field_u32(data, position + aux_at, be),
+            field_u32(data, position + entry_size - 4, be),
+        ) else {
+            break;
+        };
+        let mut aux_positions = Vec::new();
+        let mut aux_position = position + aux as usize;
+        for _ in 0..count.min((data.len() / aux_size) as u16) {
+            if aux_position
+                .checked_add(aux_size)
+                .is_none_or(|end| end > data.len())
+            {
+                break;
+            }
+            let Some(aux_next) = field_u32(data, aux_position + aux_next_at, be) else {
+                break;
+            };
+            aux_positions.push(aux_position);
+            if aux_next == 0 {
+                break;
+            }
+            aux_position += aux_next as usize;
+        }
+        entries.push(VersionEntry {
+            position,
+            aux: aux_positions,
+        });
+        if next == 0 {
+            break;
+        }
+        position += next as usize;
+    }
+    entries
+}
+
+impl ElfFile {
+    fn version_section(&self, sh_type: SectionType) -> Option<(&[u8], &[u8])> {
+        let index = self
+            .section_headers
+            .iter()
+            .position(|sh| sh.get_type() == sh_type)?;
+        let data = self.get_section_data(index)?;
+        let link = self.section_headers[index].sh_link() as usize;
+        Some((data, self.get_section_data(link).unwrap_or(&[])))
+    }
+
+    /// Returns the entries of `SHT_GNU_verdef`.
+    pub fn get_version_definitions(&self) -> Vec<VersionDefinition> {
+        let Some((data, strtab)) = self.version_section(SectionType::GnuVerdef) else {
+            return Vec::new();
+        };
+        let be = self.is_big_endian();
+        let mut definitions = Vec::new();
+        for entry in version_entries(data, false, be) {
+            let (Some(flags), Some(index)) = (
+                field_u16(data, entry.position + 2, be),
+                field_u16(data, entry.position + 4, be),
+            ) else {
+                continue;
+            };
+            let mut names: Vec<String> = entry
+                .aux
+                .iter()
+                .filter_map(|&aux| field_u32(data, aux, be))
+                .map(|name| read_cstr(strtab, name as usize).unwrap_or_default())
+                .collect();
+            if !names.is_empty() {
+                let name = names.remove(0);
+                definitions.push(VersionDefinition {
+                    index,
+                    flags,
+                    name,
+                    parents: names,
+                });
+            }
+        }
+        definitions
+    }
+
+    /// Returns the entries of `SHT_GNU_verneed`.
+    pub fn get_version_needs(&self) -> Vec<VersionNeed> {
+        let Some((data, strtab)) = self.version_section(SectionType::GnuVerneed) else {
+            return Vec::new();
+        };
+        let be = self.is_big_endian();
+        let mut needs = Vec::new();
+        for entry in version_entries(data, true, be) {
+            let Some(file) = field_u32(data, entry.position + 4, be) else {
+                continue;
+            };
+            let versions = entry
+                .aux
+                .iter()
+                .filter_map(|&aux| {
+                    Some((
+                        field_u16(data, aux + 6, be)?,
+                        field_u16(data, aux + 4, be)?,
+                        read_cstr(strtab, field_u32(data, aux + 8, be)? as usize)
+                            .unwrap_or_default(),
+                    ))
+                })
+                .collect();
+            needs.push(VersionNeed {
+                file: read_cstr(strtab, file as usize).unwrap_or_default(),
+                versions,
+            });
+        }
+        needs
+    }
+
+    /// Returns the version of each symbol of `.dynsym`, indexed like
+    /// [`ElfFile::get_dynamic_symbols`]. Local and unversioned symbols have
+    /// none.
+    pub fn get_symbol_versions(&self) -> Result<Vec<Option<SymbolVersion>>> {
+        let count = self.get_dynamic_symbols()?.len();
+        let Some((versym, _)) = self.version_section(SectionType::GnuVersym) else {
+            return Ok(vec![None; count]);
+        };
+        let definitions = self.get_version_definitions();
+        let needs = self.get_version_needs();
+        let be = self.is_big_endian();
+        let versions = (0..count)
+            .map(|i| {
+                let value = field_u16(versym, i * 2, be)?;
+                let index = value & !VERSYM_HIDDEN;
+                // 0 is local, 1 the unversioned global base
+                if index < 2 {
+                    return None;
+                }
+                let hidden = value & VERSYM_HIDDEN != 0;
+                if let Some(definition) = definitions.iter().find(|d| d.index == index) {
+                    return Some(SymbolVersion {
+                        name: definition.name.clone(),
+                        hidden,
+                        defined: true,
+                    });
+                }
+                needs
+                    .iter()
+                    .flat_map(|need| &need.versions)
+                    .find(|(other, _, _)| *other == index)
+                    .map(|(_, _, name)| SymbolVersion {
+                        name: name.clone(),
+                        hidden,
+                        defined: false,
+                    })
+            })
+            .collect();
+        Ok(versions)
+    }
+
+    /// Returns the symbols of `.dynsym` that other files can bind to:
+    /// defined, global or weak, and not hidden or internal. The absolute
+    /// symbol GNU ld emits for each version definition, named after the
+    /// version, is left out.
+    pub fn get_exported_symbols(&self) -> Result<Vec<(Symbol, Option<SymbolVersion>)>> {
+        let symbols = self.get_dynamic_symbols()?;
+        let versions = self.get_symbol_versions()?;
+        Ok(symbols
+            .into_iter()
+            .zip(versions)
+            .filter(|(symbol, version)| {
+                !symbol.is_undefined()
+                    && !symbol.name.is_empty()
+                    && symbol.get_binding() != SymbolBinding::Local
+                    && !matches!(
+                        symbol.get_visibility(),
+                        SymbolVisibility::Hidden | SymbolVisibility::Internal
+                    )
+                    && version
+                        .as_ref()
+                        .is_none_or(|version| version.name != symbol.name)
+            })
+            .collect())
+    }
+
+    /// Returns the undefined global and weak symbols of `.dynsym` with the
+    /// version they need.
+    pub fn get_imported_symbols(&self) -> Result<Vec<(Symbol, Option<SymbolVersion>)>> {
+        let symbols = self.get_dynamic_symbols()?;
+        let versions = self.get_symbol_versions()?;
+        Ok(symbols
+            .into_iter()
+            .zip(versions)
+            .filter(|(symbol, _)| {
+                symbol.is_undefined()
+                    && !symbol.name.is_empty()
+                    && symbol.get_binding() != SymbolBinding::Local
+            })
+            .collect())
+    }
+}
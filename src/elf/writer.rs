@@ -0,0 +1,614 @@
+//! Serialisation of a (possibly modified) ELF file.
+//!
+//! `ElfWriter` copies the header fields, program headers and section
+//! headers of an `ElfFile` together with the stored (raw) contents of every
+//! section. `write` lays the file out again:
+//!
+//! - the original image is the background, so bytes not covered by any
+//!   section (segment padding, gaps) are reproduced;
+//! - sections whose contents still fit in their original place stay there;
+//!   grown and new sections are appended at the end of the file;
+//! - `.shstrtab` is rebuilt only when section names changed;
+//! - the header tables stay in place unless they grew.
+//!
+//! An unmodified file is therefore reproduced byte for byte. Allocated
+//! sections cannot move in files with program headers, since that would
+//! break the segments that map them, unless they are moved into a new
+//! segment with [`ElfWriter::move_to_new_segment`].
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use super::error::ElfError;
+use super::header::{ProgramHeader, SectionHeader, SectionHeader32, SectionHeader64};
+use super::types::{SectionFlags, SectionType, SegmentFlags, SegmentType};
+use super::{ElfFile, Result, read_cstr, write_u16, write_u32, write_word};
+
+/// Section indices from `SHN_LORESERVE` up are reserved.
+const SHN_LORESERVE: usize = 0xFF00;
+
+#[derive(Debug, Clone)]
+pub struct WriterSection {
+    pub name: String,
+    /// `sh_name` and, except for `SHT_NOBITS`, `sh_size` and `sh_offset`
+    /// are recomputed when writing.
+    pub header: SectionHeader,
+    /// Contents as stored in the file; compressed sections stay compressed.
+    pub data: Vec<u8>,
+    /// Offset and size of the contents in the original file.
+    original: Option<(u64, u64)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfWriter {
+    pub e_ident: [u8; 16],
+    pub e_type: u16,
+    pub e_machine: u16,
+    pub e_version: u32,
+    pub e_entry: u64,
+    pub e_flags: u32,
+    pub program_headers: Vec<ProgramHeader>,
+    pub sections: Vec<WriterSection>,
+    /// Index of the section header string table.
+    pub shstrndx: Option<usize>,
+    /// The original image, kept as the background of the output.
+    base: Vec<u8>,
+    /// Original location of the header tables: offset and entry count.
+    original_ph: (u64, usize),
+    original_sh: (u64, usize),
+    /// Original `e_phentsize` and `e_shentsize`, kept for empty tables.
+    original_entsize: (u16, u16),
+}
+
+fn align_up(value: u64, align: u64) -> Result<u64> {
+    if align <= 1 {
+        return Ok(value);
+    }
+    value
+        .div_ceil(align)
+        .checked_mul(align)
+        .ok_or_else(layout_overflow)
+}
+
+fn layout_overflow() -> ElfError {
+    ElfError::ParseError("the file layout exceeds the address space.".to_string())
+}
+
+/// Grows `data` to `len` bytes, failing instead of aborting when the
+/// memory cannot be allocated.
+fn resize(data: &mut Vec<u8>, len: u64) -> Result<()> {
+    let len = usize::try_from(len).map_err(|_| layout_overflow())?;
+    if let Some(additional) = len.checked_sub(data.len()) {
+        data.try_reserve_exact(additional).map_err(|_| {
+            ElfError::ParseError(format!("cannot allocate an output of {} bytes.", len))
+        })?;
+    }
+    data.resize(len, 0);
+    Ok(())
+}
+
+fn overlaps(ranges: &[(u64, u64)], start: u64, end: u64) -> bool {
+    ranges.iter().any(|&(s, e)| start < e && s < end)
+}
+
+impl ElfWriter {
+    pub fn new(elf: &ElfFile) -> Self {
+        let sections = elf
+            .section_headers
+            .iter()
+            .enumerate()
+            .map(|(i, sh)| {
+                let data = elf.get_raw_section_data(i).unwrap_or(&[]).to_vec();
+                let original = (sh.get_type() != SectionType::Nobits && !data.is_empty())
+                    .then(|| (sh.sh_offset(), data.len() as u64));
+                WriterSection {
+                    name: elf.get_section_name(i).unwrap_or_default(),
+                    header: *sh,
+                    data,
+                    original,
+                }
+            })
+            .collect();
+        let shstrndx = elf.e_shstrndx as usize;
+
+        Self {
+            e_ident: elf.e_ident,
+            e_type: elf.e_type,
+            e_machine: elf.e_machine,
+            e_version: elf.e_version,
+            e_entry: elf.e_entry,
+            e_flags: elf.e_flags,
+            program_headers: elf.program_headers.clone(),
+            sections,
+            shstrndx: (shstrndx != 0 && shstrndx < elf.section_headers.len()).then_some(shstrndx),
+            base: elf.data.clone(),
+            original_ph: (elf.e_phoff, elf.program_headers.len()),
+            original_sh: (elf.e_shoff, elf.section_headers.len()),
+            original_entsize: (elf.e_phentsize, elf.e_shentsize),
+        }
+    }
+
+    pub fn is_64(&self) -> bool {
+        self.e_ident[4] == 2
+    }
+
+    pub fn is_big_endian(&self) -> bool {
+        self.e_ident[5] == 2
+    }
+
+    /// Returns the end of the last segment in the file, checking that every
+    /// segment lies within the original image.
+    fn segments_end(&self) -> Result<u64> {
+        let mut end = 0;
+        for ph in &self.program_headers {
+            match ph.p_offset().checked_add(ph.p_filesz()) {
+                Some(segment_end) if segment_end <= self.base.len() as u64 => {
+                    end = end.max(segment_end)
+                }
+                _ => {
+                    return Err(ElfError::ParseError(format!(
+                        "the segment at offset 0x{:X} with size 0x{:X} exceeds the file.",
+                        ph.p_offset(),
+                        ph.p_filesz()
+                    )));
+                }
+            }
+        }
+        Ok(end)
+    }
+
+    /// Finds the index of the first section called `name`.
+    pub fn find_section(&self, name: &str) -> Option<usize> {
+        self.sections.iter().position(|s| s.name == name)
+    }
+
+    /// Appends a section and returns its index. The header's name, offset
+    /// and size are filled in when writing.
+    pub fn add_section(&mut self, name: &str, header: SectionHeader, data: Vec<u8>) -> usize {
+        self.sections.push(WriterSection {
+            name: name.to_string(),
+            header,
+            data,
+            original: None,
+        });
+        self.sections.len() - 1
+    }
+
+    /// Returns an empty section header of the file's class, to be filled in
+    /// for [`ElfWriter::add_section`].
+    pub fn new_section_header(&self, sh_type: SectionType) -> SectionHeader {
+        if self.is_64() {
+            SectionHeader::Elf64(SectionHeader64 {
+                sh_name: 0,
+                sh_type: sh_type.into(),
+                sh_flags: 0,
+                sh_addr: 0,
+                sh_offset: 0,
+                sh_size: 0,
+                sh_link: 0,
+                sh_info: 0,
+                sh_addralign: 1,
+                sh_entsize: 0,
+            })
+        } else {
+            SectionHeader::Elf32(SectionHeader32 {
+                sh_name: 0,
+                sh_type: sh_type.into(),
+                sh_flags: 0,
+                sh_addr: 0,
+                sh_offset: 0,
+                sh_size: 0,
+                sh_link: 0,
+                sh_info: 0,
+                sh_addralign: 1,
+                sh_entsize: 0,
+            })
+        }
+    }
+
+    /// Removes section `index`, renumbering the `sh_link` and `sh_info`
+    /// references of the other sections and the string table index.
+    /// References to the removed section become 0.
+    pub fn remove_section(&mut self, index: usize) {
+        if index == 0 || index >= self.sections.len() {
+            return;
+        }
+        self.sections.remove(index);
+        let renumber = |value: u32| -> u32 {
+            match (value as usize).cmp(&index) {
+                std::cmp::Ordering::Less => value,
+                std::cmp::Ordering::Equal => 0,
+                std::cmp::Ordering::Greater => value - 1,
+            }
+        };
+        for section in &mut self.sections {
+            let link = section.header.sh_link();
+            section.header.set_sh_link(renumber(link));
+            // sh_info holds a section index only for these
+            let info_is_index = section.header.get_flags().contains(SectionFlags::INFO_LINK)
+                || matches!(
+                    section.header.get_type(),
+                    SectionType::Rel | SectionType::Rela
+                );
+            if info_is_index {
+                let info = section.header.sh_info();
+                section.header.set_sh_info(renumber(info));
+            }
+        }
+        self.shstrndx = match self.shstrndx {
+            Some(i) if i == index => None,
+            Some(i) if i > index => Some(i - 1),
+            other => other,
+        };
+    }
+
+    /// Moves the allocated sections `indices` into a new `PT_LOAD` segment
+    /// at the end of the file, mapped above every existing segment, for
+    /// contents that no longer fit in place. Segments that covered exactly
+    /// one of the sections, such as `PT_INTERP` and `PT_DYNAMIC`, follow it.
+    /// The program header table moves into the new segment as well, unless
+    /// a `PT_NULL` entry can be reused for it.
+    ///
+    /// The section contents must already have their final size.
+    pub fn move_to_new_segment(&mut self, indices: &[usize]) -> Result<()> {
+        let loads: Vec<usize> = (0..self.program_headers.len())
+            .filter(|&i| self.program_headers[i].get_type() == SegmentType::Load)
+            .collect();
+        let (Some(&first), Some(&last)) = (loads.first(), loads.last()) else {
+            return Err(ElfError::ParseError(
+                "the file has no PT_LOAD segment to extend.".to_string(),
+            ));
+        };
+        let page = loads
+            .iter()
+            .map(|&i| self.program_headers[i].p_align())
+            .max()
+            .unwrap_or(0)
+            .max(0x1000);
+        // Keep the offset-to-address delta of the first segment, so that
+        // the program headers are found at the same place either way.
+        self.segments_end()?;
+        let first = self.program_headers[first];
+        let delta = first.p_vaddr().wrapping_sub(first.p_offset());
+        let mut vaddr_end = 0;
+        for &i in &loads {
+            let ph = &self.program_headers[i];
+            vaddr_end = vaddr_end.max(
+                ph.p_vaddr()
+                    .checked_add(ph.p_memsz())
+                    .ok_or_else(layout_overflow)?,
+            );
+        }
+        let offset = align_up(
+            (self.base.len() as u64).max(vaddr_end.wrapping_sub(delta)),
+            page,
+        )?;
+        let vaddr = offset.wrapping_add(delta);
+
+        let null = self
+            .program_headers
+            .iter()
+            .position(|ph| ph.get_type() == SegmentType::Null);
+        let phentsize = if self.is_64() { 56 } else { 32 };
+        let mut size = match null {
+            Some(_) => 0,
+            None => (self.program_headers.len() as u64 + 1) * phentsize,
+        };
+
+        let mut flags = SegmentFlags::R;
+        for &i in indices {
+            let section = self
+                .sections
+                .get_mut(i)
+                .ok_or_else(|| ElfError::ParseError(format!("section {} does not exist.", i)))?;
+            if !section.header.get_flags().contains(SectionFlags::ALLOC) {
+                continue;
+            }
+            let (old_offset, old_size) = (section.header.sh_offset(), section.header.sh_size());
+            let len = section.data.len() as u64;
+            let start = align_up(size, section.header.sh_addralign())?;
+            let section_offset = offset.checked_add(start).ok_or_else(layout_overflow)?;
+            section.header.set_sh_offset(section_offset);
+            section.header.set_sh_addr(vaddr.wrapping_add(start));
+            section.header.set_sh_size(len);
+            section.original = Some((section_offset, len));
+            if section.header.get_flags().contains(SectionFlags::WRITE) {
+                flags |= SegmentFlags::W;
+            }
+            if section.header.get_flags().contains(SectionFlags::EXEC) {
+                flags |= SegmentFlags::X;
+            }
+            for ph in &mut self.program_headers {
+                if matches!(
+                    ph.get_type(),
+                    SegmentType::Load | SegmentType::Phdr | SegmentType::GnuRelro
+                ) || ph.p_offset() != old_offset
+                    || ph.p_filesz() != old_size
+                {
+                    continue;
+                }
+                ph.set_p_offset(section_offset);
+                ph.set_p_vaddr(vaddr.wrapping_add(start));
+                ph.set_p_paddr(vaddr.wrapping_add(start));
+                ph.set_p_filesz(len);
+                ph.set_p_memsz(len);
+            }
+            size = start.checked_add(len).ok_or_else(layout_overflow)?;
+        }
+
+        let mut load = self.program_headers[last];
+        load.set_p_flags(flags);
+        load.set_p_offset(offset);
+        load.set_p_vaddr(vaddr);
+        load.set_p_paddr(vaddr);
+        load.set_p_filesz(size);
+        load.set_p_memsz(size);
+        load.set_p_align(page);
+        match null {
+            Some(i) => {
+                self.program_headers.remove(i);
+                let last = if i < last { last - 1 } else { last };
+                self.program_headers.insert(last + 1, load);
+            }
+            None => {
+                self.program_headers.insert(last + 1, load);
+                let table = self.program_headers.len() as u64 * phentsize;
+                for ph in &mut self.program_headers {
+                    if ph.get_type() == SegmentType::Phdr {
+                        ph.set_p_offset(offset);
+                        ph.set_p_vaddr(vaddr);
+                        ph.set_p_paddr(vaddr);
+                        ph.set_p_filesz(table);
+                        ph.set_p_memsz(table);
+                    }
+                }
+                self.original_ph = (offset, self.program_headers.len());
+            }
+        }
+        resize(
+            &mut self.base,
+            offset.checked_add(size).ok_or_else(layout_overflow)?,
+        )?;
+        Ok(())
+    }
+
+    /// Drops the parts of the original image beyond the header tables and
+    /// the segments, so that the sections there are packed again after
+    /// them. Used after removing sections, to shrink the file.
+    pub fn discard_unmapped(&mut self) -> Result<()> {
+        let (ehsize, phentsize) = if self.is_64() { (64, 56) } else { (52, 32) };
+        let mut end = ehsize;
+        if self.program_headers.is_empty() {
+            self.original_ph = (0, 0);
+        } else {
+            end = end.max(
+                self.original_ph
+                    .0
+                    .saturating_add(self.original_ph.1 as u64 * phentsize),
+            );
+        }
+        end = end.max(self.segments_end()?);
+        self.base
+            .truncate(usize::try_from(end).unwrap_or(usize::MAX));
+        for section in &mut self.sections {
+            if section
+                .original
+                .is_some_and(|(offset, size)| offset.saturating_add(size) > end)
+            {
+                section.original = None;
+            }
+        }
+        if self.original_sh.0 >= end {
+            self.original_sh.0 = 0;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the section header string table if any name is missing
+    /// from it, and sets every `sh_name`.
+    fn section_names(&self, sections: &mut [WriterSection]) {
+        let Some(shstrndx) = self.shstrndx else {
+            return;
+        };
+        let table = &sections[shstrndx].data;
+        let unchanged = sections
+            .iter()
+            .all(|s| read_cstr(table, s.header.sh_name() as usize).unwrap_or_default() == s.name);
+        if unchanged {
+            return;
+        }
+
+        let mut table = vec![0u8];
+        let mut offsets: HashMap<String, u32> = HashMap::new();
+        offsets.insert(String::new(), 0);
+        for section in sections.iter_mut() {
+            let offset = *offsets.entry(section.name.clone()).or_insert_with(|| {
+                let offset = table.len() as u32;
+                table.extend_from_slice(section.name.as_bytes());
+                table.push(0);
+                offset
+            });
+            section.header.set_sh_name(offset);
+        }
+        sections[shstrndx].data = table;
+    }
+
+    /// Serialises the file.
+    pub fn write(&self) -> Result<Vec<u8>> {
+        let is_64 = self.is_64();
+        let be = self.is_big_endian();
+        let (ehsize, phentsize, shentsize) = if is_64 {
+            (64u64, 56u64, 64u64)
+        } else {
+            (52, 32, 40)
+        };
+        if self.sections.len() >= SHN_LORESERVE {
+            return Err(ElfError::ParseError(format!(
+                "{} sections need extended section numbering, which is not supported.",
+                self.sections.len()
+            )));
+        }
+
+        let mut sections = self.sections.clone();
+        self.section_names(&mut sections);
+        for section in &mut sections {
+            if section.header.get_type() != SectionType::Nobits {
+                section.header.set_sh_size(section.data.len() as u64);
+            }
+        }
+
+        // the header tables, then every placed section
+        let mut tables: Vec<(u64, u64)> = vec![(0, ehsize)];
+        let mut end = (self.base.len() as u64).max(self.segments_end()?);
+
+        // program headers stay in place unless there are more of them
+        let ph_size = self.program_headers.len() as u64 * phentsize;
+        let phoff = if self.program_headers.is_empty()
+            || (self.program_headers.len() <= self.original_ph.1 && self.original_ph.0 != 0)
+        {
+            self.original_ph.0
+        } else if self
+            .program_headers
+            .iter()
+            .any(|ph| ph.get_type() == SegmentType::Phdr)
+        {
+            return Err(ElfError::ParseError(
+                "the program header table cannot grow while PT_PHDR maps it.".to_string(),
+            ));
+        } else {
+            0
+        };
+        if phoff != 0 {
+            tables.push((
+                phoff,
+                phoff.checked_add(ph_size).ok_or_else(layout_overflow)?,
+            ));
+        }
+
+        // Sections that still fit keep their offset. They may overlap each
+        // other only where the original sections did.
+        let mut used = Vec::new();
+        let mut pending = Vec::new();
+        for (i, section) in sections.iter().enumerate() {
+            if section.header.get_type() == SectionType::Nobits || section.data.is_empty() {
+                continue;
+            }
+            let len = section.data.len() as u64;
+            match section.original {
+                Some((offset, size))
+                    if section.header.sh_offset() == offset
+                        && len <= size
+                        && !overlaps(&tables, offset, offset.saturating_add(len)) =>
+                {
+                    used.push((offset, offset + len));
+                }
+                _ => pending.push(i),
+            }
+        }
+        let has_segments = !self.program_headers.is_empty();
+        for &i in &pending {
+            let section = &mut sections[i];
+            if has_segments && section.header.get_flags().contains(SectionFlags::ALLOC) {
+                return Err(ElfError::ParseError(format!(
+                    "allocated section {} cannot move without breaking its segment.",
+                    section.name
+                )));
+            }
+            let offset = align_up(end, section.header.sh_addralign())?;
+            end = offset
+                .checked_add(section.data.len() as u64)
+                .ok_or_else(layout_overflow)?;
+            section.header.set_sh_offset(offset);
+            used.push((offset, end));
+        }
+
+        let phoff = if phoff == 0 && !self.program_headers.is_empty() {
+            let offset = align_up(end, if is_64 { 8 } else { 4 })?;
+            end = offset.checked_add(ph_size).ok_or_else(layout_overflow)?;
+            offset
+        } else {
+            phoff
+        };
+
+        // the section header table likewise
+        let sh_size = sections.len() as u64 * shentsize;
+        let shoff = if sections.is_empty()
+            || (sections.len() <= self.original_sh.1
+                && self.original_sh.0 != 0
+                && !overlaps(
+                    &used,
+                    self.original_sh.0,
+                    self.original_sh.0.saturating_add(sh_size),
+                )) {
+            self.original_sh.0
+        } else {
+            let offset = align_up(end, if is_64 { 8 } else { 4 })?;
+            end = offset.checked_add(sh_size).ok_or_else(layout_overflow)?;
+            offset
+        };
+        end = end.max(
+            shoff
+                .checked_add(if sections.is_empty() { 0 } else { sh_size })
+                .ok_or_else(layout_overflow)?,
+        );
+
+        if !is_64 && end > u32::MAX as u64 {
+            return Err(ElfError::ParseError(
+                "the output exceeds the 4 GiB limit of ELF32.".to_string(),
+            ));
+        }
+
+        let mut out = self.base.clone();
+        resize(&mut out, end)?;
+        for section in &sections {
+            if section.header.get_type() == SectionType::Nobits || section.data.is_empty() {
+                continue;
+            }
+            let offset = section.header.sh_offset() as usize;
+            out[offset..offset + section.data.len()].copy_from_slice(&section.data);
+        }
+
+        let mut writer = Cursor::new(&mut out);
+        writer.set_position(phoff);
+        for ph in &self.program_headers {
+            ph.write_to(&mut writer, be)?;
+        }
+        if !sections.is_empty() {
+            writer.set_position(shoff);
+            for section in &sections {
+                section.header.write_to(&mut writer, be)?;
+            }
+        }
+
+        writer.set_position(0);
+        std::io::Write::write_all(&mut writer, &self.e_ident)?;
+        write_u16(&mut writer, self.e_type, be)?;
+        write_u16(&mut writer, self.e_machine, be)?;
+        write_u32(&mut writer, self.e_version, be)?;
+        write_word(&mut writer, self.e_entry, is_64, be)?;
+        write_word(&mut writer, phoff, is_64, be)?;
+        write_word(&mut writer, shoff, is_64, be)?;
+        write_u32(&mut writer, self.e_flags, be)?;
+        write_u16(&mut writer, ehsize as u16, be)?;
+        let (phentsize, shentsize) = match (self.program_headers.is_empty(), sections.is_empty()) {
+            (true, true) => self.original_entsize,
+            (true, false) => (self.original_entsize.0, shentsize as u16),
+            (false, true) => (phentsize as u16, self.original_entsize.1),
+            (false, false) => (phentsize as u16, shentsize as u16),
+        };
+        write_u16(&mut writer, phentsize, be)?;
+        write_u16(&mut writer, self.program_headers.len() as u16, be)?;
+        write_u16(&mut writer, shentsize, be)?;
+        write_u16(&mut writer, sections.len() as u16, be)?;
+        write_u16(&mut writer, self.shstrndx.unwrap_or(0) as u16, be)?;
+        Ok(out)
+    }
+}
+
+impl ElfFile {
+    /// Serialises the file from its parsed headers and section contents.
+    /// See [`ElfWriter`] to change section contents.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        ElfWriter::new(self).write()
+    }
+}
@@ -1,23 +1,52 @@
-mod elf;
-mod output;
-
-use clap::Parser;
-use elf::ElfFile;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use elfread::elf;
+use elfread::elf::ElfFile;
+use elfread::elf::abi::AbiReport;
+use elfread::elf::bloat::{BloatGroup, BloatReport};
+use elfread::elf::deps::{DependencyTree, ResolveOptions};
+use elfread::elf::diff::{self, DiffOptions, ScopeDiff, ScopeStatus};
+use elfread::elf::edit::DynamicEdit;
+use elfread::elf::image::uf2::{self, Uf2Options};
+use elfread::elf::image::{self, Chunk, ElfImageOptions, ihex, srec};
+use elfread::elf::linkcheck;
+use elfread::elf::memory;
+use elfread::elf::sizediff::{SizeChange, SizeChangeKind, SizeDiff};
+use elfread::elf::strip::StripOptions;
 use owo_colors::OwoColorize;
-use tabled::settings::object::{Columns, Rows};
-use tabled::settings::{Alignment, Border, Margin, Modify, Padding, Width};
 use std::fs;
 use std::io::{BufReader, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tabled::builder::Builder;
+use tabled::settings::object::Columns;
+use tabled::settings::{Alignment, Margin, Modify, Padding};
 use tabled::{Table, settings::Style};
 
-use crate::output::{
-    ProgramHeaderTable, ProgramHeaderTable2, SectionHeaderTable, SectionHeaderTable2
+use elfread::elf::dwarf::Encoding;
+use elfread::elf::dwarf::addr2line::Addr2Line;
+use elfread::elf::dwarf::cfi::{
+    CallFrameInfo, CallFrameInstruction, CfaRule, Cie, EhFrameHdr, FrameKind, RegisterRule,
+    UnwindRow,
+};
+use elfread::elf::dwarf::describe::{display_attr, display_form, display_tag};
+use elfread::elf::dwarf::expr::describe_expression;
+use elfread::elf::dwarf::layout::StructLayout;
+use elfread::elf::dwarf::line::LineProgram;
+use elfread::elf::dwarf::registers::register_name;
+use elfread::elf::dwarf::typeinfo::TypeIndex;
+use elfread::output::{
+    AbiChangeTable, Addr2LineTable, AttributeTable, BloatSectionTable, BloatSymbolTable,
+    ChunkTable, CompilationUnitTable, DebugSectionSizeTable, DirectorySizeTable, ExidxTable,
+    FunctionTable, ProgramHeaderTable, ProgramHeaderTable2, RegionUsageTable, RemovedSectionTable,
+    SectionHeaderTable, SectionHeaderTable2, SectionPlacementTable, SectionSizeChangeTable,
+    SymbolSizeChangeTable, UnitSizeTable,
 };
 
 #[derive(Debug, Parser)]
 #[command(version, about, long_about = "A tool for parsing ELF32/64 files.")]
 struct Usage {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     file: Option<PathBuf>,
 
     #[arg(short, long, help = "Print program header information")]
@@ -26,8 +55,466 @@ struct Usage {
     #[arg(short, long, help = "Print section header information")]
     section: bool,
 
+    #[arg(short = 'A', long, help = "Print architecture specific information")]
+    arch_specific: bool,
+
     #[arg(short, long, help = "Print all information")]
     all: bool,
+
+    #[arg(
+        short,
+        long,
+        help = "Print the ARM EHABI unwind tables (.ARM.exidx/.ARM.extab)"
+    )]
+    unwind: bool,
+
+    #[arg(
+        long,
+        num_args = 1..,
+        value_name = "ADDR",
+        help = "Map addresses (hex) to source locations using DWARF line tables"
+    )]
+    addr2line: Vec<String>,
+
+    #[arg(
+        long,
+        requires = "addr2line",
+        help = "Also show the frames of inlined functions"
+    )]
+    inlines: bool,
+
+    #[arg(
+        long,
+        help = "List functions recovered from symbols, FDEs, the entry point, init/fini arrays and PLT stubs"
+    )]
+    functions: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "KIND",
+        help = "Dump DWARF debugging information"
+    )]
+    debug_dump: Option<DebugDump>,
+
+    #[arg(
+        long,
+        num_args = 0..,
+        value_name = "NAME",
+        help = "Print the memory layout of structs (all named structs if no NAME is given)"
+    )]
+    struct_layout: Option<Vec<String>>,
+
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_name = "BYTES",
+        help = "Cache line size for --struct-layout"
+    )]
+    cacheline_size: u64,
+
+    #[arg(
+        long,
+        help = "Break down debug info size per section, compilation unit and directory"
+    )]
+    debug_size: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Change the interpreter, RPATH/RUNPATH, SONAME and needed libraries
+    Edit(EditArgs),
+    /// Remove symbols, debug information and other sections that are not loaded
+    Strip(StripArgs),
+    /// Write the loadable segments as a raw binary, Intel HEX, S-record or UF2 image
+    Export(ExportArgs),
+    /// Build a minimal ELF file from a raw binary, Intel HEX, S-record or UF2 image
+    Import(ImportArgs),
+    /// Report flash and RAM usage per linker script memory region
+    Memory(MemoryArgs),
+    /// Break the binary size down by symbol, section, source file and namespace
+    Bloat(BloatArgs),
+    /// Compare the section and symbol sizes of two builds
+    DiffSize(DiffSizeArgs),
+    /// Compare the headers, segments, sections, dynamic entries, exported symbols and notes of two files
+    Diff(DiffArgs),
+    /// Check whether a new version of a shared library is ABI compatible with the old one
+    AbiCheck(AbiCheckArgs),
+    /// Resolve the shared library dependency tree and symbol bindings without running the binary
+    Deps(DepsArgs),
+    /// Check that a set of executables, libraries and objects defines every symbol they reference, once
+    LinkCheck(LinkCheckArgs),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ImageFormat {
+    /// Flat binary from the lowest load address, gaps filled
+    Binary,
+    /// Intel HEX with extended linear address records
+    Ihex,
+    /// Motorola S-records (S1/S2/S3 by address width)
+    Srec,
+    /// UF2 blocks for USB mass storage bootloaders
+    Uf2,
+}
+
+/// Parses a decimal or `0x`-prefixed hexadecimal number.
+fn parse_number(text: &str) -> Result<u64, String> {
+    let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    parsed.map_err(|_| format!("invalid number '{}'", text))
+}
+
+/// Parses a UF2 family ID given as a board name or a number.
+fn parse_family(text: &str) -> Result<u32, String> {
+    if let Some(id) = uf2::family_id(text) {
+        return Ok(id);
+    }
+    let id = parse_number(text).map_err(|_| format!("unknown family '{}'", text))?;
+    u32::try_from(id).map_err(|_| format!("family ID '{}' does not fit in 32 bits", text))
+}
+
+#[derive(Debug, Args)]
+struct ExportArgs {
+    file: PathBuf,
+
+    #[arg(short, long, value_name = "FILE", help = "Image file to write")]
+    output: PathBuf,
+
+    #[arg(short = 'O', long, value_enum, help = "Image format")]
+    format: ImageFormat,
+
+    #[arg(
+        long,
+        value_name = "BYTE",
+        default_value = "0xFF",
+        value_parser = parse_number,
+        help = "Value filling the gaps between segments in binary images"
+    )]
+    gap_fill: u64,
+
+    #[arg(
+        long,
+        value_name = "ID",
+        value_parser = parse_family,
+        help = "UF2 family ID, as a number or a board name (e.g. RP2040, NRF52840)"
+    )]
+    family: Option<u32>,
+
+    #[arg(long, value_name = "BYTES", default_value = "256", value_parser = parse_number, help = "UF2 payload bytes per block")]
+    page_size: u64,
+
+    #[arg(long, value_name = "ADDR", value_parser = parse_number, requires = "flash_size", help = "Start of the flash; UF2 data outside it is refused")]
+    flash_start: Option<u64>,
+
+    #[arg(long, value_name = "BYTES", value_parser = parse_number, requires = "flash_start", help = "Size of the flash")]
+    flash_size: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+struct ImportArgs {
+    file: PathBuf,
+
+    #[arg(short, long, value_name = "FILE", help = "ELF file to write")]
+    output: PathBuf,
+
+    #[arg(
+        short = 'I',
+        long,
+        value_enum,
+        help = "Image format (detected from the contents if omitted)"
+    )]
+    format: Option<ImageFormat>,
+
+    #[arg(long, value_name = "ADDR", default_value = "0", value_parser = parse_number, help = "Load address of a binary image")]
+    base: u64,
+
+    #[arg(long, value_name = "NUMBER", default_value = "0", value_parser = parse_number, help = "e_machine of the ELF file (e.g. 40 for ARM)")]
+    machine: u64,
+
+    #[arg(long, value_name = "ADDR", value_parser = parse_number, help = "Entry point (defaults to the image's start address)")]
+    entry: Option<u64>,
+
+    #[arg(long, help = "Write an ELF64 file")]
+    elf64: bool,
+
+    #[arg(long, help = "Write a big-endian file")]
+    big_endian: bool,
+}
+
+#[derive(Debug, Args)]
+struct MemoryArgs {
+    file: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        help = "Linker script with a MEMORY block, or a list of 'NAME ORIGIN LENGTH' lines"
+    )]
+    regions: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value = "100",
+        help = "Exit with an error when a region is fuller than this"
+    )]
+    threshold: f64,
+}
+
+#[derive(Debug, Args)]
+struct BloatArgs {
+    file: PathBuf,
+
+    #[arg(
+        short = 'n',
+        long,
+        value_name = "N",
+        default_value = "20",
+        help = "Number of rows per table"
+    )]
+    top: usize,
+
+    #[arg(long, help = "Print the report as JSON")]
+    json: bool,
+}
+
+#[derive(Debug, Args)]
+struct DiffSizeArgs {
+    old: PathBuf,
+
+    new: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "BYTES",
+        value_parser = parse_number,
+        help = "Exit with an error when the allocated sections grow by more than this"
+    )]
+    budget: Option<u64>,
+
+    #[arg(
+        short = 'n',
+        long,
+        value_name = "N",
+        default_value = "20",
+        help = "Number of symbol rows"
+    )]
+    top: usize,
+
+    #[arg(long, help = "Print the differences as JSON")]
+    json: bool,
+}
+
+#[derive(Debug, Args)]
+struct DiffArgs {
+    old: PathBuf,
+
+    new: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Leave out a scope or scope.field, e.g. 'section[.comment]' or 'header.e_shoff' (* matches anything)"
+    )]
+    ignore: Vec<String>,
+
+    #[arg(long, help = "Leave out the build ID and debug link checksums")]
+    ignore_volatile: bool,
+
+    #[arg(long, help = "Print the differences as JSON")]
+    json: bool,
+}
+
+#[derive(Debug, Args)]
+struct AbiCheckArgs {
+    old: PathBuf,
+
+    new: PathBuf,
+
+    #[arg(long, help = "Print the report as JSON")]
+    json: bool,
+}
+
+#[derive(Debug, Args)]
+struct DepsArgs {
+    file: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Look up absolute paths, ld.so.conf and the default directories in DIR"
+    )]
+    sysroot: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATHS",
+        help = "Colon-separated LD_LIBRARY_PATH (defaults to the environment without --sysroot)"
+    )]
+    library_path: Option<String>,
+
+    #[arg(long, help = "Do not read /etc/ld.so.conf")]
+    no_ld_so_conf: bool,
+
+    #[arg(
+        long,
+        help = "List the provider of every undefined symbol, not just the unresolved ones"
+    )]
+    symbols: bool,
+}
+
+#[derive(Debug, Args)]
+struct LinkCheckArgs {
+    #[arg(
+        required = true,
+        help = "Executables, shared libraries and relocatable objects"
+    )]
+    files: Vec<PathBuf>,
+
+    #[arg(long, help = "Also list weak references no file defines")]
+    weak: bool,
+}
+
+#[derive(Debug, Args)]
+struct EditArgs {
+    file: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        help = "Write the result to FILE instead of modifying the input"
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Set the program interpreter (PT_INTERP)"
+    )]
+    set_interpreter: Option<String>,
+
+    #[arg(long, value_name = "NAME", help = "Set DT_SONAME")]
+    set_soname: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATHS",
+        conflicts_with = "remove_rpath",
+        help = "Set DT_RPATH"
+    )]
+    set_rpath: Option<String>,
+
+    #[arg(long, help = "Remove DT_RPATH")]
+    remove_rpath: bool,
+
+    #[arg(
+        long,
+        value_name = "PATHS",
+        conflicts_with = "remove_runpath",
+        help = "Set DT_RUNPATH"
+    )]
+    set_runpath: Option<String>,
+
+    #[arg(long, help = "Remove DT_RUNPATH")]
+    remove_runpath: bool,
+
+    #[arg(long, value_name = "LIB", help = "Add a DT_NEEDED entry")]
+    add_needed: Vec<String>,
+
+    #[arg(long, value_name = "LIB", help = "Remove a DT_NEEDED entry")]
+    remove_needed: Vec<String>,
+
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        help = "Replace the DT_NEEDED entry OLD with NEW"
+    )]
+    replace_needed: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct StripArgs {
+    file: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        help = "Write the result to FILE instead of modifying the input"
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Keep sections matching PATTERN (`*` is a wildcard)"
+    )]
+    keep_section: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Also remove non-loaded sections matching PATTERN"
+    )]
+    remove_section: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write the removed sections to FILE and link it with .gnu_debuglink"
+    )]
+    debug_file: Option<PathBuf>,
+}
+
+impl EditArgs {
+    /// Collects the requested edits: needed libraries first, then SONAME,
+    /// RPATH, RUNPATH and the interpreter.
+    fn edits(&self) -> Vec<DynamicEdit> {
+        let mut edits = Vec::new();
+        edits.extend(
+            self.remove_needed
+                .iter()
+                .cloned()
+                .map(DynamicEdit::RemoveNeeded),
+        );
+        edits.extend(
+            self.replace_needed
+                .chunks_exact(2)
+                .map(|pair| DynamicEdit::ReplaceNeeded(pair[0].clone(), pair[1].clone())),
+        );
+        edits.extend(self.add_needed.iter().cloned().map(DynamicEdit::AddNeeded));
+        edits.extend(self.set_soname.clone().map(DynamicEdit::SetSoname));
+        if self.remove_rpath {
+            edits.push(DynamicEdit::RemoveRpath);
+        }
+        edits.extend(self.set_rpath.clone().map(DynamicEdit::SetRpath));
+        if self.remove_runpath {
+            edits.push(DynamicEdit::RemoveRunpath);
+        }
+        edits.extend(self.set_runpath.clone().map(DynamicEdit::SetRunpath));
+        edits.extend(
+            self.set_interpreter
+                .clone()
+                .map(DynamicEdit::SetInterpreter),
+        );
+        edits
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DebugDump {
+    /// Compilation units and their DIE trees from .debug_info
+    Info,
+    /// CIEs and FDEs from .eh_frame and .debug_frame, and the .eh_frame_hdr table
+    Frames,
+    /// Unwind rows of every FDE
+    FramesInterp,
 }
 
 fn print_brief(elf_file: &ElfFile) {
@@ -49,7 +536,7 @@ fn print_brief(elf_file: &ElfFile) {
     println!("  Entry Point: 0x{:X}", elf_file.e_entry);
     println!("  Program Header Offset: 0x{:X}", elf_file.e_phoff);
     println!("  Section Header Offset: 0x{:X}", elf_file.e_shoff);
-    println!("  Flags: 0x{:X}", elf_file.e_flags);
+    println!("  Flags: {}", elf_file.get_flags());
     println!("  ELF Header Size: {} bytes", elf_file.e_ehsize);
     println!(
         "  Program Header Entry Size: {} bytes",
@@ -80,12 +567,12 @@ fn print_section(elf_file: &ElfFile) {
             .section_headers
             .iter()
             .enumerate()
-            .map(|(i, sh)| SectionHeaderTable::from_sh(i, &sh, &elf_file));
+            .map(|(i, sh)| SectionHeaderTable::from_sh(i, sh, elf_file));
         let shs2 = elf_file
             .section_headers
             .iter()
             .enumerate()
-            .map(|(i, sh)| SectionHeaderTable2::from_sh(i, &sh, &elf_file));
+            .map(|(i, sh)| SectionHeaderTable2::from_sh(i, sh, elf_file));
         let mut sh_table = Table::new(shs);
         sh_table.with(Style::ascii());
         sh_table.with(Padding::new(0, 1, 0, 0));
@@ -96,7 +583,6 @@ fn print_section(elf_file: &ElfFile) {
         sh_table2.with(Padding::new(0, 1, 0, 0));
         sh_table2.with(Margin::new(0, 0, 1, 1));
 
-
         println!("\n{}", "Section Header Info Table1:".green());
         println!("{}", sh_table);
         /*
@@ -111,6 +597,7 @@ fn print_section(elf_file: &ElfFile) {
         println!("  L (link order), O (extra OS processing required), G (group), T (TLS),");
         println!("  C (compressed), x (unknown), o (OS specific), E (exclude),");
         println!("  D (mbind), p (processor specific), - (empty)");
+        println!("Compression: type, uncompressed size (compressed/uncompressed ratio)");
 
         println!("\n{}", "Section Header Info Table2:".green());
         println!("{}", sh_table2);
@@ -127,12 +614,12 @@ fn print_program(elf_file: &ElfFile) {
             .program_headers
             .iter()
             .enumerate()
-            .map(|(i, ph)| ProgramHeaderTable::from_ph(i, &ph));
+            .map(|(i, ph)| ProgramHeaderTable::from_ph(i, ph, elf_file));
         let phs2 = elf_file
             .program_headers
             .iter()
             .enumerate()
-            .map(|(i, ph)| ProgramHeaderTable2::from_ph(i, &ph));
+            .map(|(i, ph)| ProgramHeaderTable2::from_ph(i, ph));
         let mut ph_table = Table::new(phs);
         ph_table.with(Style::ascii());
         ph_table.with(Padding::new(0, 1, 0, 0));
@@ -145,58 +632,1752 @@ fn print_program(elf_file: &ElfFile) {
 
         println!("\n{}", "Program Header Info Table1:".green());
         println!("{}", ph_table);
-        
+
         println!("\n{}", "Program Header Info Table2:".green());
         println!("{}", ph_table2);
     }
 }
 
-fn main() {
-    let cli = Usage::parse();
-    if cli.file.is_none() {
-        eprintln!("{}", "Error: the parsed file must be specified.".red());
+fn print_arch_specific(elf_file: &ElfFile) {
+    let sections = elf_file.get_attribute_sections();
+    if sections.is_empty() {
         return;
     }
-    let path_buf = cli.file.unwrap();
-    let file = fs::File::open(path_buf);
-    if file.is_err() {
+
+    println!(
+        "\n{}",
+        ">>>>>>>>>>> Architecture Specific Information <<<<<<<<<<<".green()
+    );
+    for index in sections {
+        let attributes = match elf_file.get_attributes(index) {
+            Ok(attributes) => attributes,
+            Err(e) => {
+                eprintln!("{}", e.red());
+                continue;
+            }
+        };
+
+        for subsection in &attributes.subsections {
+            println!(
+                "\n{}",
+                format!("Attribute Section: {}", subsection.vendor).green()
+            );
+            for group in &subsection.groups {
+                let rows = group
+                    .attributes
+                    .iter()
+                    .map(|attr| AttributeTable::from_attribute(&subsection.vendor, attr));
+                let mut table = Table::new(rows);
+                table.with(Style::ascii());
+                table.with(Padding::new(0, 1, 0, 0));
+                table.with(Margin::new(0, 0, 1, 1));
+
+                println!("{}", group.scope);
+                println!("{}", table);
+            }
+        }
+    }
+}
+
+fn print_unwind(elf_file: &ElfFile) {
+    let entries = match elf_file.arm_exidx_entries() {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+
+    println!("\n{}", ">>>>>>>>>>> ARM Unwind Table <<<<<<<<<<<".green());
+    let mut table = Table::new(entries.iter().map(ExidxTable::from_entry));
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    println!("{}", table);
+}
+
+fn print_addr2line(elf_file: &ElfFile, addresses: &[String], inlines: bool) {
+    let symbolizer = match Addr2Line::new(elf_file.get_dwarf_sections()) {
+        Ok(symbolizer) => symbolizer,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+    // names functions the debug info does not cover, e.g. in stripped files
+    let functions = elf_file.recover_functions().unwrap_or_default();
+
+    let mut rows = Vec::new();
+    for text in addresses {
+        let hex = text.trim_start_matches("0x").trim_start_matches("0X");
+        let address = match u64::from_str_radix(hex, 16) {
+            Ok(address) => address,
+            Err(_) => {
+                eprintln!("{}", format!("Error: invalid address '{}'.", text).red());
+                continue;
+            }
+        };
+        match symbolizer.find_frames(address, inlines) {
+            Ok(mut frames) => {
+                if let Some(frame) = frames.first_mut()
+                    && frame.function.is_none()
+                {
+                    frame.function = functions.symbolize(address);
+                }
+                rows.extend(Addr2LineTable::from_frames(address, &frames));
+            }
+            Err(e) => eprintln!("{}", e.red()),
+        }
+    }
+
+    let mut table = Table::new(rows);
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    println!("{}", table);
+}
+
+fn print_functions(elf_file: &ElfFile) {
+    let functions = match elf_file.recover_functions() {
+        Ok(functions) => functions,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+
+    println!("\n{}", ">>>>>>>>>>> Functions <<<<<<<<<<<".green());
+    let mut table = Table::new(functions.functions.iter().map(FunctionTable::from_function));
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(2..=2)).with(Alignment::right()));
+    println!("{}", table);
+}
+
+fn print_debug_info(elf_file: &ElfFile) {
+    let sections = elf_file.get_dwarf_sections();
+    let units = match sections.units() {
+        Ok(units) => units,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+
+    println!("\n{}", ">>>>>>>>>>> Compilation Units <<<<<<<<<<<".green());
+    let mut table = Table::new(units.iter().map(CompilationUnitTable::from_unit));
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    println!("{}", table);
+
+    println!(
+        "\n{}",
+        ">>>>>>>>>>> Debugging Information Entries <<<<<<<<<<<".green()
+    );
+    for unit in &units {
+        println!(
+            "\n{}",
+            format!("Compilation Unit @ offset 0x{:X}:", unit.offset).green()
+        );
+        let length_size = if unit.encoding.format_64 { 12 } else { 4 };
+        println!("  Length: 0x{:X}", unit.size - length_size);
+        println!(
+            "  Version: {} ({})",
+            unit.encoding.version,
+            if unit.encoding.format_64 {
+                "64-bit"
+            } else {
+                "32-bit"
+            }
+        );
+        println!("  Unit Type: {}", unit.unit_type_name());
+        println!("  Abbrev Offset: 0x{:X}", unit.abbrev_offset);
+        println!("  Address Size: {}", unit.encoding.address_size);
+
+        // only used to name DW_AT_decl_file and DW_AT_call_file
+        let line = unit
+            .stmt_list
+            .and_then(|offset| LineProgram::parse(&sections, offset).ok());
+        for die in unit.entries(&sections) {
+            let die = match die {
+                Ok(die) => die,
+                Err(e) => {
+                    eprintln!("{}", e.red());
+                    break;
+                }
+            };
+            println!(
+                " <{}><0x{:X}>: {}",
+                die.depth,
+                die.offset,
+                display_tag(die.tag)
+            );
+            for attr in &die.attrs {
+                println!(
+                    "    {:<28} {:<22} {}",
+                    display_attr(attr.name),
+                    display_form(attr.form),
+                    sections.describe_attr(unit, attr, line.as_ref().map(|l| &l.header))
+                );
+            }
+        }
+    }
+}
+
+/// Formats a register as `r7 (rsp)`, the way readelf does.
+fn format_register(machine: elf::types::Machine, reg: u64) -> String {
+    let name = register_name(machine, reg);
+    if name == format!("r{}", reg) {
+        name
+    } else {
+        format!("r{} ({})", reg, name)
+    }
+}
+
+fn format_cfa_instruction(
+    inst: &CallFrameInstruction,
+    elf_file: &ElfFile,
+    encoding: Encoding,
+    loc: &mut u64,
+) -> String {
+    let machine = elf_file.get_machine();
+    let be = elf_file.is_big_endian();
+    let reg = |r: u64| format_register(machine, r);
+    match *inst {
+        CallFrameInstruction::SetLoc(address) => {
+            *loc = address;
+            format!("DW_CFA_set_loc: {:016x}", address)
+        }
+        CallFrameInstruction::AdvanceLoc(delta) => {
+            *loc = loc.wrapping_add(delta);
+            format!("DW_CFA_advance_loc: {} to {:016x}", delta, loc)
+        }
+        CallFrameInstruction::DefCfa { register, offset } => {
+            format!("DW_CFA_def_cfa: {} ofs {}", reg(register), offset)
+        }
+        CallFrameInstruction::DefCfaRegister(register) => {
+            format!("DW_CFA_def_cfa_register: {}", reg(register))
+        }
+        CallFrameInstruction::DefCfaOffset(offset) => format!("DW_CFA_def_cfa_offset: {}", offset),
+        CallFrameInstruction::DefCfaExpression(expr) => format!(
+            "DW_CFA_def_cfa_expression ({})",
+            describe_expression(expr, encoding, be)
+        ),
+        CallFrameInstruction::Undefined(register) => {
+            format!("DW_CFA_undefined: {}", reg(register))
+        }
+        CallFrameInstruction::SameValue(register) => {
+            format!("DW_CFA_same_value: {}", reg(register))
+        }
+        CallFrameInstruction::Offset { register, offset } => {
+            format!("DW_CFA_offset: {} at cfa{:+}", reg(register), offset)
+        }
+        CallFrameInstruction::ValOffset { register, offset } => {
+            format!("DW_CFA_val_offset: {} is cfa{:+}", reg(register), offset)
+        }
+        CallFrameInstruction::Register { register, target } => {
+            format!("DW_CFA_register: {} in {}", reg(register), reg(target))
+        }
+        CallFrameInstruction::Expression { register, expr } => format!(
+            "DW_CFA_expression: {} ({})",
+            reg(register),
+            describe_expression(expr, encoding, be)
+        ),
+        CallFrameInstruction::ValExpression { register, expr } => format!(
+            "DW_CFA_val_expression: {} ({})",
+            reg(register),
+            describe_expression(expr, encoding, be)
+        ),
+        CallFrameInstruction::Restore(register) => format!("DW_CFA_restore: {}", reg(register)),
+        CallFrameInstruction::RememberState => "DW_CFA_remember_state".to_string(),
+        CallFrameInstruction::RestoreState => "DW_CFA_restore_state".to_string(),
+        CallFrameInstruction::ArgsSize(size) => format!("DW_CFA_GNU_args_size: {}", size),
+        CallFrameInstruction::WindowSave => "DW_CFA_GNU_window_save".to_string(),
+        CallFrameInstruction::Nop => "DW_CFA_nop".to_string(),
+    }
+}
+
+fn print_cfa_instructions(
+    elf_file: &ElfFile,
+    cfi: &CallFrameInfo,
+    cie: &Cie,
+    offset: u64,
+    bytes: &[u8],
+    mut loc: u64,
+) {
+    let encoding = Encoding {
+        version: 4,
+        format_64: false,
+        address_size: cie.address_size,
+    };
+    match cfi.instructions(cie, offset, bytes) {
+        Ok(insts) => {
+            for inst in &insts {
+                println!(
+                    "  {}",
+                    format_cfa_instruction(inst, elf_file, encoding, &mut loc)
+                );
+            }
+        }
+        Err(e) => eprintln!("{}", e.red()),
+    }
+}
+
+fn print_frame_entries(elf_file: &ElfFile, cfi: &CallFrameInfo) {
+    let machine = elf_file.get_machine();
+    // CIEs and FDEs in section order
+    let mut offsets: Vec<(u64, Option<usize>, Option<usize>)> = cfi
+        .cies
+        .iter()
+        .enumerate()
+        .map(|(i, cie)| (cie.offset, Some(i), None))
+        .chain(
+            cfi.fdes
+                .iter()
+                .enumerate()
+                .map(|(i, fde)| (fde.offset, None, Some(i))),
+        )
+        .collect();
+    offsets.sort();
+
+    for (offset, cie, fde) in offsets {
+        if let Some(i) = cie {
+            let cie = &cfi.cies[i];
+            println!(
+                "\n{}",
+                format!("{:08x} {:016x} CIE", offset, cie.length).green()
+            );
+            println!("  Version:               {}", cie.version);
+            println!("  Augmentation:          \"{}\"", cie.augmentation);
+            if cie.version >= 4 {
+                println!("  Address size:          {}", cie.address_size);
+            }
+            println!("  Code alignment factor: {}", cie.code_alignment_factor);
+            println!("  Data alignment factor: {}", cie.data_alignment_factor);
+            println!(
+                "  Return address column: {}",
+                format_register(machine, cie.return_address_register)
+            );
+            if cie.augmentation.contains('R') {
+                println!("  FDE encoding:          0x{:02x}", cie.fde_encoding);
+            }
+            if let Some(personality) = cie.personality {
+                println!("  Personality:           0x{:x}", personality);
+            }
+            if cie.augmentation.contains('L') {
+                println!("  LSDA encoding:         0x{:02x}", cie.lsda_encoding);
+            }
+            if cie.signal_frame {
+                println!("  Signal frame");
+            }
+            println!();
+            print_cfa_instructions(
+                elf_file,
+                cfi,
+                cie,
+                cie.instructions_offset,
+                cie.initial_instructions,
+                0,
+            );
+        } else if let Some(i) = fde {
+            let fde = &cfi.fdes[i];
+            println!(
+                "\n{}",
+                format!(
+                    "{:08x} {:016x} FDE cie={:08x} pc={:016x}..{:016x}",
+                    offset,
+                    fde.length,
+                    fde.cie_offset,
+                    fde.pc_begin,
+                    fde.pc_end()
+                )
+                .green()
+            );
+            if let Some(lsda) = fde.lsda {
+                println!("  LSDA: 0x{:x}", lsda);
+            }
+            if let Some(cie) = cfi.cie(fde) {
+                print_cfa_instructions(
+                    elf_file,
+                    cfi,
+                    cie,
+                    fde.instructions_offset,
+                    fde.instructions,
+                    fde.pc_begin,
+                );
+            }
+        }
+    }
+}
+
+fn format_cfa_rule(machine: elf::types::Machine, rule: &CfaRule) -> String {
+    match *rule {
+        CfaRule::RegisterOffset { register, offset } => {
+            format!("{}{:+}", register_name(machine, register), offset)
+        }
+        CfaRule::Expression(_) => "exp".to_string(),
+    }
+}
+
+fn format_register_rule(machine: elf::types::Machine, rule: Option<&RegisterRule>) -> String {
+    match rule {
+        None => "-".to_string(),
+        Some(RegisterRule::Undefined) => "u".to_string(),
+        Some(RegisterRule::SameValue) => "s".to_string(),
+        Some(RegisterRule::Offset(offset)) => format!("c{:+}", offset),
+        Some(RegisterRule::ValOffset(offset)) => format!("v{:+}", offset),
+        Some(RegisterRule::Register(register)) => register_name(machine, *register),
+        Some(RegisterRule::Expression(_)) => "exp".to_string(),
+        Some(RegisterRule::ValExpression(_)) => "vexp".to_string(),
+    }
+}
+
+fn print_unwind_rows(elf_file: &ElfFile, cfi: &CallFrameInfo) {
+    let machine = elf_file.get_machine();
+    for fde in &cfi.fdes {
+        println!(
+            "\n{}",
+            format!(
+                "{:08x} FDE cie={:08x} pc={:016x}..{:016x}",
+                fde.offset,
+                fde.cie_offset,
+                fde.pc_begin,
+                fde.pc_end()
+            )
+            .green()
+        );
+        let rows: Vec<UnwindRow> = match cfi.unwind_rows(fde) {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("{}", e.red());
+                continue;
+            }
+        };
+
+        // one column per register that has a rule in any row
+        let mut registers: Vec<u64> = rows
+            .iter()
+            .flat_map(|row| row.registers.keys().copied())
+            .collect();
+        registers.sort_unstable();
+        registers.dedup();
+
+        let ra = cfi.cie(fde).map(|cie| cie.return_address_register);
+        let mut builder = Builder::default();
+        builder.push_record(["LOC".to_string(), "CFA".to_string()].into_iter().chain(
+            registers.iter().map(|&r| match Some(r) == ra {
+                true => "ra".to_string(),
+                false => register_name(machine, r),
+            }),
+        ));
+        for row in &rows {
+            builder.push_record(
+                [
+                    format!("{:016x}", row.address),
+                    format_cfa_rule(machine, &row.cfa),
+                ]
+                .into_iter()
+                .chain(
+                    registers
+                        .iter()
+                        .map(|r| format_register_rule(machine, row.registers.get(r))),
+                ),
+            );
+        }
+        let mut table = builder.build();
+        table.with(Style::ascii());
+        table.with(Padding::new(0, 1, 0, 0));
+        table.with(Margin::new(0, 0, 1, 1));
+        println!("{}", table);
+    }
+}
+
+fn print_eh_frame_hdr(hdr: &EhFrameHdr, eh_frame: &CallFrameInfo) {
+    println!(
+        "\n{}",
+        ">>>>>>>>>>> Exception Frame Header (.eh_frame_hdr) <<<<<<<<<<<".green()
+    );
+    println!("  Address:            0x{:x}", hdr.address);
+    println!("  Version:            {}", hdr.version);
+    println!(
+        "  eh_frame_ptr:       0x{:x} (encoding 0x{:02x})",
+        hdr.eh_frame_ptr, hdr.eh_frame_ptr_encoding
+    );
+    println!(
+        "  fde_count:          {} (encoding 0x{:02x})",
+        hdr.fde_count, hdr.fde_count_encoding
+    );
+    println!("  Table encoding:     0x{:02x}", hdr.table_encoding);
+
+    let problems = hdr.verify(eh_frame);
+    if problems.is_empty() {
+        println!(
+            "  Search table:       {} entries, consistent with .eh_frame",
+            hdr.table.len()
+        );
+    } else {
+        for problem in &problems {
+            eprintln!("{}", format!("Error: .eh_frame_hdr {}.", problem).red());
+        }
+    }
+}
+
+fn print_frames(elf_file: &ElfFile, interp: bool) {
+    let mut found = false;
+    for frames in [elf_file.get_eh_frame(), elf_file.get_debug_frame()] {
+        let cfi = match frames {
+            Ok(Some(cfi)) => cfi,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("{}", e.red());
+                continue;
+            }
+        };
+        found = true;
+
+        println!(
+            "\n{}",
+            format!(
+                ">>>>>>>>>>> Call Frame Information ({}) <<<<<<<<<<<",
+                cfi.kind.section_name()
+            )
+            .green()
+        );
+        if interp {
+            print_unwind_rows(elf_file, &cfi);
+            continue;
+        }
+        print_frame_entries(elf_file, &cfi);
+
+        if cfi.kind == FrameKind::EhFrame {
+            match elf_file.get_eh_frame_hdr() {
+                Ok(Some(hdr)) => print_eh_frame_hdr(&hdr, &cfi),
+                Ok(None) => {}
+                Err(e) => eprintln!("{}", e.red()),
+            }
+        }
+    }
+    if !found {
         eprintln!(
             "{}",
-            format!("{}", elf::error::ElfError::IoError(file.unwrap_err()).red())
+            "Error: no .eh_frame or .debug_frame section found.".red()
+        );
+    }
+}
+
+/// Formats a bit count as bytes when it is byte aligned, e.g. `4` or
+/// `3 bits`.
+fn format_bits(bits: u64) -> String {
+    if bits.is_multiple_of(8) {
+        (bits / 8).to_string()
+    } else {
+        format!("{} bits", bits)
+    }
+}
+
+fn print_layout(layout: &StructLayout, cacheline: u64) {
+    println!("{} {} {{", layout.kind, layout.name);
+    let mut boundary = cacheline;
+    for member in &layout.members {
+        if member.hole_bits > 0 {
+            let hole = match member.hole_bits {
+                8 => "1 byte".to_string(),
+                bits if bits.is_multiple_of(8) => format!("{} bytes", bits / 8),
+                bits => format!("{} bits", bits),
+            };
+            println!("\n    /* XXX {} hole, try to pack */\n", hole);
+        }
+        let offset = member.byte_offset();
+        while offset >= boundary && boundary < u64::MAX {
+            println!(
+                "    /* --- cacheline {} boundary ({} bytes) --- */",
+                boundary / cacheline,
+                boundary
+            );
+            boundary = boundary.saturating_add(cacheline);
+        }
+
+        let mut name = member.name.clone().unwrap_or_default();
+        if let Some(bit_size) = member.bit_size {
+            name = format!("{}:{}", name, bit_size);
+        }
+        if member.is_base {
+            name = "<ancestor>".to_string();
+        }
+        let position = match member.bit_size {
+            Some(_) => format!(
+                "{:>5}:{:>2} {:>4}",
+                offset,
+                member.bit_offset % 8,
+                member.size
+            ),
+            None => format!("{:>5} {:>5}", offset, member.size),
+        };
+        println!(
+            "    {:<28} {:<24} /* {} */",
+            member.type_name,
+            format!("{};", name),
+            position
         );
+
+        let end = offset.saturating_add(member.size);
+        if offset < boundary && end > boundary {
+            println!(
+                "    /* --- cacheline {} boundary ({} bytes) was {} bytes ago --- */",
+                boundary / cacheline,
+                boundary,
+                end - boundary
+            );
+            // a member may span several cache lines
+            while boundary < end {
+                boundary = boundary.saturating_add(cacheline);
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "    /* size: {}, cachelines: {}, members: {} */",
+        layout.size,
+        layout.size.div_ceil(cacheline),
+        layout.members.len()
+    );
+    if layout.holes() > 0 {
+        println!(
+            "    /* sum members: {}, holes: {}, sum holes: {} */",
+            layout.sum_members(),
+            layout.holes(),
+            format_bits(layout.sum_hole_bits())
+        );
+    }
+    if layout.padding_bits > 0 {
+        println!("    /* padding: {} */", format_bits(layout.padding_bits));
+    }
+    if !layout.size.is_multiple_of(cacheline) && layout.size > cacheline {
+        println!(
+            "    /* last cacheline: {} bytes */",
+            layout.size % cacheline
+        );
+    }
+    println!("}};\n");
+}
+
+fn print_struct_layouts(elf_file: &ElfFile, names: &[String], cacheline: u64) {
+    let index = match TypeIndex::new(elf_file.get_dwarf_sections()) {
+        Ok(index) => index,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+
+    let layouts: Vec<StructLayout> = index
+        .struct_layouts()
+        .into_iter()
+        .filter(|layout| names.is_empty() || names.contains(&layout.name))
+        .collect();
+    if layouts.is_empty() {
+        eprintln!(
+            "{}",
+            "Error: no matching struct found in the debug information.".red()
+        );
+        return;
+    }
+
+    println!("\n{}\n", ">>>>>>>>>>> Struct Layouts <<<<<<<<<<<".green());
+    for layout in &layouts {
+        print_layout(layout, cacheline);
+    }
+}
+
+fn print_debug_size(elf_file: &ElfFile) {
+    let sizes = match elf_file.debug_sizes() {
+        Ok(sizes) => sizes,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+    if sizes.sections.is_empty() {
+        eprintln!("{}", "Error: no debug sections found.".red());
         return;
     }
 
+    println!(
+        "\n{}",
+        ">>>>>>>>>>> Debug Section Sizes <<<<<<<<<<<".green()
+    );
+    let total = sizes.total();
+    let mut table = Table::new(
+        sizes
+            .sections
+            .iter()
+            .map(|section| DebugSectionSizeTable::from_section(section, total)),
+    );
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(1..=3)).with(Alignment::right()));
+    println!("{}", table);
+    println!(
+        "  Total: {} bytes ({} bytes uncompressed)",
+        total,
+        sizes.uncompressed_total()
+    );
+
+    if sizes.units.is_empty() {
+        return;
+    }
+    // units and directories are measured against the uncompressed
+    // .debug_info + .debug_line
+    let unit_total = sizes
+        .units
+        .iter()
+        .fold(0u64, |total, u| total.saturating_add(u.total()));
+
+    println!(
+        "\n{}",
+        ">>>>>>>>>>> Size per Compilation Unit <<<<<<<<<<<".green()
+    );
+    let mut table = Table::new(
+        sizes
+            .units
+            .iter()
+            .map(|unit| UnitSizeTable::from_unit(unit, unit_total)),
+    );
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(2..=5)).with(Alignment::right()));
+    println!("{}", table);
+
+    println!(
+        "\n{}",
+        ">>>>>>>>>>> Size per Source Directory <<<<<<<<<<<".green()
+    );
+    let mut table = Table::new(
+        sizes
+            .directories()
+            .iter()
+            .map(|dir| DirectorySizeTable::from_directory(dir, unit_total)),
+    );
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(1..=5)).with(Alignment::right()));
+    println!("{}", table);
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, elf::error::ElfError> {
     let mut buffer = Vec::new();
-    let mut reader = BufReader::new(file.unwrap());
-    let temp_res = reader.read_to_end(&mut buffer);
-    if temp_res.is_err() {
-        eprintln!(
+    BufReader::new(fs::File::open(path)?).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn print_dynamic_info(elf_file: &ElfFile) {
+    let info = match elf_file.get_dynamic_info() {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+
+    println!("\n{}", ">>>>>>>>>>> Dynamic Linking <<<<<<<<<<<".green());
+    let mut builder = Builder::default();
+    builder.push_record(["Field", "Value"]);
+    let mut push = |field: &str, value: Option<&String>| {
+        if let Some(value) = value {
+            builder.push_record([field, value.as_str()]);
+        }
+    };
+    push("INTERP", elf_file.get_interpreter().as_ref());
+    push("SONAME", info.soname.as_ref());
+    push("RPATH", info.rpath.as_ref());
+    push("RUNPATH", info.runpath.as_ref());
+    for needed in &info.needed {
+        push("NEEDED", Some(needed));
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    println!("{}", table);
+}
+
+/// Applies the edits and returns whether it succeeded.
+fn run_edit(args: &EditArgs) -> bool {
+    let result = read_file(&args.file).and_then(|buffer| {
+        let elf_file = ElfFile::from_bytes(&buffer)?;
+        let edits = args.edits();
+        if edits.is_empty() {
+            return Ok(elf_file);
+        }
+        let data = elf_file.edit_dynamic(&edits)?;
+        write_output(
+            &args.file,
+            args.output.as_ref().unwrap_or(&args.file),
+            &data,
+        )?;
+        ElfFile::from_bytes(&data)
+    });
+    match result {
+        Ok(elf_file) => {
+            print_dynamic_info(&elf_file);
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e.red());
+            false
+        }
+    }
+}
+
+/// Writes `data` to `output`, giving a new file the permissions of `input`.
+fn write_output(input: &Path, output: &Path, data: &[u8]) -> Result<(), elf::error::ElfError> {
+    fs::write(output, data)?;
+    if output != input {
+        fs::set_permissions(output, fs::metadata(input)?.permissions())?;
+    }
+    Ok(())
+}
+
+/// Strips the file and returns whether it succeeded.
+fn run_strip(args: &StripArgs) -> bool {
+    let buffer = match read_file(&args.file) {
+        Ok(buffer) => buffer,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return false;
+        }
+    };
+    let elf_file = match ElfFile::from_bytes(&buffer) {
+        Ok(elf_file) => elf_file,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return false;
+        }
+    };
+    let options = StripOptions {
+        keep: args.keep_section.clone(),
+        remove: args.remove_section.clone(),
+        debuglink: args
+            .debug_file
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned()),
+    };
+    let result = match elf_file.strip(&options) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return false;
+        }
+    };
+
+    let output = args.output.as_ref().unwrap_or(&args.file);
+    let written = write_output(&args.file, output, &result.stripped).and_then(|_| {
+        match (&args.debug_file, &result.debug) {
+            (Some(path), Some(debug)) => fs::write(path, debug).map_err(Into::into),
+            _ => Ok(()),
+        }
+    });
+    if let Err(e) = written {
+        eprintln!("{}", e.red());
+        return false;
+    }
+
+    println!("\n{}", ">>>>>>>>>>> Removed Sections <<<<<<<<<<<".green());
+    let mut table = Table::new(
+        result
+            .removed
+            .iter()
+            .map(|&index| RemovedSectionTable::from_section(&elf_file, index)),
+    );
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(3..=3)).with(Alignment::right()));
+    println!("{}", table);
+    println!("{} -> {} bytes", buffer.len(), result.stripped.len());
+    if let (Some(path), Some(debug)) = (&args.debug_file, &result.debug) {
+        println!("debug file: {} ({} bytes)", path.display(), debug.len());
+    }
+    true
+}
+
+fn print_chunks(title: &str, chunks: &[Chunk]) {
+    println!("\n{}", format!(">>>>>>>>>>> {} <<<<<<<<<<<", title).green());
+    let mut table = Table::new(chunks.iter().map(ChunkTable::from_chunk));
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(2..=2)).with(Alignment::right()));
+    println!("{}", table);
+}
+
+/// Writes the image and returns whether it succeeded.
+fn run_export(args: &ExportArgs) -> bool {
+    let result = read_file(&args.file).and_then(|buffer| {
+        let elf_file = ElfFile::from_bytes(&buffer)?;
+        let chunks = elf_file.load_image()?;
+        let data = match args.format {
+            ImageFormat::Binary => {
+                let gap_fill = u8::try_from(args.gap_fill).map_err(|_| {
+                    elf::error::ElfError::ParseError("the gap fill must be a byte.".to_string())
+                })?;
+                image::to_binary(&chunks, gap_fill)?
+            }
+            ImageFormat::Ihex => ihex::write(&chunks, Some(elf_file.e_entry))?.into_bytes(),
+            ImageFormat::Srec => {
+                let header = args
+                    .output
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                srec::write(&chunks, &header, elf_file.e_entry)?.into_bytes()
+            }
+            ImageFormat::Uf2 => {
+                let page_size = u32::try_from(args.page_size).map_err(|_| {
+                    elf::error::ElfError::ParseError("the page size is too large.".to_string())
+                })?;
+                let options = Uf2Options {
+                    family_id: args.family,
+                    page_size,
+                    flash: args.flash_start.zip(args.flash_size),
+                };
+                uf2::write(&chunks, &options)?
+            }
+        };
+        fs::write(&args.output, &data)?;
+        Ok((chunks, data.len()))
+    });
+    match result {
+        Ok((chunks, size)) => {
+            print_chunks("Exported Segments", &chunks);
+            if args.format == ImageFormat::Uf2 {
+                let family = match args.family {
+                    Some(id) => match uf2::family_name(id) {
+                        Some(name) => format!("family 0x{:08X} ({})", id, name),
+                        None => format!("family 0x{:08X}", id),
+                    },
+                    None => "no family ID".to_string(),
+                };
+                println!("{} UF2 blocks, {}", size / uf2::BLOCK_SIZE, family);
+            }
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e.red());
+            false
+        }
+    }
+}
+
+/// Writes the ELF file and returns whether it succeeded.
+fn run_import(args: &ImportArgs) -> bool {
+    let result = read_file(&args.file).and_then(|buffer| {
+        let format = args.format.unwrap_or(match buffer.first() {
+            Some(b':') => ImageFormat::Ihex,
+            Some(b'S') if buffer.get(1).is_some_and(u8::is_ascii_digit) => ImageFormat::Srec,
+            _ if buffer.starts_with(b"UF2\n") => ImageFormat::Uf2,
+            _ => ImageFormat::Binary,
+        });
+        let text = || {
+            String::from_utf8(buffer.clone())
+                .map_err(|_| elf::error::ElfError::ParseError("the image is not text.".to_string()))
+        };
+        let (chunks, start) = match format {
+            ImageFormat::Binary => {
+                let chunk = Chunk {
+                    address: args.base,
+                    data: buffer.clone(),
+                };
+                (image::normalize(vec![chunk])?, None)
+            }
+            ImageFormat::Ihex => ihex::parse(&text()?)?,
+            ImageFormat::Srec => srec::parse(&text()?)?,
+            ImageFormat::Uf2 => (uf2::parse(&buffer)?.0, None),
+        };
+        let machine = u16::try_from(args.machine).map_err(|_| {
+            elf::error::ElfError::ParseError("e_machine must fit in 16 bits.".to_string())
+        })?;
+        let options = ElfImageOptions {
+            machine,
+            entry: args.entry.or(start).unwrap_or(0),
+            is_64: args.elf64,
+            big_endian: args.big_endian,
+        };
+        fs::write(&args.output, image::build_elf(&chunks, &options)?)?;
+        Ok(chunks)
+    });
+    match result {
+        Ok(chunks) => {
+            print_chunks("Imported Segments", &chunks);
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e.red());
+            false
+        }
+    }
+}
+
+/// Prints the region usage and returns whether every region is within the
+/// threshold.
+fn run_memory(args: &MemoryArgs) -> bool {
+    let result = read_file(&args.file).and_then(|buffer| {
+        let elf_file = ElfFile::from_bytes(&buffer)?;
+        let text = fs::read_to_string(&args.regions)?;
+        let regions = memory::parse_regions(&text)?;
+        Ok((elf_file, regions))
+    });
+    let (elf_file, regions) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return false;
+        }
+    };
+    let usage = elf_file.memory_usage(&regions);
+
+    println!("\n{}", ">>>>>>>>>>> Memory Regions <<<<<<<<<<<".green());
+    let mut table = Table::new(usage.regions.iter().map(RegionUsageTable::from_usage));
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(2..=5)).with(Alignment::right()));
+    println!("{}", table);
+
+    println!("\n{}", ">>>>>>>>>>> Section Placement <<<<<<<<<<<".green());
+    let mut table =
+        Table::new(usage.sections.iter().map(|placement| {
+            SectionPlacementTable::from_placement(&elf_file, placement, &regions)
+        }));
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(1..=1)).with(Alignment::right()));
+    println!("{}", table);
+
+    for placement in &usage.sections {
+        let name = elf_file
+            .get_section_name(placement.index)
+            .unwrap_or_default();
+        if placement.vma_region.is_none()
+            || (placement.lma.is_some() && placement.lma_region.is_none())
+        {
+            println!(
+                "{}",
+                format!("warning: {} lies outside every memory region.", name).yellow()
+            );
+        }
+    }
+    let mut within = true;
+    for region in &usage.regions {
+        if region.percent() > args.threshold {
+            eprintln!(
+                "{}",
+                format!(
+                    "error: region {} is {:.2}% full ({} of {} bytes), above the {}% threshold.",
+                    region.region.name,
+                    region.percent(),
+                    region.used,
+                    region.region.length,
+                    args.threshold
+                )
+                .red()
+            );
+            within = false;
+        }
+    }
+    within
+}
+
+fn print_bloat_groups(
+    title: &str,
+    column: &str,
+    report: &BloatReport,
+    groups: &[BloatGroup],
+    top: usize,
+) {
+    println!("\n{}", format!(">>>>>>>>>>> {} <<<<<<<<<<<", title).green());
+    let mut builder = Builder::default();
+    builder.push_record([column, "Size", "% of .text", "Symbols"]);
+    for group in groups.iter().take(top) {
+        builder.push_record([
+            group.name.clone(),
+            group.size.to_string(),
+            report
+                .percent_of_text(group.size)
+                .map_or("-".to_string(), |percent| format!("{:.1}%", percent)),
+            group.symbols.to_string(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(1..=3)).with(Alignment::right()));
+    println!("{}", table);
+}
+
+fn bloat_json(elf_file: &ElfFile, report: &BloatReport, top: usize) -> serde_json::Value {
+    let groups = |groups: &[BloatGroup]| {
+        groups
+            .iter()
+            .take(top)
+            .map(|group| {
+                serde_json::json!({
+                    "name": group.name,
+                    "size": group.size,
+                    "percent_of_text": report.percent_of_text(group.size),
+                    "symbols": group.symbols,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    let symbols = report.symbols.iter().take(top).map(|symbol| {
+        serde_json::json!({
+            "name": symbol.name,
+            "demangled": symbol.demangled,
+            "section": elf_file.get_section_name(symbol.section),
+            "address": symbol.address,
+            "size": symbol.size,
+            "percent_of_text": report.percent_of_text(symbol.size),
+            "file": symbol.file,
+            "namespace": symbol.namespace,
+        })
+    });
+    let sections = report.sections.iter().take(top).map(|section| {
+        serde_json::json!({
+            "name": elf_file.get_section_name(section.index),
+            "size": section.size,
+            "percent_of_text": report.percent_of_text(section.size),
+            "symbol_size": section.symbol_size,
+            "symbols": section.symbols,
+        })
+    });
+    serde_json::json!({
+        "text_size": report.text_size,
+        "symbols": symbols.collect::<Vec<_>>(),
+        "sections": sections.collect::<Vec<_>>(),
+        "files": groups(&report.files),
+        "namespaces": groups(&report.namespaces),
+    })
+}
+
+fn run_bloat(args: &BloatArgs) {
+    let result = read_file(&args.file).and_then(|buffer| {
+        let elf_file = ElfFile::from_bytes(&buffer)?;
+        let report = elf_file.bloat_report()?;
+        Ok((elf_file, report))
+    });
+    let (elf_file, report) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+    if args.json {
+        let json = bloat_json(&elf_file, &report, args.top);
+        println!(
             "{}",
-            format!(
+            serde_json::to_string_pretty(&json).unwrap_or_default()
+        );
+        return;
+    }
+
+    println!("\n{}", ">>>>>>>>>>> Largest Symbols <<<<<<<<<<<".green());
+    let mut table = Table::new(
+        report
+            .symbols
+            .iter()
+            .take(args.top)
+            .map(|symbol| BloatSymbolTable::from_symbol(&elf_file, symbol, report.text_size)),
+    );
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(2..=3)).with(Alignment::right()));
+    println!("{}", table);
+
+    println!("\n{}", ">>>>>>>>>>> Sections <<<<<<<<<<<".green());
+    let mut table =
+        Table::new(
+            report.sections.iter().take(args.top).map(|section| {
+                BloatSectionTable::from_section(&elf_file, section, report.text_size)
+            }),
+        );
+    table.with(Style::ascii());
+    table.with(Padding::new(0, 1, 0, 0));
+    table.with(Margin::new(0, 0, 1, 1));
+    table.with(Modify::new(Columns::new(1..=4)).with(Alignment::right()));
+    println!("{}", table);
+
+    print_bloat_groups("Source Files", "File", &report, &report.files, args.top);
+    print_bloat_groups(
+        "Namespaces",
+        "Namespace",
+        &report,
+        &report.namespaces,
+        args.top,
+    );
+}
+
+fn size_diff_json(diff: &SizeDiff, top: usize) -> serde_json::Value {
+    let change = |change: &SizeChange| {
+        serde_json::json!({
+            "name": change.name,
+            "change": change.kind().name(),
+            "old": change.old,
+            "new": change.new,
+            "delta": change.delta(),
+        })
+    };
+    serde_json::json!({
+        "old_total": diff.old_total,
+        "new_total": diff.new_total,
+        "delta": diff.total_delta(),
+        "sections": diff.sections.iter().map(change).collect::<Vec<_>>(),
+        "symbols": diff.symbols.iter().take(top).map(change).collect::<Vec<_>>(),
+    })
+}
+
+/// Prints the size differences and returns whether the growth is within the
+/// budget.
+fn run_diff_size(args: &DiffSizeArgs) -> bool {
+    let result = read_file(&args.old).and_then(|old| {
+        let new = read_file(&args.new)?;
+        ElfFile::from_bytes(&old)?.diff_sizes(&ElfFile::from_bytes(&new)?)
+    });
+    let diff = match result {
+        Ok(diff) => diff,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return false;
+        }
+    };
+
+    if args.json {
+        let json = size_diff_json(&diff, args.top);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json).unwrap_or_default()
+        );
+    } else {
+        println!("\n{}", ">>>>>>>>>>> Section Sizes <<<<<<<<<<<".green());
+        let mut table = Table::new(
+            diff.sections
+                .iter()
+                .map(SectionSizeChangeTable::from_change),
+        );
+        table.with(Style::ascii());
+        table.with(Padding::new(0, 1, 0, 0));
+        table.with(Margin::new(0, 0, 1, 1));
+        table.with(Modify::new(Columns::new(1..=4)).with(Alignment::right()));
+        println!("{}", table);
+
+        println!("\n{}", ">>>>>>>>>>> Symbol Changes <<<<<<<<<<<".green());
+        let mut table = Table::new(
+            diff.symbols
+                .iter()
+                .take(args.top)
+                .map(SymbolSizeChangeTable::from_change),
+        );
+        table.with(Style::ascii());
+        table.with(Padding::new(0, 1, 0, 0));
+        table.with(Margin::new(0, 0, 1, 1));
+        table.with(Modify::new(Columns::new(2..=4)).with(Alignment::right()));
+        println!("{}", table);
+
+        println!(
+            "{} added, {} removed, {} grown, {} shrunk",
+            diff.count(SizeChangeKind::Added),
+            diff.count(SizeChangeKind::Removed),
+            diff.count(SizeChangeKind::Grown),
+            diff.count(SizeChangeKind::Shrunk)
+        );
+        println!(
+            "Total: {} -> {} bytes ({:+})",
+            diff.old_total,
+            diff.new_total,
+            diff.total_delta()
+        );
+    }
+
+    match args.budget {
+        Some(budget) if u64::try_from(diff.total_delta()).is_ok_and(|grown| grown > budget) => {
+            eprintln!(
                 "{}",
-                elf::error::ElfError::IoError(temp_res.unwrap_err()).red()
-            )
+                format!(
+                    "error: the allocated sections grew by {} bytes, above the budget of {} bytes.",
+                    diff.total_delta(),
+                    budget
+                )
+                .red()
+            );
+            false
+        }
+        _ => true,
+    }
+}
+
+fn diff_json(diffs: &[ScopeDiff]) -> serde_json::Value {
+    let diffs: Vec<serde_json::Value> = diffs
+        .iter()
+        .map(|diff| {
+            let status = match diff.status {
+                ScopeStatus::Added => "added",
+                ScopeStatus::Removed => "removed",
+                ScopeStatus::Changed => "changed",
+            };
+            let fields: Vec<serde_json::Value> = diff
+                .fields
+                .iter()
+                .map(|field| serde_json::json!({ "field": field.field, "old": field.old, "new": field.new }))
+                .collect();
+            serde_json::json!({ "scope": diff.scope, "status": status, "fields": fields })
+        })
+        .collect();
+    serde_json::Value::Array(diffs)
+}
+
+/// Prints the structural differences and returns the exit code of diff(1):
+/// 0 if the files match, 1 if they differ and 2 on error.
+fn run_diff(args: &DiffArgs) -> i32 {
+    let mut options = DiffOptions {
+        ignore: args.ignore.clone(),
+    };
+    if args.ignore_volatile {
+        options
+            .ignore
+            .extend(diff::VOLATILE.iter().map(|pattern| pattern.to_string()));
+    }
+    let result = read_file(&args.old).and_then(|old| {
+        let new = read_file(&args.new)?;
+        diff::diff_files(
+            &ElfFile::from_bytes(&old)?,
+            &ElfFile::from_bytes(&new)?,
+            &options,
+        )
+    });
+    let diffs = match result {
+        Ok(diffs) => diffs,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return 2;
+        }
+    };
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&diff_json(&diffs)).unwrap_or_default()
+        );
+    } else if !diffs.is_empty() {
+        println!("--- {}", args.old.display());
+        println!("+++ {}", args.new.display());
+        for diff in &diffs {
+            let status = match diff.status {
+                ScopeStatus::Added => " (added)",
+                ScopeStatus::Removed => " (removed)",
+                ScopeStatus::Changed => "",
+            };
+            println!("{}", format!("@@ {}{} @@", diff.scope, status).cyan());
+            for field in &diff.fields {
+                if let Some(old) = &field.old {
+                    println!("{}", format!("-{}: {}", field.field, old).red());
+                }
+                if let Some(new) = &field.new {
+                    println!("{}", format!("+{}: {}", field.field, new).green());
+                }
+            }
+        }
+    }
+    if diffs.is_empty() { 0 } else { 1 }
+}
+
+fn abi_json(report: &AbiReport) -> serde_json::Value {
+    let changes: Vec<serde_json::Value> = report
+        .changes
+        .iter()
+        .map(|change| {
+            serde_json::json!({
+                "change": change.kind.name(),
+                "breaking": change.kind.is_breaking(),
+                "subject": change.subject,
+                "old": change.old,
+                "new": change.new,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "result": if report.is_breaking() { "breaking" } else { "compatible" },
+        "changes": changes,
+    })
+}
+
+/// Prints the ABI changes and returns whether the new library is compatible.
+fn run_abi_check(args: &AbiCheckArgs) -> bool {
+    let result = read_file(&args.old).and_then(|old| {
+        let new = read_file(&args.new)?;
+        ElfFile::from_bytes(&old)?.check_abi(&ElfFile::from_bytes(&new)?)
+    });
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return false;
+        }
+    };
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&abi_json(&report)).unwrap_or_default()
+        );
+    } else {
+        if !report.changes.is_empty() {
+            println!("\n{}", ">>>>>>>>>>> ABI Changes <<<<<<<<<<<".green());
+            let mut table = Table::new(report.changes.iter().map(AbiChangeTable::from_change));
+            table.with(Style::ascii());
+            table.with(Padding::new(0, 1, 0, 0));
+            table.with(Margin::new(0, 0, 1, 1));
+            println!("{}", table);
+        }
+        let summary = format!(
+            "{} breaking, {} compatible changes",
+            report.count(true),
+            report.count(false)
+        );
+        if report.is_breaking() {
+            println!("{}", format!("Result: breaking ({})", summary).red());
+        } else {
+            println!("{}", format!("Result: compatible ({})", summary).green());
+        }
+    }
+    !report.is_breaking()
+}
+
+fn print_dependency(tree: &DependencyTree, index: usize) {
+    let node = &tree.nodes[index];
+    let indent = "    ".repeat(node.depth);
+    match (&node.path, node.duplicate_of) {
+        (_, Some(_)) => println!("{}{} (already loaded)", indent, node.name),
+        (Some(path), None) => match node.source {
+            Some(source) => println!(
+                "{}{} => {} [{}]",
+                indent,
+                node.name,
+                path.display(),
+                source.name()
+            ),
+            None => println!("{}{}", indent, node.name),
+        },
+        (None, None) => println!("{}", format!("{}{} => not found", indent, node.name).red()),
+    }
+    for (path, reason) in &node.skipped {
+        println!(
+            "{}",
+            format!("{}    skipped {}: {}", indent, path.display(), reason).yellow()
+        );
+    }
+    for child in tree.children(index) {
+        print_dependency(tree, child);
+    }
+}
+
+/// Prints the dependency tree and returns whether every library and
+/// non-weak symbol was found.
+fn run_deps(args: &DepsArgs) -> bool {
+    let library_path = args.library_path.clone().or_else(|| {
+        if args.sysroot.is_none() {
+            std::env::var("LD_LIBRARY_PATH").ok()
+        } else {
+            None
+        }
+    });
+    let options = ResolveOptions {
+        sysroot: args.sysroot.clone(),
+        library_path: library_path
+            .unwrap_or_default()
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(str::to_string)
+            .collect(),
+        ld_so_conf: !args.no_ld_so_conf,
+    };
+    let result = read_file(&args.file)
+        .and_then(|data| ElfFile::from_bytes(&data)?.resolve_dependencies(&args.file, &options));
+    let tree = match result {
+        Ok(tree) => tree,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return false;
+        }
+    };
+
+    println!("\n{}", ">>>>>>>>>>> Dependencies <<<<<<<<<<<".green());
+    if let Some(interpreter) = &tree.interpreter {
+        match &tree.interpreter_path {
+            Some(path) => println!("Interpreter: {} => {}", interpreter, path.display()),
+            None => println!(
+                "{}",
+                format!("Interpreter: {} => not found", interpreter).red()
+            ),
+        }
+    }
+    print_dependency(&tree, 0);
+
+    let symbols: Vec<_> = tree
+        .symbols
+        .iter()
+        .filter(|symbol| args.symbols || symbol.provider.is_none() && !symbol.weak)
+        .collect();
+    if !symbols.is_empty() {
+        let title = if args.symbols {
+            "Symbol Bindings"
+        } else {
+            "Unresolved Symbols"
+        };
+        println!("\n{}", format!(">>>>>>>>>>> {} <<<<<<<<<<<", title).green());
+        let mut builder = Builder::default();
+        builder.push_record(["Object", "Symbol", "Version", "Provided By"]);
+        for symbol in symbols {
+            let provider = match symbol.provider {
+                Some(provider) => tree.nodes[provider].name.clone(),
+                None if symbol.weak => "- (weak)".to_string(),
+                None => "not found".to_string(),
+            };
+            builder.push_record([
+                tree.nodes[symbol.object].name.clone(),
+                symbol.name.clone(),
+                symbol.version.clone().unwrap_or_else(|| "-".to_string()),
+                provider,
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::ascii());
+        table.with(Padding::new(0, 1, 0, 0));
+        table.with(Margin::new(0, 0, 1, 1));
+        println!("{}", table);
+    }
+
+    let missing = tree.missing().count();
+    let unresolved = tree.unresolved().count();
+    let interpreter_missing = tree.interpreter.is_some() && tree.interpreter_path.is_none();
+    println!(
+        "{} libraries loaded, {} missing, {} unresolved symbols",
+        tree.nodes.iter().filter(|node| node.path.is_some()).count() - 1,
+        missing,
+        unresolved
+    );
+    missing == 0 && unresolved == 0 && !interpreter_missing
+}
+
+/// Prints the unresolved references and duplicate definitions of a set of
+/// files and returns whether there are none.
+fn run_link_check(args: &LinkCheckArgs) -> bool {
+    let result = args
+        .files
+        .iter()
+        .map(|path| read_file(path).and_then(|data| ElfFile::from_bytes(&data)))
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(|files| linkcheck::check_links(&files));
+    let check = match result {
+        Ok(check) => check,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return false;
+        }
+    };
+    let file_name = |index: usize| args.files[index].display().to_string();
+
+    let mut undefined: Vec<_> = check.undefined.iter().collect();
+    if args.weak {
+        undefined.extend(&check.weak_undefined);
+    }
+    if !undefined.is_empty() {
+        println!("\n{}", ">>>>>>>>>>> Undefined Symbols <<<<<<<<<<<".green());
+        let mut builder = Builder::default();
+        builder.push_record(["File", "Symbol", "Binding"]);
+        for symbol in undefined {
+            builder.push_record([
+                file_name(symbol.file),
+                symbol.display_name(),
+                symbol.binding.to_string(),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::ascii());
+        table.with(Padding::new(0, 1, 0, 0));
+        table.with(Margin::new(0, 0, 1, 1));
+        println!("{}", table);
+    }
+
+    if !check.duplicates.is_empty() {
+        println!(
+            "\n{}",
+            ">>>>>>>>>>> Duplicate Definitions <<<<<<<<<<<".green()
         );
+        let mut builder = Builder::default();
+        builder.push_record(["Symbol", "Version", "Defined In"]);
+        for duplicate in &check.duplicates {
+            let files: Vec<String> = duplicate
+                .files
+                .iter()
+                .map(|&index| file_name(index))
+                .collect();
+            builder.push_record([
+                duplicate.name.clone(),
+                duplicate.version.clone().unwrap_or_else(|| "-".to_string()),
+                files.join(", "),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::ascii());
+        table.with(Padding::new(0, 1, 0, 0));
+        table.with(Margin::new(0, 0, 1, 1));
+        println!("{}", table);
+    }
+
+    let summary = format!(
+        "{} undefined, {} weak undefined, {} duplicate definitions",
+        check.undefined.len(),
+        check.weak_undefined.len(),
+        check.duplicates.len()
+    );
+    if check.is_clean() {
+        println!("{}", summary.green());
+    } else {
+        println!("{}", summary.red());
+    }
+    check.is_clean()
+}
+
+fn main() {
+    let cli = Usage::parse();
+    if let Some(command) = &cli.command {
+        match command {
+            Command::Edit(args) => {
+                if !run_edit(args) {
+                    std::process::exit(1);
+                }
+            }
+            Command::Strip(args) => {
+                if !run_strip(args) {
+                    std::process::exit(1);
+                }
+            }
+            Command::Export(args) => {
+                if !run_export(args) {
+                    std::process::exit(1);
+                }
+            }
+            Command::Import(args) => {
+                if !run_import(args) {
+                    std::process::exit(1);
+                }
+            }
+            Command::Memory(args) => {
+                if !run_memory(args) {
+                    std::process::exit(1);
+                }
+            }
+            Command::Bloat(args) => run_bloat(args),
+            Command::DiffSize(args) => {
+                if !run_diff_size(args) {
+                    std::process::exit(1);
+                }
+            }
+            Command::Diff(args) => std::process::exit(run_diff(args)),
+            Command::AbiCheck(args) => {
+                if !run_abi_check(args) {
+                    std::process::exit(1);
+                }
+            }
+            Command::Deps(args) => {
+                if !run_deps(args) {
+                    std::process::exit(1);
+                }
+            }
+            Command::LinkCheck(args) => {
+                if !run_link_check(args) {
+                    std::process::exit(1);
+                }
+            }
+        }
+        return;
+    }
+
+    if cli.file.is_none() {
+        eprintln!("{}", "Error: the parsed file must be specified.".red());
         return;
     }
+    let buffer = match read_file(&cli.file.unwrap()) {
+        Ok(buffer) => buffer,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
 
     // output content
-    let elf_file = ElfFile::from_bytes(&buffer);
-    if elf_file.is_err() {
-        eprintln!("{}", format!("{}", elf_file.unwrap_err()).red());
+    let elf_file = match ElfFile::from_bytes(&buffer) {
+        Ok(elf_file) => elf_file,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            return;
+        }
+    };
+
+    if !cli.addr2line.is_empty() {
+        print_addr2line(&elf_file, &cli.addr2line, cli.inlines);
+        return;
+    }
+
+    if cli.functions {
+        print_functions(&elf_file);
+        return;
+    }
+
+    if cli.unwind {
+        print_unwind(&elf_file);
+        return;
+    }
+
+    if cli.debug_size {
+        print_debug_size(&elf_file);
+        return;
+    }
+
+    if let Some(names) = &cli.struct_layout {
+        print_struct_layouts(&elf_file, names, cli.cacheline_size.max(1));
+        return;
+    }
+
+    if let Some(kind) = cli.debug_dump {
+        match kind {
+            DebugDump::Info => print_debug_info(&elf_file),
+            DebugDump::Frames => print_frames(&elf_file, false),
+            DebugDump::FramesInterp => print_frames(&elf_file, true),
+        }
         return;
     }
-    let elf_file = elf_file.unwrap();
 
     if cli.all {
         print_brief(&elf_file);
         print_program(&elf_file);
         print_section(&elf_file);
+        print_arch_specific(&elf_file);
         return;
     }
 
-    if !cli.section && !cli.program {
+    if !cli.section && !cli.program && !cli.arch_specific {
         print_brief(&elf_file);
         return;
     }
@@ -208,4 +2389,8 @@ fn main() {
     if cli.section {
         print_section(&elf_file);
     }
+
+    if cli.arch_specific {
+        print_arch_specific(&elf_file);
+    }
 }
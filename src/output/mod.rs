@@ -1,4 +1,15 @@
+use crate::elf::abi::AbiChange;
+use crate::elf::attributes::Attribute;
+use crate::elf::bloat::{BloatSection, BloatSymbol};
+use crate::elf::dwarf::addr2line::Frame;
+use crate::elf::dwarf::info::Unit;
+use crate::elf::dwarf::sizes::{DirectorySize, SectionSize, UnitSize};
+use crate::elf::ehabi::{ExidxEntry, ExidxUnwind, describe_opcodes};
+use crate::elf::functions::RecoveredFunction;
 use crate::elf::header::SectionHeader;
+use crate::elf::image::Chunk;
+use crate::elf::memory::{MemoryRegion, RegionUsage, SectionPlacement};
+use crate::elf::sizediff::SizeChange;
 
 use super::elf::ElfFile;
 use super::elf::header::ProgramHeader;
@@ -75,7 +86,7 @@ impl ElfHeaderTable {
             entry_point: format!("0x{:X}", elf_file.e_entry),
             pragram_header_offset: format!("0x{:X}", elf_file.e_phoff),
             section_header_offset: format!("0x{:X}", elf_file.e_shoff),
-            flags: format!("0x{:X}", elf_file.e_flags),
+            flags: elf_file.get_flags().to_string(),
             elf_header_size: format!("{} bytes", elf_file.e_ehsize),
             ph_ensize: format!("0x{:X} bytes", elf_file.e_phentsize),
             ph_cnt: elf_file.e_phnum,
@@ -146,18 +157,18 @@ impl ProgramHeaderTable2 {
 }
 
 impl ProgramHeaderTable {
-    pub fn from_ph(ndx: usize, ph: &ProgramHeader) -> Self {
+    pub fn from_ph(ndx: usize, ph: &ProgramHeader, elf_file: &ElfFile) -> Self {
         match ph {
             ProgramHeader::Elf32(p) => Self {
                 index: ndx,
-                tp: ph.get_type().to_string(),
+                tp: ph.get_type_name(elf_file.get_machine(), elf_file.get_osabi()),
                 flags: ph.get_flags().to_string(),
                 offset: format!("0x{:016X}", p.p_offset),
             },
 
             ProgramHeader::Elf64(p) => Self {
                 index: ndx,
-                tp: ph.get_type().to_string(),
+                tp: ph.get_type_name(elf_file.get_machine(), elf_file.get_osabi()),
                 flags: ph.get_flags().to_string(),
                 offset: format!("0x{:016X}", p.p_offset),
             },
@@ -179,6 +190,8 @@ pub struct SectionHeaderTable {
     entsize: String,
     #[tabled(rename = "Sz")]
     size: String,
+    #[tabled(rename = "Compression")]
+    compression: String,
 }
 
 #[derive(Debug, Tabled)]
@@ -199,59 +212,68 @@ pub struct SectionHeaderTable2 {
     addr: String,
 }
 
+/// Formats the compression of a section as type, uncompressed size and
+/// compressed/uncompressed ratio, or `-` if it is stored uncompressed.
+fn compression_info(ndx: usize, elf_file: &ElfFile) -> String {
+    let compression = match elf_file.get_section_compression(ndx) {
+        Some(compression) => compression,
+        None => return "-".to_string(),
+    };
+    let size = elf_file.section_headers[ndx].sh_size();
+    let ratio = if compression.uncompressed_size == 0 {
+        0.0
+    } else {
+        size as f64 * 100.0 / compression.uncompressed_size as f64
+    };
+    format!(
+        "{} {} ({:.1}%)",
+        compression.ch_type, compression.uncompressed_size, ratio
+    )
+}
 
 impl SectionHeaderTable {
     pub fn from_sh(ndx: usize, sh: &SectionHeader, elf_file: &ElfFile) -> Self {
         match sh {
             SectionHeader::Elf32(s) => {
-                let name = match elf_file.get_section_name(ndx) {
-                    Some(n) => n,
-                    _ => String::new(),
-                };
+                let name = elf_file.get_section_name(ndx).unwrap_or_default();
 
                 Self {
                     index: ndx,
-                    name: name,
-                    sh_type: sh.get_type().to_string(),
+                    name,
+                    sh_type: sh.get_type_name(elf_file.get_machine(), elf_file.get_osabi()),
                     flags: sh.get_flags().to_string(),
                     entsize: format!("{}", s.sh_entsize),
-                    size: format!("{}", s.sh_size)
-                    
+                    size: format!("{}", s.sh_size),
+                    compression: compression_info(ndx, elf_file),
                 }
             }
 
             SectionHeader::Elf64(s) => {
-                let name = match elf_file.get_section_name(ndx) {
-                    Some(n) => n,
-                    _ => String::new(),
-                };
+                let name = elf_file.get_section_name(ndx).unwrap_or_default();
 
                 Self {
                     index: ndx,
-                    name: name,
-                    sh_type: sh.get_type().to_string(),
+                    name,
+                    sh_type: sh.get_type_name(elf_file.get_machine(), elf_file.get_osabi()),
                     flags: sh.get_flags().to_string(),
                     entsize: format!("{}", s.sh_entsize),
-                    size: format!("{}", s.sh_size)
+                    size: format!("{}", s.sh_size),
+                    compression: compression_info(ndx, elf_file),
                 }
             }
         }
     }
 }
 
-
 impl SectionHeaderTable2 {
     pub fn from_sh(ndx: usize, sh: &SectionHeader, elf_file: &ElfFile) -> Self {
         match sh {
             SectionHeader::Elf32(s) => {
-                let name = match elf_file.get_section_name(ndx) {
-                    Some(n) => n,
-                    _ => String::new(),
-                };
+                let name = elf_file.get_section_name(ndx).unwrap_or_default();
 
                 Self {
                     index: ndx,
-                    name: name,
+                    name,
                     offset: format!("0x{:016X}", s.sh_offset),
                     link: s.sh_link,
                     info: s.sh_info,
@@ -261,14 +283,11 @@ impl SectionHeaderTable2 {
             }
 
             SectionHeader::Elf64(s) => {
-                let name = match elf_file.get_section_name(ndx) {
-                    Some(n) => n,
-                    _ => String::new(),
-                };
+                let name = elf_file.get_section_name(ndx).unwrap_or_default();
 
                 Self {
                     index: ndx,
-                    name: name,
+                    name,
                     offset: format!("0x{:016X}", s.sh_offset),
                     link: s.sh_link,
                     info: s.sh_info,
@@ -280,3 +299,519 @@ impl SectionHeaderTable2 {
     }
 }
 
+#[derive(Debug, Tabled)]
+pub struct AttributeTable {
+    #[tabled(rename = "Tag")]
+    tag: String,
+    #[tabled(rename = "Value")]
+    value: String,
+}
+
+impl AttributeTable {
+    pub fn from_attribute(vendor: &str, attr: &Attribute) -> Self {
+        Self {
+            tag: attr.tag_name(vendor),
+            value: attr.describe(vendor),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct Addr2LineTable {
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Function")]
+    function: String,
+    #[tabled(rename = "Location")]
+    location: String,
+}
+
+impl Addr2LineTable {
+    /// Builds one row per frame. Frames after the first are the callers an
+    /// inlined function was expanded into.
+    pub fn from_frames(address: u64, frames: &[Frame]) -> Vec<Self> {
+        frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| Self {
+                address: if i == 0 {
+                    format!("0x{:016X}", address)
+                } else {
+                    "(inlined by)".to_string()
+                },
+                function: frame.function.clone().unwrap_or_else(|| "??".to_string()),
+                location: match &frame.location {
+                    Some(loc) => format!("{}:{}", loc.file.as_deref().unwrap_or("??"), loc.line),
+                    None => "??:0".to_string(),
+                },
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct CompilationUnitTable {
+    #[tabled(rename = "Offset")]
+    offset: String,
+    #[tabled(rename = "Version")]
+    version: u16,
+    #[tabled(rename = "Unit Type")]
+    unit_type: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Language")]
+    language: String,
+    #[tabled(rename = "Producer")]
+    producer: String,
+    #[tabled(rename = "Comp Dir")]
+    comp_dir: String,
+}
+
+impl CompilationUnitTable {
+    pub fn from_unit(unit: &Unit) -> Self {
+        Self {
+            offset: format!("0x{:X}", unit.offset),
+            version: unit.encoding.version,
+            unit_type: unit.unit_type_name(),
+            name: unit.name.clone().unwrap_or_default(),
+            language: unit.language_name().unwrap_or_default(),
+            producer: unit.producer.clone().unwrap_or_default(),
+            comp_dir: unit.comp_dir.clone().unwrap_or_default(),
+        }
+    }
+}
+
+fn percent(part: u64, total: u64) -> String {
+    if total == 0 {
+        "-".to_string()
+    } else {
+        format!("{:.1}%", part as f64 * 100.0 / total as f64)
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct DebugSectionSizeTable {
+    #[tabled(rename = "Section")]
+    name: String,
+    #[tabled(rename = "Size")]
+    size: u64,
+    #[tabled(rename = "Uncompressed")]
+    uncompressed_size: u64,
+    #[tabled(rename = "Share")]
+    share: String,
+}
+
+impl DebugSectionSizeTable {
+    pub fn from_section(section: &SectionSize, total: u64) -> Self {
+        Self {
+            name: section.name.clone(),
+            size: section.size,
+            uncompressed_size: section.uncompressed_size,
+            share: percent(section.size, total),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct UnitSizeTable {
+    #[tabled(rename = "Offset")]
+    offset: String,
+    #[tabled(rename = "Unit")]
+    path: String,
+    #[tabled(rename = ".debug_info")]
+    info_size: u64,
+    #[tabled(rename = ".debug_line")]
+    line_size: u64,
+    #[tabled(rename = "Total")]
+    total: u64,
+    #[tabled(rename = "Share")]
+    share: String,
+}
+
+impl UnitSizeTable {
+    pub fn from_unit(unit: &UnitSize, total: u64) -> Self {
+        Self {
+            offset: format!("0x{:X}", unit.offset),
+            path: unit.path.clone(),
+            info_size: unit.info_size,
+            line_size: unit.line_size,
+            total: unit.total(),
+            share: percent(unit.total(), total),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct DirectorySizeTable {
+    #[tabled(rename = "Directory")]
+    directory: String,
+    #[tabled(rename = "Units")]
+    units: usize,
+    #[tabled(rename = ".debug_info")]
+    info_size: u64,
+    #[tabled(rename = ".debug_line")]
+    line_size: u64,
+    #[tabled(rename = "Total")]
+    total: u64,
+    #[tabled(rename = "Share")]
+    share: String,
+}
+
+impl DirectorySizeTable {
+    pub fn from_directory(dir: &DirectorySize, total: u64) -> Self {
+        Self {
+            directory: dir.directory.clone(),
+            units: dir.units,
+            info_size: dir.info_size,
+            line_size: dir.line_size,
+            total: dir.total(),
+            share: percent(dir.total(), total),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct ExidxTable {
+    #[tabled(rename = "Function")]
+    function: String,
+    #[tabled(rename = "Symbol")]
+    symbol: String,
+    #[tabled(rename = "Entry")]
+    entry: String,
+    #[tabled(rename = "Personality")]
+    personality: String,
+    #[tabled(rename = "Unwind Opcodes")]
+    opcodes: String,
+}
+
+impl ExidxTable {
+    pub fn from_entry(entry: &ExidxEntry) -> Self {
+        let (kind, description) = match &entry.unwind {
+            ExidxUnwind::CantUnwind => ("cantunwind".to_string(), None),
+            ExidxUnwind::Inline(description) => ("inline".to_string(), Some(description)),
+            ExidxUnwind::Table {
+                address,
+                description,
+            } => (format!("@0x{:x}", address), description.as_ref()),
+        };
+        let (personality, opcodes) = match description {
+            Some(description) => {
+                let mut lines = describe_opcodes(&description.opcodes);
+                if let Some(lsda) = description.lsda {
+                    lines.push(format!("LSDA @0x{:x}", lsda));
+                }
+                (description.personality.name(), lines.join("\n"))
+            }
+            None if matches!(entry.unwind, ExidxUnwind::CantUnwind) => {
+                ("-".to_string(), "-".to_string())
+            }
+            None => ("-".to_string(), "<unreadable>".to_string()),
+        };
+        Self {
+            function: format!("0x{:08x}", entry.function),
+            symbol: entry.symbol.clone().unwrap_or_else(|| "??".to_string()),
+            entry: kind,
+            personality,
+            opcodes,
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct FunctionTable {
+    #[tabled(rename = "Start")]
+    start: String,
+    #[tabled(rename = "End")]
+    end: String,
+    #[tabled(rename = "Size")]
+    size: u64,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Sources")]
+    sources: String,
+}
+
+impl FunctionTable {
+    pub fn from_function(function: &RecoveredFunction) -> Self {
+        Self {
+            start: format!("0x{:016X}", function.start),
+            end: format!("0x{:016X}", function.end),
+            size: function.size(),
+            name: function.name.clone(),
+            sources: function
+                .sources
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct RemovedSectionTable {
+    #[tabled(rename = "Index")]
+    index: usize,
+    #[tabled(rename = "Section")]
+    name: String,
+    #[tabled(rename = "Type")]
+    sh_type: String,
+    #[tabled(rename = "Size")]
+    size: u64,
+}
+
+impl RemovedSectionTable {
+    pub fn from_section(elf_file: &ElfFile, index: usize) -> Self {
+        let sh = &elf_file.section_headers[index];
+        Self {
+            index,
+            name: elf_file.get_section_name(index).unwrap_or_default(),
+            sh_type: sh.get_type_name(elf_file.get_machine(), elf_file.get_osabi()),
+            size: sh.sh_size(),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct ChunkTable {
+    #[tabled(rename = "Load Address")]
+    address: String,
+    #[tabled(rename = "End")]
+    end: String,
+    #[tabled(rename = "Size")]
+    size: usize,
+}
+
+impl ChunkTable {
+    pub fn from_chunk(chunk: &Chunk) -> Self {
+        Self {
+            address: format!("0x{:08X}", chunk.address),
+            end: chunk
+                .end()
+                .map_or_else(|| "-".to_string(), |end| format!("0x{:08X}", end)),
+            size: chunk.data.len(),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct RegionUsageTable {
+    #[tabled(rename = "Region")]
+    name: String,
+    #[tabled(rename = "Origin")]
+    origin: String,
+    #[tabled(rename = "Length")]
+    length: u64,
+    #[tabled(rename = "Used")]
+    used: u64,
+    #[tabled(rename = "Free")]
+    free: i128,
+    #[tabled(rename = "Usage")]
+    percent: String,
+}
+
+impl RegionUsageTable {
+    pub fn from_usage(usage: &RegionUsage) -> Self {
+        Self {
+            name: usage.region.name.clone(),
+            origin: format!("0x{:08X}", usage.region.origin),
+            length: usage.region.length,
+            used: usage.used,
+            free: usage.region.length as i128 - usage.used as i128,
+            percent: format!("{:.2}%", usage.percent()),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct SectionPlacementTable {
+    #[tabled(rename = "Section")]
+    name: String,
+    #[tabled(rename = "Size")]
+    size: u64,
+    #[tabled(rename = "VMA")]
+    vma: String,
+    #[tabled(rename = "VMA Region")]
+    vma_region: String,
+    #[tabled(rename = "LMA")]
+    lma: String,
+    #[tabled(rename = "LMA Region")]
+    lma_region: String,
+}
+
+impl SectionPlacementTable {
+    pub fn from_placement(
+        elf_file: &ElfFile,
+        placement: &SectionPlacement,
+        regions: &[MemoryRegion],
+    ) -> Self {
+        let region = |index: Option<usize>| match index {
+            Some(index) => regions[index].name.clone(),
+            None => "-".to_string(),
+        };
+        Self {
+            name: elf_file
+                .get_section_name(placement.index)
+                .unwrap_or_default(),
+            size: placement.size,
+            vma: format!("0x{:08X}", placement.vma),
+            vma_region: region(placement.vma_region),
+            lma: placement
+                .lma
+                .map_or("-".to_string(), |lma| format!("0x{:08X}", lma)),
+            lma_region: if placement.lma.is_some() {
+                region(placement.lma_region)
+            } else {
+                "-".to_string()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct BloatSymbolTable {
+    #[tabled(rename = "Symbol")]
+    name: String,
+    #[tabled(rename = "Section")]
+    section: String,
+    #[tabled(rename = "Size")]
+    size: u64,
+    #[tabled(rename = "% of .text")]
+    percent: String,
+    #[tabled(rename = "File")]
+    file: String,
+}
+
+impl BloatSymbolTable {
+    pub fn from_symbol(elf_file: &ElfFile, symbol: &BloatSymbol, text_size: Option<u64>) -> Self {
+        Self {
+            name: symbol.display_name().to_string(),
+            section: elf_file
+                .get_section_name(symbol.section)
+                .unwrap_or_default(),
+            size: symbol.size,
+            percent: percent(symbol.size, text_size.unwrap_or(0)),
+            file: symbol.file.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct BloatSectionTable {
+    #[tabled(rename = "Section")]
+    name: String,
+    #[tabled(rename = "Size")]
+    size: u64,
+    #[tabled(rename = "% of .text")]
+    percent: String,
+    #[tabled(rename = "In Symbols")]
+    symbol_size: u64,
+    #[tabled(rename = "Symbols")]
+    symbols: usize,
+}
+
+impl BloatSectionTable {
+    pub fn from_section(
+        elf_file: &ElfFile,
+        section: &BloatSection,
+        text_size: Option<u64>,
+    ) -> Self {
+        Self {
+            name: elf_file.get_section_name(section.index).unwrap_or_default(),
+            size: section.size,
+            percent: percent(section.size, text_size.unwrap_or(0)),
+            symbol_size: section.symbol_size,
+            symbols: section.symbols,
+        }
+    }
+}
+
+fn size_or_dash(size: Option<u64>) -> String {
+    size.map_or("-".to_string(), |size| size.to_string())
+}
+
+#[derive(Debug, Tabled)]
+pub struct SectionSizeChangeTable {
+    #[tabled(rename = "Section")]
+    name: String,
+    #[tabled(rename = "Old")]
+    old: String,
+    #[tabled(rename = "New")]
+    new: String,
+    #[tabled(rename = "Delta")]
+    delta: String,
+    #[tabled(rename = "Change")]
+    percent: String,
+}
+
+impl SectionSizeChangeTable {
+    pub fn from_change(change: &SizeChange) -> Self {
+        let percent = match change.old {
+            Some(old) if old > 0 => format!("{:+.1}%", change.delta() as f64 * 100.0 / old as f64),
+            _ => "-".to_string(),
+        };
+        Self {
+            name: change.name.clone(),
+            old: size_or_dash(change.old),
+            new: size_or_dash(change.new),
+            delta: format!("{:+}", change.delta()),
+            percent,
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct SymbolSizeChangeTable {
+    #[tabled(rename = "Symbol")]
+    name: String,
+    #[tabled(rename = "Change")]
+    kind: &'static str,
+    #[tabled(rename = "Old")]
+    old: String,
+    #[tabled(rename = "New")]
+    new: String,
+    #[tabled(rename = "Delta")]
+    delta: String,
+}
+
+impl SymbolSizeChangeTable {
+    pub fn from_change(change: &SizeChange) -> Self {
+        Self {
+            name: change.name.clone(),
+            kind: change.kind().name(),
+            old: size_or_dash(change.old),
+            new: size_or_dash(change.new),
+            delta: format!("{:+}", change.delta()),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+pub struct AbiChangeTable {
+    #[tabled(rename = "Impact")]
+    impact: &'static str,
+    #[tabled(rename = "Change")]
+    kind: &'static str,
+    #[tabled(rename = "Subject")]
+    subject: String,
+    #[tabled(rename = "Old")]
+    old: String,
+    #[tabled(rename = "New")]
+    new: String,
+}
+
+impl AbiChangeTable {
+    pub fn from_change(change: &AbiChange) -> Self {
+        Self {
+            impact: if change.kind.is_breaking() {
+                "breaking"
+            } else {
+                "compatible"
+            },
+            kind: change.kind.name(),
+            subject: change.subject.clone(),
+            old: change.old.clone().unwrap_or_else(|| "-".to_string()),
+            new: change.new.clone().unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
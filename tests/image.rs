@@ -0,0 +1,206 @@
+use elfread::elf::image::{self, Chunk, ihex, srec, uf2};
+
+/// Returns `len` bytes of a counting pattern.
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+/// Returns the record types of the data lines of S-record text.
+fn srec_kinds(text: &str) -> Vec<&str> {
+    text.lines().map(|line| &line[..2]).collect()
+}
+
+#[test]
+fn round_trip_ihex() {
+    let chunks = vec![
+        Chunk {
+            address: 0x100,
+            data: pattern(40),
+        },
+        Chunk {
+            address: 0x0800_0000,
+            data: pattern(300),
+        },
+    ];
+    let text = ihex::write(&chunks, Some(0x0800_0101)).expect("write");
+    let (parsed, entry) = ihex::parse(&text).expect("parse");
+    assert_eq!(parsed, chunks);
+    assert_eq!(entry, Some(0x0800_0101));
+}
+
+#[test]
+fn round_trip_ihex_across_64k_boundary() {
+    let chunks = vec![Chunk {
+        address: 0x1_FFF8,
+        data: pattern(32),
+    }];
+    let text = ihex::write(&chunks, None).expect("write");
+    // one extended linear address record per 64 KiB page
+    assert!(text.contains(":020000040001F9"));
+    assert!(text.contains(":020000040002F8"));
+    // no data record crosses the boundary
+    assert!(text.contains(":08FFF800"));
+    let (parsed, entry) = ihex::parse(&text).expect("parse");
+    assert_eq!(parsed, chunks);
+    assert_eq!(entry, None);
+}
+
+#[test]
+fn ihex_rejects_malformed_lines() {
+    assert!(ihex::parse("00000001FF\n").is_err());
+    assert!(ihex::parse(":00000001FE\n").is_err());
+    assert!(ihex::parse(":0000001FF\n").is_err());
+    assert!(ihex::parse(":02000000GG00FE\n").is_err());
+    assert!(ihex::parse(":0100000000\n").is_err());
+    assert!(ihex::parse(":\u{e9}00000001FF\n").is_err());
+    assert!(ihex::parse(":00000007F9\n").is_err());
+}
+
+#[test]
+fn round_trip_srec() {
+    let chunks = vec![
+        Chunk {
+            address: 0x10,
+            data: pattern(20),
+        },
+        Chunk {
+            address: 0x2000,
+            data: pattern(33),
+        },
+    ];
+    let text = srec::write(&chunks, "test", 0x10).expect("write");
+    let (parsed, entry) = srec::parse(&text).expect("parse");
+    assert_eq!(parsed, chunks);
+    assert_eq!(entry, Some(0x10));
+}
+
+#[test]
+fn srec_picks_record_type_by_highest_address() {
+    let cases = [
+        (0xFFF0, "S1", "S9"),
+        (0xFF_FFF0, "S2", "S8"),
+        (0xFFFF_FFF0, "S3", "S7"),
+    ];
+    for (address, data_kind, end_kind) in cases {
+        let chunks = vec![Chunk {
+            address,
+            data: pattern(16),
+        }];
+        let text = srec::write(&chunks, "", 0).expect("write");
+        assert_eq!(srec_kinds(&text), ["S0", data_kind, end_kind]);
+        let (parsed, entry) = srec::parse(&text).expect("parse");
+        assert_eq!(parsed, chunks);
+        assert_eq!(entry, Some(0));
+    }
+    // the entry point counts too
+    let text = srec::write(
+        &[Chunk {
+            address: 0,
+            data: pattern(4),
+        }],
+        "",
+        0x1_0000,
+    )
+    .expect("write");
+    assert_eq!(srec_kinds(&text), ["S0", "S2", "S8"]);
+
+    let beyond = [Chunk {
+        address: 0xFFFF_FFFF,
+        data: pattern(2),
+    }];
+    assert!(srec::write(&beyond, "", 0).is_err());
+}
+
+#[test]
+fn srec_rejects_malformed_lines() {
+    assert!(srec::parse("X1030000FC\n").is_err());
+    assert!(srec::parse("S\n").is_err());
+    assert!(srec::parse("S1030000FD\n").is_err());
+    assert!(srec::parse("S103000FC\n").is_err());
+    assert!(srec::parse("S1030000ZZ\n").is_err());
+    assert!(srec::parse("S1040000FC\n").is_err());
+    assert!(srec::parse("S4030000FC\n").is_err());
+    // a multi-byte character right after the 'S' must not panic
+    assert!(srec::parse("S\u{e9}000000\n").is_err());
+    assert!(srec::parse("S1\u{e9}0000\n").is_err());
+}
+
+#[test]
+fn round_trip_uf2() {
+    let chunks = vec![
+        Chunk {
+            address: 0x1000_0000,
+            data: pattern(512),
+        },
+        Chunk {
+            address: 0x1000_0400,
+            data: pattern(256),
+        },
+    ];
+    let options = uf2::Uf2Options {
+        family_id: uf2::family_id("RP2040"),
+        ..Default::default()
+    };
+    let data = uf2::write(&chunks, &options).expect("write");
+    assert_eq!(data.len(), 3 * uf2::BLOCK_SIZE);
+    let (parsed, family) = uf2::parse(&data).expect("parse");
+    assert_eq!(parsed, chunks);
+    assert_eq!(family, options.family_id);
+}
+
+#[test]
+fn uf2_pads_partial_pages() {
+    let chunks = vec![Chunk {
+        address: 0x2010,
+        data: pattern(8),
+    }];
+    let data = uf2::write(&chunks, &uf2::Uf2Options::default()).expect("write");
+    let (parsed, family) = uf2::parse(&data).expect("parse");
+    let mut page = vec![0; 256];
+    page[0x10..0x18].copy_from_slice(&chunks[0].data);
+    assert_eq!(
+        parsed,
+        [Chunk {
+            address: 0x2000,
+            data: page
+        }]
+    );
+    assert_eq!(family, None);
+}
+
+#[test]
+fn uf2_rejects_malformed_blocks() {
+    let chunks = vec![Chunk {
+        address: 0,
+        data: pattern(256),
+    }];
+    let data = uf2::write(&chunks, &uf2::Uf2Options::default()).expect("write");
+    assert!(uf2::parse(&data[..uf2::BLOCK_SIZE - 1]).is_err());
+
+    let mut bad_magic = data.clone();
+    bad_magic[0] ^= 1;
+    assert!(uf2::parse(&bad_magic).is_err());
+
+    let mut bad_size = data.clone();
+    bad_size[16..20].copy_from_slice(&(uf2::MAX_PAYLOAD + 1).to_le_bytes());
+    assert!(uf2::parse(&bad_size).is_err());
+
+    let options = uf2::Uf2Options {
+        flash: Some((0xFFFF_FFFF_FFFF_FFF0, 0x100)),
+        ..Default::default()
+    };
+    assert!(uf2::write(&chunks, &options).is_err());
+}
+
+#[test]
+fn chunks_past_the_address_space_are_rejected() {
+    let chunks = vec![Chunk {
+        address: 0xFFFF_FFFF_FFFF_FFF0,
+        data: pattern(0x40),
+    }];
+    assert_eq!(chunks[0].end(), None);
+    assert!(image::normalize(chunks.clone()).is_err());
+    assert!(ihex::write(&chunks, None).is_err());
+    assert!(srec::write(&chunks, "", 0).is_err());
+    assert!(uf2::write(&chunks, &uf2::Uf2Options::default()).is_err());
+}
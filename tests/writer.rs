@@ -0,0 +1,158 @@
+use elfread::elf::ElfFile;
+use elfread::elf::types::{SectionFlags, SectionType};
+use elfread::elf::writer::ElfWriter;
+
+/// Builds a big-endian ELF32 relocatable file with `.data` and
+/// `.shstrtab` sections.
+fn small_elf32_be() -> Vec<u8> {
+    let shstrtab = b"\0.data\0.shstrtab\0";
+    let data = b"hello\0";
+    let data_off = 52u32;
+    let shstrtab_off = data_off + data.len() as u32;
+    let shoff = (shstrtab_off + shstrtab.len() as u32).div_ceil(4) * 4;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"\x7FELF\x01\x02\x01");
+    out.resize(16, 0);
+    let half = |out: &mut Vec<u8>, v: u16| out.extend_from_slice(&v.to_be_bytes());
+    let word = |out: &mut Vec<u8>, v: u32| out.extend_from_slice(&v.to_be_bytes());
+    half(&mut out, 1); // ET_REL
+    half(&mut out, 8); // EM_MIPS
+    word(&mut out, 1);
+    word(&mut out, 0); // e_entry
+    word(&mut out, 0); // e_phoff
+    word(&mut out, shoff);
+    word(&mut out, 0); // e_flags
+    half(&mut out, 52);
+    half(&mut out, 32);
+    half(&mut out, 0);
+    half(&mut out, 40);
+    half(&mut out, 3);
+    half(&mut out, 2);
+
+    out.extend_from_slice(data);
+    out.extend_from_slice(shstrtab);
+    out.resize(shoff as usize, 0);
+    let sections: [[u32; 10]; 3] = [
+        [0; 10],
+        [1, 1, 3, 0, data_off, data.len() as u32, 0, 0, 1, 0],
+        [7, 3, 0, 0, shstrtab_off, shstrtab.len() as u32, 0, 0, 1, 0],
+    ];
+    for section in sections {
+        for field in section {
+            word(&mut out, field);
+        }
+    }
+    out
+}
+
+fn assert_round_trip(bytes: &[u8]) {
+    let elf = ElfFile::from_bytes(bytes).expect("parse");
+    let written = elf.to_bytes().expect("write");
+    assert!(written == bytes, "round trip changed the file");
+}
+
+#[test]
+fn round_trip_small_elf32_big_endian() {
+    assert_round_trip(&small_elf32_be());
+}
+
+#[test]
+fn round_trip_test_executable() {
+    let bytes = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    assert_round_trip(&bytes);
+}
+
+#[test]
+fn round_trip_system_files() {
+    for path in [
+        "/bin/sh",
+        "/bin/ls",
+        "/lib/x86_64-linux-gnu/libc.so.6",
+        "/lib/aarch64-linux-gnu/libc.so.6",
+    ] {
+        if let Ok(bytes) = std::fs::read(path)
+            && bytes.starts_with(b"\x7FELF")
+        {
+            assert_round_trip(&bytes);
+        }
+    }
+}
+
+#[test]
+fn rename_rebuilds_shstrtab() {
+    let elf = ElfFile::from_bytes(&small_elf32_be()).unwrap();
+    let mut writer = ElfWriter::new(&elf);
+    let index = writer.find_section(".data").unwrap();
+    writer.sections[index].name = ".rodata.renamed".to_string();
+    let written = ElfFile::from_bytes(&writer.write().unwrap()).unwrap();
+
+    assert_eq!(written.find_section(".rodata.renamed"), Some(index));
+    assert_eq!(written.find_section(".data"), None);
+    assert_eq!(written.get_section_data(index), Some(&b"hello\0"[..]));
+    assert_eq!(written.get_section_name(2).as_deref(), Some(".shstrtab"));
+}
+
+#[test]
+fn grown_section_moves_to_end() {
+    let bytes = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let elf = ElfFile::from_bytes(&bytes).unwrap();
+    let index = elf.find_section(".comment").expect(".comment");
+    let mut writer = ElfWriter::new(&elf);
+    writer.sections[index]
+        .data
+        .extend_from_slice(b"elfread writer test\0");
+    let written = ElfFile::from_bytes(&writer.write().unwrap()).unwrap();
+
+    let contents = written.get_section_data(index).unwrap();
+    assert!(contents.ends_with(b"elfread writer test\0"));
+    assert!(written.section_headers[index].sh_offset() >= bytes.len() as u64);
+    for i in 0..elf.section_headers.len() {
+        assert_eq!(written.get_section_name(i), elf.get_section_name(i));
+        if i != index {
+            assert_eq!(written.get_raw_section_data(i), elf.get_raw_section_data(i));
+        }
+    }
+    assert_eq!(written.program_headers.len(), elf.program_headers.len());
+}
+
+#[test]
+fn grown_allocated_section_is_rejected() {
+    let bytes = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let elf = ElfFile::from_bytes(&bytes).unwrap();
+    let index = (1..elf.section_headers.len())
+        .find(|&i| {
+            let sh = &elf.section_headers[i];
+            sh.get_flags().contains(SectionFlags::ALLOC) && sh.get_type() == SectionType::Progbits
+        })
+        .unwrap();
+    let mut writer = ElfWriter::new(&elf);
+    writer.sections[index].data.push(0);
+    assert!(writer.write().is_err());
+}
+
+#[test]
+fn add_and_remove_sections() {
+    let elf = ElfFile::from_bytes(&small_elf32_be()).unwrap();
+    let mut writer = ElfWriter::new(&elf);
+    let mut header = writer.new_section_header(SectionType::Note);
+    header.set_sh_addralign(4);
+    let added = writer.add_section(".note.test", header, vec![1, 2, 3, 4]);
+    writer.remove_section(1);
+    let written = ElfFile::from_bytes(&writer.write().unwrap()).unwrap();
+
+    assert_eq!(written.section_headers.len(), 3);
+    assert_eq!(written.find_section(".data"), None);
+    assert_eq!(written.find_section(".note.test"), Some(added - 1));
+    assert_eq!(
+        written.get_section_data(added - 1),
+        Some(&[1u8, 2, 3, 4][..])
+    );
+    assert_eq!(written.section_headers[added - 1].sh_offset() % 4, 0);
+    assert_eq!(
+        written
+            .get_section_name(written.e_shstrndx as usize)
+            .as_deref(),
+        Some(".shstrtab")
+    );
+}